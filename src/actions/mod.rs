@@ -82,6 +82,61 @@ pub fn allowlisted_run_cmd(action: &ActionPlan) -> Option<AllowlistedRunCmdSpec>
         });
     }
 
+    if let Some(formula) = action.id.strip_prefix("homebrew-old-kegs-cleanup:") {
+        if action.risk_level == RiskLevel::R1
+            && cmd == "brew"
+            && is_safe_brew_formula_name(formula)
+            && args.iter().map(String::as_str).eq(["cleanup", formula])
+        {
+            return Some(AllowlistedRunCmdSpec {
+                confirm_token: "cleanup",
+                final_confirm_token: "run",
+            });
+        }
+        return None;
+    }
+
+    if action.id == "homebrew-autoremove"
+        && action.risk_level == RiskLevel::R2
+        && cmd == "brew"
+        && args.iter().map(String::as_str).eq(["autoremove"])
+    {
+        return Some(AllowlistedRunCmdSpec {
+            confirm_token: "autoremove",
+            final_confirm_token: "run",
+        });
+    }
+
+    if let Some(rest) = action.id.strip_prefix("lang-version-uninstall:") {
+        let mut parts = rest.splitn(3, ':');
+        let (Some(manager), Some(tool), Some(version)) = (parts.next(), parts.next(), parts.next())
+        else {
+            return None;
+        };
+        if action.risk_level != RiskLevel::R2
+            || cmd != manager
+            || !is_safe_version_token(tool)
+            || !is_safe_version_token(version)
+        {
+            return None;
+        }
+        let mise_arg = format!("{tool}@{version}");
+        let expected: Vec<&str> = match manager {
+            "pyenv" if tool == "python" => vec!["uninstall", "-f", version],
+            "rbenv" if tool == "ruby" => vec!["uninstall", "-f", version],
+            "asdf" => vec!["uninstall", tool, version],
+            "mise" => vec!["uninstall", mise_arg.as_str()],
+            _ => return None,
+        };
+        if args.iter().map(String::as_str).eq(expected) {
+            return Some(AllowlistedRunCmdSpec {
+                confirm_token: "uninstall",
+                final_confirm_token: "run",
+            });
+        }
+        return None;
+    }
+
     if action.id == "npm-cache-cleanup"
         && action.risk_level == RiskLevel::R1
         && cmd == "npm"
@@ -192,7 +247,7 @@ pub fn evaluate_allowlisted_run_cmd_output(
                 output.exit_code
             ))
         }
-        "homebrew-cache-cleanup" => {
+        id if is_brew_cleanup_action_id(id) => {
             if output.exit_code == 0 {
                 return AllowlistedRunCmdOutcome::Ok;
             }
@@ -292,6 +347,26 @@ pub fn suggest_allowlisted_run_cmd_repair_action(
         .next()
 }
 
+fn is_brew_cleanup_action_id(id: &str) -> bool {
+    id == "homebrew-cache-cleanup" || id.starts_with("homebrew-old-kegs-cleanup:")
+}
+
+fn is_safe_brew_formula_name(name: &str) -> bool {
+    if name.is_empty() || name.starts_with('-') || name.starts_with('.') {
+        return false;
+    }
+    name.chars()
+        .all(|c| c.is_ascii_alphanumeric() || matches!(c, '@' | '+' | '_' | '-' | '.'))
+}
+
+fn is_safe_version_token(s: &str) -> bool {
+    if s.is_empty() || s.starts_with('-') || s.starts_with('.') {
+        return false;
+    }
+    s.chars()
+        .all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '_' | '-' | '.'))
+}
+
 fn brew_output_has_hard_error(output: &CommandOutput) -> bool {
     for s in [&output.stdout, &output.stderr] {
         if s.contains("Error:") {
@@ -350,7 +425,7 @@ pub fn suggest_allowlisted_run_cmd_repair_actions(
     action: &ActionPlan,
     output: &CommandOutput,
 ) -> Vec<ActionPlan> {
-    if action.id != "homebrew-cache-cleanup" {
+    if !is_brew_cleanup_action_id(&action.id) {
         return vec![];
     }
     if output.exit_code == 0 {
@@ -490,7 +565,13 @@ pub fn run_allowlisted_cmd(
 
     let args_ref: Vec<&str> = args.iter().map(|s| s.as_str()).collect();
     match action.id.as_str() {
+        id if id.starts_with("homebrew-old-kegs-cleanup:")
+            || id.starts_with("lang-version-uninstall:") =>
+        {
+            crate::platform::run_command_invoking_user(cmd, &args_ref, timeout)
+        }
         "homebrew-cache-cleanup"
+        | "homebrew-autoremove"
         | "coresimulator-simctl-delete-unavailable"
         | "docker-builder-prune"
         | "docker-storage-df"
@@ -565,6 +646,250 @@ pub fn apply_trash_moves(actions: &[ActionPlan], home_dir: &Path) -> Result<Appl
     })
 }
 
+#[derive(Debug, Clone)]
+pub struct RestoreOutcome {
+    pub restored: Vec<TrashMoveRecord>,
+    pub skipped_missing: Vec<PathBuf>,
+    pub conflicts: Vec<PathBuf>,
+    pub errors: Vec<TrashMoveError>,
+}
+
+// 途中の要素がシンボリックリンクだと、home 配下に見えるパスでも実体は外になる。
+// 存在する最も深い祖先を実体のパスに解決し、home（解決済み）の配下でゴミ箱の外にあるかを確かめる。
+fn resolves_under_home(path: &Path, home_dir: &Path) -> bool {
+    let real_home = home_dir
+        .canonicalize()
+        .unwrap_or_else(|_| home_dir.to_path_buf());
+    let Some(existing) = path
+        .ancestors()
+        .find(|a| std::fs::symlink_metadata(a).is_ok())
+    else {
+        return false;
+    };
+    match existing.canonicalize() {
+        Ok(real) => real.starts_with(&real_home) && !real.starts_with(real_home.join(".Trash")),
+        Err(_) => false,
+    }
+}
+
+// fix --apply で ~/.Trash へ移動したものを元の場所へ戻す（`moved` は fix のログと同じ向き）。
+// 元の場所に何かあれば上書きせず conflicts に入れる。戻した記録は from がゴミ箱側になる。
+pub fn restore_from_trash(moved: &[TrashMoveRecord], home_dir: &Path) -> RestoreOutcome {
+    let trash_dir = home_dir.join(".Trash");
+    let mut restored = Vec::new();
+    let mut skipped_missing = Vec::new();
+    let mut conflicts = Vec::new();
+    let mut errors = Vec::new();
+
+    for record in moved {
+        let (original, in_trash) = (&record.from, &record.to);
+        if crate::privacy::is_redacted_path(&original.to_string_lossy())
+            || crate::privacy::is_redacted_path(&in_trash.to_string_lossy())
+        {
+            errors.push(TrashMoveError {
+                path: original.clone(),
+                error: "パスが伏せられているため復元できません（privacy.redaction = \"strict\" のログ）"
+                    .to_string(),
+            });
+            continue;
+        }
+        // `starts_with` は要素単位の比較なので、`..` を含むパスは ~/.Trash や home の外を指せてしまう。
+        let has_parent_dir = |p: &Path| {
+            p.components()
+                .any(|c| matches!(c, std::path::Component::ParentDir))
+        };
+        if has_parent_dir(original) || has_parent_dir(in_trash) {
+            errors.push(TrashMoveError {
+                path: original.clone(),
+                error: format!(
+                    "`..` を含むパスは戻せません: {} -> {}",
+                    in_trash.display(),
+                    original.display()
+                ),
+            });
+            continue;
+        }
+        // fix --apply は ~/.Trash の直下へ移動するので、それ以外の場所は戻す対象にしない。
+        if in_trash.parent() != Some(trash_dir.as_path()) {
+            errors.push(TrashMoveError {
+                path: original.clone(),
+                error: format!(
+                    "~/.Trash の直下以外からは戻せません: {}",
+                    in_trash.display()
+                ),
+            });
+            continue;
+        }
+        if !original.starts_with(home_dir)
+            || original == home_dir
+            || original.starts_with(&trash_dir)
+        {
+            errors.push(TrashMoveError {
+                path: original.clone(),
+                error: "ホーム配下以外（またはゴミ箱内）へは戻せません".to_string(),
+            });
+            continue;
+        }
+        if std::fs::symlink_metadata(in_trash).is_err() {
+            skipped_missing.push(in_trash.clone());
+            continue;
+        }
+        if std::fs::symlink_metadata(original).is_ok() {
+            conflicts.push(original.clone());
+            continue;
+        }
+        if !resolves_under_home(original, home_dir) {
+            errors.push(TrashMoveError {
+                path: original.clone(),
+                error: format!(
+                    "シンボリックリンクを辿るとホーム配下以外（またはゴミ箱内）になるため戻せません: {}",
+                    original.display()
+                ),
+            });
+            continue;
+        }
+
+        let res = match original.parent() {
+            Some(parent) => {
+                std::fs::create_dir_all(parent).and_then(|()| rename_no_replace(in_trash, original))
+            }
+            None => rename_no_replace(in_trash, original),
+        };
+        match res {
+            Ok(()) => restored.push(TrashMoveRecord {
+                from: in_trash.clone(),
+                to: original.clone(),
+            }),
+            Err(err) if err.kind() == std::io::ErrorKind::AlreadyExists => {
+                conflicts.push(original.clone());
+            }
+            Err(err) => errors.push(TrashMoveError {
+                path: original.clone(),
+                error: format!(
+                    "元の場所へ戻せませんでした: {} -> {}: {err}",
+                    in_trash.display(),
+                    original.display()
+                ),
+            }),
+        }
+    }
+
+    RestoreOutcome {
+        restored,
+        skipped_missing,
+        conflicts,
+        errors,
+    }
+}
+
+// 移動先に何もないときだけ rename する。確認から rename までの間に別のプロセスが作ったものを
+// 上書きしないよう、macOS は renamex_np(RENAME_EXCL)、Linux（glibc）は renameat2(RENAME_NOREPLACE)
+// を使う。どちらも使えない環境（対応していないファイルシステムを含む）では直前に確認してから
+// rename するため、その間に作られたものは上書きされうる。
+fn rename_no_replace(from: &Path, to: &Path) -> std::io::Result<()> {
+    #[cfg(any(target_os = "macos", all(target_os = "linux", target_env = "gnu")))]
+    {
+        use std::ffi::CString;
+        use std::os::unix::ffi::OsStrExt;
+
+        let c_path = |p: &Path| {
+            CString::new(p.as_os_str().as_bytes())
+                .map_err(|_| std::io::Error::from(std::io::ErrorKind::InvalidInput))
+        };
+        let (from_c, to_c) = (c_path(from)?, c_path(to)?);
+        #[cfg(target_os = "macos")]
+        let rc = unsafe { libc::renamex_np(from_c.as_ptr(), to_c.as_ptr(), libc::RENAME_EXCL) };
+        #[cfg(target_os = "linux")]
+        let rc = unsafe {
+            libc::renameat2(
+                libc::AT_FDCWD,
+                from_c.as_ptr(),
+                libc::AT_FDCWD,
+                to_c.as_ptr(),
+                libc::RENAME_NOREPLACE,
+            )
+        };
+        if rc == 0 {
+            return Ok(());
+        }
+        let err = std::io::Error::last_os_error();
+        if !matches!(
+            err.raw_os_error(),
+            Some(libc::EINVAL | libc::ENOSYS | libc::ENOTSUP)
+        ) {
+            return Err(err);
+        }
+    }
+    if std::fs::symlink_metadata(to).is_ok() {
+        return Err(std::io::Error::from(std::io::ErrorKind::AlreadyExists));
+    }
+    std::fs::rename(from, to)
+}
+
+const DRIFT_MIN_BYTES: u64 = 1024 * 1024;
+const DRIFT_PERCENT: u64 = 25;
+
+// 保存済みレポート（fix --from-report）の生成後に TRASH_MOVE の対象が大きく変わっていないかを調べる。
+// 更新日時が生成時刻より新しい、またはサイズが推定から 25% かつ 1 MiB を超えてずれていれば理由を返す。
+pub fn target_drift(
+    action: &ActionPlan,
+    home_dir: &Path,
+    generated_at: std::time::SystemTime,
+    timeout: Duration,
+    sizer: crate::scan::Sizer,
+) -> Option<String> {
+    let ActionKind::TrashMove { paths } = &action.kind else {
+        return None;
+    };
+    let mut current = 0u64;
+    for path in paths {
+        let expanded = expand_tilde(path, home_dir);
+        let Ok(meta) = std::fs::symlink_metadata(&expanded) else {
+            continue;
+        };
+        if meta.modified().is_ok_and(|m| m > generated_at) {
+            return Some(format!("レポート生成後に更新されています: {path}"));
+        }
+        current += if meta.is_dir() {
+            match crate::scan::estimate_dir_size(&expanded, timeout, None, None, sizer) {
+                Ok(estimate) => estimate.bytes,
+                Err(err) => return Some(format!("サイズを確認できません: {path}: {err}")),
+            }
+        } else {
+            meta.len()
+        };
+    }
+    let expected = action.estimated_reclaimed_bytes;
+    let diff = current.abs_diff(expected);
+    if diff > DRIFT_MIN_BYTES && diff.saturating_mul(100) > expected.saturating_mul(DRIFT_PERCENT) {
+        return Some(format!(
+            "サイズがレポート生成時から大きく変わっています（レポート: {expected} bytes、現在: {current} bytes）"
+        ));
+    }
+    None
+}
+
+// 保存済みレポートのアクションを現在の診断結果と突き合わせる。リスクはファイルの値を使わず、
+// 同じ id の現在のアクションから取り直す。現在の結果に無い、または内容が違えば理由を返す。
+pub fn current_risk(
+    action: &ActionPlan,
+    current: &[ActionPlan],
+) -> std::result::Result<RiskLevel, String> {
+    let Some(now) = current.iter().find(|c| c.id == action.id) else {
+        return Err("現在の診断結果に同じアクションがありません".to_string());
+    };
+    let same = match (&action.kind, &now.kind) {
+        (ActionKind::TrashMove { paths }, ActionKind::TrashMove { paths: now_paths }) => {
+            paths.iter().all(|p| now_paths.contains(p))
+        }
+        (kind, now_kind) => kind == now_kind,
+    };
+    if !same {
+        return Err("現在の診断結果と内容が一致しません".to_string());
+    }
+    Ok(now.risk_level)
+}
+
 fn validate_action(action: &ActionPlan, home_dir: &Path) -> Result<()> {
     match &action.kind {
         ActionKind::TrashMove { paths } => {
@@ -595,16 +920,41 @@ fn validate_trash_target(path: &str, home_dir: &Path) -> Result<PathBuf> {
     if !expanded.starts_with(home_dir) {
         return Err(anyhow!("パスは home 配下である必要があります: {path}"));
     }
+    // `starts_with` は要素単位の比較なので、`~/.cargo/registry/cache/../../../Documents` のような
+    // `..` を含むパスは許可リストの接頭辞を満たしてしまう。home 以下は通常の要素だけを許す。
+    let has_special_component = expanded
+        .strip_prefix(home_dir)
+        .map(|rest| {
+            rest.components()
+                .any(|c| !matches!(c, std::path::Component::Normal(_)))
+        })
+        .unwrap_or(true);
+    if has_special_component {
+        return Err(anyhow!(
+            "`..` などを含むパスは拒否します（正規化したパスを指定してください）: {path}"
+        ));
+    }
 
-    let allowed = allowed_trash_targets(home_dir);
-    if !allowed.iter().any(|p| p == &expanded) {
-        let prefixes = allowed_trash_target_prefixes(home_dir);
-        let allowed_by_prefix = prefixes
-            .iter()
-            .any(|p| expanded.starts_with(p) && &expanded != p);
-        if !allowed_by_prefix {
+    if !is_allowed_trash_path(&expanded, home_dir) {
+        return Err(anyhow!(
+            "TRASH_MOVE の許可リストに含まれていないパスです: {path}"
+        ));
+    }
+
+    // 途中の要素がシンボリックリンクだと、許可リスト上のパスでも実体は別の場所になる。
+    // 親ディレクトリを実体のパスに解決し、同じ許可リスト（home も解決済み）で再確認する。
+    // 対象そのものはリンクを辿らずに移動するため、解決するのは親だけでよい。
+    if let (Some(parent), Some(name)) = (expanded.parent(), expanded.file_name())
+        && let Ok(real_parent) = parent.canonicalize()
+    {
+        let real_home = home_dir
+            .canonicalize()
+            .unwrap_or_else(|_| home_dir.to_path_buf());
+        let real = real_parent.join(name);
+        if !real.starts_with(&real_home) || !is_allowed_trash_path(&real, &real_home) {
             return Err(anyhow!(
-                "TRASH_MOVE の許可リストに含まれていないパスです: {path}"
+                "シンボリックリンクを辿ると許可リスト外になるパスです: {path} -> {}",
+                real.display()
             ));
         }
     }
@@ -612,13 +962,26 @@ fn validate_trash_target(path: &str, home_dir: &Path) -> Result<PathBuf> {
     Ok(expanded)
 }
 
+fn is_allowed_trash_path(path: &Path, home_dir: &Path) -> bool {
+    if allowed_trash_targets(home_dir).iter().any(|p| p == path) {
+        return true;
+    }
+    let allowed_by_prefix = allowed_trash_target_prefixes(home_dir)
+        .iter()
+        .any(|p| path.starts_with(p) && path != p);
+    allowed_by_prefix || is_allowed_trash_file(path, home_dir)
+}
+
 fn allowed_trash_targets(home_dir: &Path) -> Vec<PathBuf> {
     vec![
         home_dir.join("Library/Developer/Xcode/DerivedData"),
         home_dir.join("Library/Developer/Shared/Documentation/DocSets"),
         home_dir.join("Library/Developer/Xcode/iOS Device Logs"),
+        home_dir.join("Library/Developer/XCTestDevices"),
+        home_dir.join("Library/Developer/Xcode/UserData/Previews"),
+        home_dir.join("Library/Caches/com.apple.dt.Xcode"),
         home_dir.join("Library/Caches/Homebrew"),
-        home_dir.join(".cargo/registry"),
+        home_dir.join(".cargo/registry/src"),
         home_dir.join(".cargo/git"),
         home_dir.join(".gradle/caches"),
         home_dir.join(".npm"),
@@ -633,9 +996,69 @@ fn allowed_trash_target_prefixes(home_dir: &Path) -> Vec<PathBuf> {
         home_dir.join("Library/Developer/Xcode/Archives"),
         home_dir.join("Library/Developer/Xcode/iOS DeviceSupport"),
         home_dir.join("Library/Developer/CoreSimulator/Devices"),
+        home_dir.join(".cargo/registry/src"),
+        home_dir.join(".cargo/registry/cache"),
+        home_dir.join(".nvm/versions/node"),
     ]
 }
 
+fn allowed_trash_file_dirs(home_dir: &Path) -> Vec<PathBuf> {
+    vec![
+        home_dir.join("Downloads"),
+        home_dir.join("Desktop"),
+        home_dir.join("Library/iTunes/iPhone Software Updates"),
+        home_dir.join("Library/iTunes/iPad Software Updates"),
+    ]
+}
+
+pub fn installer_leftover_kind(name: &str) -> Option<&'static str> {
+    if name.starts_with("Install macOS") && name.ends_with(".app") {
+        return Some("Install macOS");
+    }
+    let (_, ext) = name.rsplit_once('.')?;
+    match ext.to_ascii_lowercase().as_str() {
+        "dmg" => Some("dmg"),
+        "pkg" => Some("pkg"),
+        "xip" => Some("xip"),
+        "ipsw" => Some("ipsw"),
+        _ => None,
+    }
+}
+
+// ファイル単位の許可: インストーラ類（.dmg/.pkg/.xip/.ipsw/Install macOS*.app）に限り、
+// home 直下・~/Applications・ダウンロード系ディレクトリ配下のみ許可する。
+fn is_allowed_trash_file(expanded: &Path, home_dir: &Path) -> bool {
+    if expanded
+        .components()
+        .any(|c| matches!(c, std::path::Component::ParentDir))
+    {
+        return false;
+    }
+    let Some(name) = expanded.file_name().and_then(|s| s.to_str()) else {
+        return false;
+    };
+    let Some(kind) = installer_leftover_kind(name) else {
+        return false;
+    };
+    let Some(parent) = expanded.parent() else {
+        return false;
+    };
+    let in_allowed_dir = parent == home_dir
+        || parent == home_dir.join("Applications")
+        || allowed_trash_file_dirs(home_dir)
+            .iter()
+            .any(|d| parent.starts_with(d));
+    if !in_allowed_dir {
+        return false;
+    }
+
+    match std::fs::symlink_metadata(expanded) {
+        Ok(meta) if meta.file_type().is_symlink() => false,
+        Ok(meta) if meta.is_dir() => matches!(kind, "Install macOS" | "pkg"),
+        _ => true,
+    }
+}
+
 fn expand_tilde(path: &str, home_dir: &Path) -> PathBuf {
     let path = path.trim();
     if path == "~" {
@@ -729,6 +1152,27 @@ mod tests {
         validate_actions(&[action], &home).expect("should validate");
     }
 
+    #[test]
+    fn validate_trash_move_allows_xctest_devices_previews_and_xcode_caches() {
+        let home = PathBuf::from("/Users/test");
+        let action = ActionPlan {
+            id: "a".to_string(),
+            title: "t".to_string(),
+            risk_level: RiskLevel::R2,
+            estimated_reclaimed_bytes: 0,
+            related_findings: vec![],
+            kind: ActionKind::TrashMove {
+                paths: vec![
+                    "~/Library/Developer/XCTestDevices".to_string(),
+                    "~/Library/Developer/Xcode/UserData/Previews".to_string(),
+                    "~/Library/Caches/com.apple.dt.Xcode".to_string(),
+                ],
+            },
+            notes: vec![],
+        };
+        validate_actions(&[action], &home).expect("should validate");
+    }
+
     #[test]
     fn validate_trash_move_allows_xcode_archives_subdir() {
         let home = PathBuf::from("/Users/test");
@@ -765,6 +1209,155 @@ mod tests {
         assert!(validate_actions(&[action], &home).is_err());
     }
 
+    #[test]
+    fn validate_trash_move_allows_cargo_registry_src_and_crate_files() {
+        let home = PathBuf::from("/Users/test");
+        let action = ActionPlan {
+            id: "a".to_string(),
+            title: "t".to_string(),
+            risk_level: RiskLevel::R1,
+            estimated_reclaimed_bytes: 0,
+            related_findings: vec![],
+            kind: ActionKind::TrashMove {
+                paths: vec![
+                    "~/.cargo/registry/src".to_string(),
+                    "~/.cargo/registry/src/index.crates.io-6f17d22bba15001f/serde-1.0.0"
+                        .to_string(),
+                    "~/.cargo/registry/cache/index.crates.io-6f17d22bba15001f/serde-1.0.0.crate"
+                        .to_string(),
+                ],
+            },
+            notes: vec![],
+        };
+        validate_actions(&[action], &home).expect("should validate");
+    }
+
+    #[test]
+    fn validate_trash_move_allows_installer_files_in_download_locations_only() {
+        let home = PathBuf::from("/Users/test");
+        let action = |path: &str| ActionPlan {
+            id: "a".to_string(),
+            title: "t".to_string(),
+            risk_level: RiskLevel::R1,
+            estimated_reclaimed_bytes: 0,
+            related_findings: vec![],
+            kind: ActionKind::TrashMove {
+                paths: vec![path.to_string()],
+            },
+            notes: vec![],
+        };
+
+        for path in [
+            "~/Downloads/Xcode_16.xip",
+            "~/Downloads/old/Tool.DMG",
+            "~/Desktop/Setup.pkg",
+            "~/Library/iTunes/iPhone Software Updates/iPhone.ipsw",
+            "~/Install macOS Sonoma.dmg",
+            "~/Applications/Install macOS Sonoma.app",
+        ] {
+            validate_actions(&[action(path)], &home)
+                .unwrap_or_else(|e| panic!("{path} should validate: {e}"));
+        }
+        for path in [
+            "~/Downloads",
+            "~/Downloads/notes.txt",
+            "~/Documents/Setup.pkg",
+            "~/Library/Caches/Setup.dmg",
+            "~/Downloads/../Documents/Setup.pkg",
+        ] {
+            assert!(
+                validate_actions(&[action(path)], &home).is_err(),
+                "{path} should be rejected"
+            );
+        }
+    }
+
+    #[test]
+    fn validate_trash_move_blocks_cargo_registry_cache_and_index_roots() {
+        let home = PathBuf::from("/Users/test");
+        for path in [
+            "~/.cargo/registry",
+            "~/.cargo/registry/cache",
+            "~/.cargo/registry/index",
+            "~/.cargo/registry/index/index.crates.io-6f17d22bba15001f",
+        ] {
+            let action = ActionPlan {
+                id: "a".to_string(),
+                title: "t".to_string(),
+                risk_level: RiskLevel::R1,
+                estimated_reclaimed_bytes: 0,
+                related_findings: vec![],
+                kind: ActionKind::TrashMove {
+                    paths: vec![path.to_string()],
+                },
+                notes: vec![],
+            };
+            assert!(validate_actions(&[action], &home).is_err(), "path={path}");
+        }
+    }
+
+    #[test]
+    fn validate_trash_move_blocks_parent_dir_components_under_allowed_prefixes() {
+        let home = PathBuf::from("/Users/test");
+        for path in [
+            "~/.cargo/registry/cache/../../../Documents",
+            "~/.nvm/versions/node/../../../x",
+            "/Users/test/.npm/..",
+        ] {
+            let action = ActionPlan {
+                id: "a".to_string(),
+                title: "t".to_string(),
+                risk_level: RiskLevel::R1,
+                estimated_reclaimed_bytes: 0,
+                related_findings: vec![],
+                kind: ActionKind::TrashMove {
+                    paths: vec![path.to_string()],
+                },
+                notes: vec![],
+            };
+            assert!(
+                validate_actions(&[action], &home).is_err(),
+                "should reject {path}"
+            );
+        }
+    }
+
+    #[test]
+    fn validate_trash_move_blocks_symlinked_parents_escaping_the_allowlist() {
+        static SEQ: AtomicU64 = AtomicU64::new(0);
+
+        let seq = SEQ.fetch_add(1, Ordering::Relaxed);
+        let home = std::env::temp_dir().join(format!(
+            "macdiet-symlink-escape-test-{}-{seq}",
+            std::process::id()
+        ));
+        let _ = std::fs::remove_dir_all(&home);
+        std::fs::create_dir_all(home.join("Documents/important")).expect("create documents");
+        std::fs::create_dir_all(home.join(".cargo/registry")).expect("create registry");
+        std::fs::create_dir_all(home.join(".cargo/registry/src/index")).expect("create src");
+        std::os::unix::fs::symlink(home.join("Documents"), home.join(".cargo/registry/cache"))
+            .expect("symlink");
+
+        let trash = |path: &str| ActionPlan {
+            id: "a".to_string(),
+            title: "t".to_string(),
+            risk_level: RiskLevel::R1,
+            estimated_reclaimed_bytes: 0,
+            related_findings: vec![],
+            kind: ActionKind::TrashMove {
+                paths: vec![path.to_string()],
+            },
+            notes: vec![],
+        };
+        let err = validate_actions(&[trash("~/.cargo/registry/cache/important")], &home)
+            .expect_err("symlinked parent must be rejected");
+        assert!(err.to_string().contains("シンボリックリンク"), "err={err}");
+        assert!(validate_actions(&[trash("~/.cargo/registry")], &home).is_err());
+        assert!(validate_actions(&[trash("~/.cargo/registry/src/index")], &home).is_ok());
+
+        let _ = std::fs::remove_dir_all(&home);
+    }
+
     #[test]
     fn validate_trash_move_blocks_outside_home() {
         let home = PathBuf::from("/Users/test");
@@ -799,6 +1392,50 @@ mod tests {
         assert!(validate_actions(&[action], &home).is_err());
     }
 
+    #[test]
+    fn target_drift_flags_newer_mtime_and_large_size_changes() {
+        static HOME_SEQ: AtomicU64 = AtomicU64::new(0);
+
+        let seq = HOME_SEQ.fetch_add(1, Ordering::Relaxed);
+        let home =
+            std::env::temp_dir().join(format!("macdiet-drift-test-{}-{seq}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&home);
+        let dir = home.join(".npm");
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("big.bin"), vec![0u8; 2 * 1024 * 1024]).unwrap();
+
+        let mut action = ActionPlan {
+            id: "npm-cache-trash".to_string(),
+            title: "t".to_string(),
+            risk_level: RiskLevel::R1,
+            estimated_reclaimed_bytes: 2 * 1024 * 1024,
+            related_findings: vec![],
+            kind: ActionKind::TrashMove {
+                paths: vec!["~/.npm".to_string(), "~/.missing".to_string()],
+            },
+            notes: vec![],
+        };
+        let later = std::time::SystemTime::now() + Duration::from_secs(3600);
+        let timeout = Duration::from_secs(5);
+        let sizer = crate::scan::Sizer::Native;
+        assert_eq!(target_drift(&action, &home, later, timeout, sizer), None);
+
+        let reason = target_drift(&action, &home, std::time::UNIX_EPOCH, timeout, sizer);
+        assert!(reason.is_some_and(|r| r.contains("更新されています")));
+
+        action.estimated_reclaimed_bytes = 10;
+        let reason = target_drift(&action, &home, later, timeout, sizer);
+        assert!(reason.is_some_and(|r| r.contains("サイズ")));
+
+        action.kind = ActionKind::RunCmd {
+            cmd: "npm".to_string(),
+            args: vec![],
+        };
+        assert_eq!(target_drift(&action, &home, later, timeout, sizer), None);
+
+        let _ = std::fs::remove_dir_all(&home);
+    }
+
     #[test]
     fn apply_trash_moves_moves_directory_into_trash() {
         static HOME_SEQ: AtomicU64 = AtomicU64::new(0);
@@ -835,6 +1472,107 @@ mod tests {
         let _ = std::fs::remove_dir_all(&home);
     }
 
+    #[test]
+    fn restore_from_trash_moves_back_and_refuses_occupied_or_redacted_paths() {
+        static HOME_SEQ: AtomicU64 = AtomicU64::new(0);
+
+        let seq = HOME_SEQ.fetch_add(1, Ordering::Relaxed);
+        let home =
+            std::env::temp_dir().join(format!("macdiet-undo-test-{}-{seq}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&home);
+        std::fs::create_dir_all(home.join(".Trash")).unwrap();
+        for dir in [".npm", ".gradle/caches"] {
+            std::fs::create_dir_all(home.join(dir)).unwrap();
+            std::fs::write(home.join(dir).join("file.txt"), b"hello").unwrap();
+        }
+
+        let action = ActionPlan {
+            id: "a".to_string(),
+            title: "t".to_string(),
+            risk_level: RiskLevel::R1,
+            estimated_reclaimed_bytes: 0,
+            related_findings: vec![],
+            kind: ActionKind::TrashMove {
+                paths: vec!["~/.npm".to_string(), "~/.gradle/caches".to_string()],
+            },
+            notes: vec![],
+        };
+        let mut moved = apply_trash_moves(&[action], &home).expect("apply").moved;
+        assert_eq!(moved.len(), 2);
+
+        // 元の場所に新しい ~/.gradle/caches ができていれば上書きしない。
+        std::fs::create_dir_all(home.join(".gradle/caches")).unwrap();
+        moved.push(TrashMoveRecord {
+            from: home.join("Library/#0123abcd"),
+            to: home.join(".Trash/#0123abcd"),
+        });
+        moved.push(TrashMoveRecord {
+            from: home.join(".cargo"),
+            to: home.join("Downloads/.cargo"),
+        });
+        // `..` で ~/.Trash や home の外を指す記録、~/.Trash の直下でない記録は戻さない。
+        std::fs::create_dir_all(home.join("Documents")).unwrap();
+        std::fs::create_dir_all(home.join(".Trash/sub/deep")).unwrap();
+        std::fs::write(home.join(".Trash/x"), b"x").unwrap();
+        moved.push(TrashMoveRecord {
+            from: home.join(".npm/../../outside"),
+            to: home.join(".Trash/x"),
+        });
+        moved.push(TrashMoveRecord {
+            from: home.join(".restored-documents"),
+            to: home.join(".Trash/../Documents"),
+        });
+        moved.push(TrashMoveRecord {
+            from: home.join(".restored-deep"),
+            to: home.join(".Trash/sub/deep"),
+        });
+        // 親がシンボリックリンクで home の外を指していれば戻さない。
+        let outside = home.with_file_name(format!(
+            "{}-outside",
+            home.file_name().unwrap().to_string_lossy()
+        ));
+        std::fs::create_dir_all(&outside).unwrap();
+        std::fs::create_dir_all(home.join("Library/Caches")).unwrap();
+        std::os::unix::fs::symlink(&outside, home.join("Library/Caches/escape")).unwrap();
+        std::fs::write(home.join(".Trash/y"), b"y").unwrap();
+        moved.push(TrashMoveRecord {
+            from: home.join("Library/Caches/escape/sub/restored"),
+            to: home.join(".Trash/y"),
+        });
+
+        let outcome = restore_from_trash(&moved, &home);
+        assert_eq!(outcome.restored.len(), 1);
+        assert_eq!(outcome.restored[0].to, home.join(".npm"));
+        assert!(home.join(".npm/file.txt").exists());
+        assert_eq!(outcome.conflicts, vec![home.join(".gradle/caches")]);
+        assert!(moved[1].to.exists());
+        assert_eq!(outcome.errors.len(), 6);
+        assert!(outcome.errors[0].error.contains("伏せられて"));
+        assert!(outcome.errors[2].error.contains("`..`"));
+        assert!(outcome.errors[3].error.contains("`..`"));
+        assert!(outcome.errors[4].error.contains("直下以外"));
+        assert!(outcome.errors[5].error.contains("シンボリックリンク"));
+        assert!(home.join(".Trash/y").exists());
+        assert!(!outside.join("sub").exists());
+        assert!(home.join("Documents").exists());
+        assert!(home.join(".Trash/x").exists());
+        assert!(home.join(".Trash/sub/deep").exists());
+
+        // 確認の後に元の場所が作られても上書きしない。
+        std::fs::write(home.join(".Trash/late"), b"trash").unwrap();
+        std::fs::write(home.join("late"), b"new").unwrap();
+        let err = rename_no_replace(&home.join(".Trash/late"), &home.join("late"))
+            .expect_err("must not replace");
+        assert_eq!(err.kind(), std::io::ErrorKind::AlreadyExists);
+        assert_eq!(std::fs::read(home.join("late")).unwrap(), b"new");
+
+        let again = restore_from_trash(&moved[..1], &home);
+        assert_eq!(again.skipped_missing, vec![moved[0].to.clone()]);
+
+        let _ = std::fs::remove_dir_all(&home);
+        let _ = std::fs::remove_dir_all(&outside);
+    }
+
     #[test]
     fn allowlisted_run_cmd_accepts_simctl_delete_unavailable() {
         let action = ActionPlan {
@@ -957,6 +1695,127 @@ mod tests {
         assert_eq!(spec.final_confirm_token, "run");
     }
 
+    #[test]
+    fn allowlisted_run_cmd_accepts_brew_autoremove() {
+        let action = ActionPlan {
+            id: "homebrew-autoremove".to_string(),
+            title: "brew autoremove".to_string(),
+            risk_level: RiskLevel::R2,
+            estimated_reclaimed_bytes: 0,
+            related_findings: vec![],
+            kind: ActionKind::RunCmd {
+                cmd: "brew".to_string(),
+                args: vec!["autoremove".to_string()],
+            },
+            notes: vec![],
+        };
+
+        let spec = allowlisted_run_cmd(&action).expect("allowlisted");
+        assert_eq!(spec.confirm_token, "autoremove");
+        assert_eq!(spec.final_confirm_token, "run");
+    }
+
+    #[test]
+    fn allowlisted_run_cmd_accepts_brew_cleanup_for_matching_formula_only() {
+        let action = |id: &str, formula: &str| ActionPlan {
+            id: id.to_string(),
+            title: "brew cleanup <formula>".to_string(),
+            risk_level: RiskLevel::R1,
+            estimated_reclaimed_bytes: 0,
+            related_findings: vec![],
+            kind: ActionKind::RunCmd {
+                cmd: "brew".to_string(),
+                args: vec!["cleanup".to_string(), formula.to_string()],
+            },
+            notes: vec![],
+        };
+
+        let spec = allowlisted_run_cmd(&action(
+            "homebrew-old-kegs-cleanup:python@3.13",
+            "python@3.13",
+        ))
+        .expect("allowlisted");
+        assert_eq!(spec.confirm_token, "cleanup");
+        assert!(
+            allowlisted_run_cmd(&action("homebrew-old-kegs-cleanup:node", "python@3.13")).is_none()
+        );
+        assert!(
+            allowlisted_run_cmd(&action(
+                "homebrew-old-kegs-cleanup:--prune=all",
+                "--prune=all"
+            ))
+            .is_none()
+        );
+    }
+
+    #[test]
+    fn allowlisted_run_cmd_accepts_language_version_uninstall() {
+        let action = |id: &str, cmd: &str, args: &[&str]| ActionPlan {
+            id: id.to_string(),
+            title: "uninstall".to_string(),
+            risk_level: RiskLevel::R2,
+            estimated_reclaimed_bytes: 0,
+            related_findings: vec![],
+            kind: ActionKind::RunCmd {
+                cmd: cmd.to_string(),
+                args: args.iter().map(|s| s.to_string()).collect(),
+            },
+            notes: vec![],
+        };
+
+        for (id, cmd, args) in [
+            (
+                "lang-version-uninstall:pyenv:python:3.11.4",
+                "pyenv",
+                &["uninstall", "-f", "3.11.4"][..],
+            ),
+            (
+                "lang-version-uninstall:rbenv:ruby:3.2.2",
+                "rbenv",
+                &["uninstall", "-f", "3.2.2"][..],
+            ),
+            (
+                "lang-version-uninstall:asdf:nodejs:18.17.0",
+                "asdf",
+                &["uninstall", "nodejs", "18.17.0"][..],
+            ),
+            (
+                "lang-version-uninstall:mise:node:20.1.0",
+                "mise",
+                &["uninstall", "node@20.1.0"][..],
+            ),
+        ] {
+            let spec = allowlisted_run_cmd(&action(id, cmd, args)).expect("allowlisted");
+            assert_eq!(spec.confirm_token, "uninstall");
+            assert_eq!(spec.final_confirm_token, "run");
+        }
+
+        assert!(
+            allowlisted_run_cmd(&action(
+                "lang-version-uninstall:pyenv:python:3.11.4",
+                "pyenv",
+                &["uninstall", "-f", "3.10.0"],
+            ))
+            .is_none()
+        );
+        assert!(
+            allowlisted_run_cmd(&action(
+                "lang-version-uninstall:asdf:nodejs:--all",
+                "asdf",
+                &["uninstall", "nodejs", "--all"],
+            ))
+            .is_none()
+        );
+        assert!(
+            allowlisted_run_cmd(&action(
+                "lang-version-uninstall:pyenv:python:3.11.4",
+                "rbenv",
+                &["uninstall", "-f", "3.11.4"],
+            ))
+            .is_none()
+        );
+    }
+
     #[test]
     fn allowlisted_run_cmd_accepts_npm_cache_cleanup() {
         let action = ActionPlan {
@@ -0,0 +1,327 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+const CACHE_SCHEMA_VERSION: u32 = 3;
+const CACHE_FILE_NAME: &str = "sizes.json";
+const CACHE_MAX_AGE_SECS: u64 = 30 * 24 * 60 * 60;
+
+pub fn cache_dir(home_dir: &Path) -> PathBuf {
+    home_dir.join(".config/macdiet/cache")
+}
+
+// ディレクトリ単位のキャッシュ。ディレクトリの mtime は直下のエントリの追加/削除/改名でのみ
+// 更新されるため、直下ファイルのサイズ合計とサブディレクトリ一覧をまとめて再利用できる。
+// 既存ファイルの上書き（サイズ変化）は mtime に現れないため、値は「最終確認時点」のものになる。
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DirRecord {
+    pub ino: u64,
+    pub mtime_secs: i64,
+    pub mtime_nsecs: i64,
+    pub filter: u64,
+    #[serde(flatten)]
+    pub listing: DirListing,
+    pub cached_at: u64,
+}
+
+// ハードリンク（st_nlink > 1）は走査全体で 1 回だけ数えるため、合計に含めず個別に持つ。
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DirListing {
+    pub file_bytes: u64,
+    pub file_count: u64,
+    pub allocated_bytes: u64,
+    pub linked: Vec<LinkedFile>,
+    pub subdirs: Vec<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LinkedFile {
+    pub dev: u64,
+    pub ino: u64,
+    pub bytes: u64,
+    pub allocated_bytes: u64,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct CacheFile {
+    schema_version: u32,
+    dirs: HashMap<String, DirRecord>,
+}
+
+#[derive(Serialize)]
+struct CacheFileRef<'a> {
+    schema_version: u32,
+    dirs: &'a HashMap<String, DirRecord>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CacheStats {
+    pub hits: u64,
+    pub misses: u64,
+    pub oldest_cached_at: Option<u64>,
+}
+
+#[derive(Debug, Default)]
+struct CacheState {
+    dirs: HashMap<String, DirRecord>,
+    dirty: bool,
+    stats: CacheStats,
+}
+
+#[derive(Debug)]
+pub struct SizeCache {
+    path: PathBuf,
+    state: Mutex<CacheState>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DirKey {
+    pub ino: u64,
+    pub mtime_secs: i64,
+    pub mtime_nsecs: i64,
+}
+
+impl DirKey {
+    pub fn from_metadata(meta: &std::fs::Metadata) -> Self {
+        use std::os::unix::fs::MetadataExt;
+        Self {
+            ino: meta.ino(),
+            mtime_secs: meta.mtime(),
+            mtime_nsecs: meta.mtime_nsec(),
+        }
+    }
+}
+
+impl SizeCache {
+    pub fn load(home_dir: &Path) -> Self {
+        let path = cache_dir(home_dir).join(CACHE_FILE_NAME);
+        let dirs = std::fs::read(&path)
+            .ok()
+            .and_then(|bytes| serde_json::from_slice::<CacheFile>(&bytes).ok())
+            .filter(|f| f.schema_version == CACHE_SCHEMA_VERSION)
+            .map(|f| f.dirs)
+            .unwrap_or_default();
+        Self {
+            path,
+            state: Mutex::new(CacheState {
+                dirs,
+                ..CacheState::default()
+            }),
+        }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn lookup(&self, dir: &Path, key: DirKey, filter: u64) -> Option<DirRecord> {
+        let mut state = self.state.lock().ok()?;
+        let hit = state
+            .dirs
+            .get(&entry_key(dir, filter))
+            .filter(|r| {
+                r.ino == key.ino
+                    && r.mtime_secs == key.mtime_secs
+                    && r.mtime_nsecs == key.mtime_nsecs
+                    && r.filter == filter
+            })
+            .cloned();
+        match &hit {
+            Some(record) => {
+                state.stats.hits = state.stats.hits.saturating_add(1);
+                state.stats.oldest_cached_at = Some(
+                    state
+                        .stats
+                        .oldest_cached_at
+                        .map_or(record.cached_at, |t| t.min(record.cached_at)),
+                );
+            }
+            None => state.stats.misses = state.stats.misses.saturating_add(1),
+        }
+        hit
+    }
+
+    pub fn store(&self, dir: &Path, key: DirKey, filter: u64, listing: DirListing) {
+        let Ok(mut state) = self.state.lock() else {
+            return;
+        };
+        let entry = entry_key(dir, filter);
+        if state.dirs.get(&entry).is_some_and(|r| {
+            r.ino == key.ino
+                && r.mtime_secs == key.mtime_secs
+                && r.mtime_nsecs == key.mtime_nsecs
+                && r.listing == listing
+        }) {
+            return;
+        }
+        state.dirs.insert(
+            entry,
+            DirRecord {
+                ino: key.ino,
+                mtime_secs: key.mtime_secs,
+                mtime_nsecs: key.mtime_nsecs,
+                filter,
+                listing,
+                cached_at: unix_now(),
+            },
+        );
+        state.dirty = true;
+    }
+
+    pub fn stats(&self) -> CacheStats {
+        self.state.lock().map(|s| s.stats).unwrap_or_default()
+    }
+
+    pub fn save(&self) -> Result<()> {
+        let Ok(mut state) = self.state.lock() else {
+            return Ok(());
+        };
+        if !state.dirty {
+            return Ok(());
+        }
+        let now = unix_now();
+        state
+            .dirs
+            .retain(|_, r| now.saturating_sub(r.cached_at) <= CACHE_MAX_AGE_SECS);
+
+        let dir = self.path.parent().unwrap_or(Path::new("."));
+        std::fs::create_dir_all(dir).with_context(|| {
+            format!(
+                "キャッシュディレクトリの作成に失敗しました: {}",
+                dir.display()
+            )
+        })?;
+        let json = serde_json::to_vec(&CacheFileRef {
+            schema_version: CACHE_SCHEMA_VERSION,
+            dirs: &state.dirs,
+        })
+        .context("キャッシュのシリアライズに失敗しました")?;
+
+        let tmp = self
+            .path
+            .with_extension(format!("json.tmp-{}", std::process::id()));
+        std::fs::write(&tmp, json)
+            .with_context(|| format!("キャッシュの書き込みに失敗しました: {}", tmp.display()))?;
+        std::fs::rename(&tmp, &self.path).with_context(|| {
+            format!(
+                "キャッシュの書き込みに失敗しました: {}",
+                self.path.display()
+            )
+        })?;
+        state.dirty = false;
+        Ok(())
+    }
+}
+
+// 同じディレクトリでも除外設定（filter）が違えば集計結果が異なるため、両方をキーにする。
+fn entry_key(dir: &Path, filter: u64) -> String {
+    format!("{filter:016x}:{}", dir.to_string_lossy())
+}
+
+pub fn filter_key(excludes: &[String]) -> u64 {
+    if excludes.is_empty() {
+        return 0;
+    }
+    let mut hasher = blake3::Hasher::new();
+    for pat in excludes {
+        hasher.update(pat.as_bytes());
+        hasher.update(b"\0");
+    }
+    let hash = hasher.finalize();
+    let mut bytes = [0u8; 8];
+    bytes.copy_from_slice(&hash.as_bytes()[..8]);
+    u64::from_le_bytes(bytes).max(1)
+}
+
+pub fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicU64, Ordering};
+
+    #[test]
+    fn size_cache_round_trips_and_invalidates_on_key_change() {
+        static SEQ: AtomicU64 = AtomicU64::new(0);
+
+        let seq = SEQ.fetch_add(1, Ordering::Relaxed);
+        let home =
+            std::env::temp_dir().join(format!("macdiet-cache-test-{}-{seq}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&home);
+        std::fs::create_dir_all(&home).expect("create home");
+
+        let key = DirKey {
+            ino: 42,
+            mtime_secs: 1_700_000_000,
+            mtime_nsecs: 5,
+        };
+        let dir = home.join("data");
+        let listing = DirListing {
+            file_bytes: 123,
+            file_count: 2,
+            subdirs: vec!["sub".to_string()],
+            ..DirListing::default()
+        };
+        let cache = SizeCache::load(&home);
+        assert!(cache.lookup(&dir, key, 0).is_none());
+        cache.store(&dir, key, 0, listing.clone());
+        cache.save().expect("save cache");
+        assert!(cache.path().exists());
+
+        let cache = SizeCache::load(&home);
+        let record = cache.lookup(&dir, key, 0).expect("cache hit");
+        assert_eq!(record.listing, listing);
+        assert!(cache.lookup(&dir, key, 7).is_none());
+        let changed = DirKey {
+            mtime_nsecs: 6,
+            ..key
+        };
+        assert!(cache.lookup(&dir, changed, 0).is_none());
+
+        let stats = cache.stats();
+        assert_eq!(stats.hits, 1);
+        assert_eq!(stats.misses, 2);
+        assert!(stats.oldest_cached_at.is_some());
+
+        cache.store(&dir, key, 0, listing);
+        assert!(!cache.state.lock().expect("state").dirty);
+        cache.store(
+            &dir,
+            key,
+            7,
+            DirListing {
+                file_bytes: 50,
+                file_count: 1,
+                ..DirListing::default()
+            },
+        );
+        assert!(cache.state.lock().expect("state").dirty);
+        assert_eq!(
+            cache
+                .lookup(&dir, key, 0)
+                .expect("filter 0")
+                .listing
+                .file_bytes,
+            123
+        );
+        assert_eq!(
+            cache
+                .lookup(&dir, key, 7)
+                .expect("filter 7")
+                .listing
+                .file_bytes,
+            50
+        );
+
+        let _ = std::fs::remove_dir_all(&home);
+    }
+}
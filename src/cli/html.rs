@@ -0,0 +1,570 @@
+use std::fmt::Write as _;
+
+use crate::core::{ActionKind, ActionPlan, Finding, Report, RiskLevel};
+
+const TREEMAP_MAX_TILES: usize = 40;
+const TREEMAP_WIDTH: f64 = 1000.0;
+const TREEMAP_HEIGHT: f64 = 360.0;
+
+// TUI の risk_style（R0=灰 / R1=緑 / R2=黄 / R3=赤・太字）に合わせる。
+const STYLE: &str = r#"
+body{font-family:-apple-system,BlinkMacSystemFont,"Hiragino Sans",sans-serif;margin:24px;color:#1f2328;background:#fff}
+h1{font-size:1.5em}h2{font-size:1.2em;margin-top:2em;border-bottom:1px solid #d0d7de;padding-bottom:4px}
+ul.meta{list-style:none;padding:0}ul.meta li{margin:2px 0}
+table{border-collapse:collapse;width:100%;font-size:14px}
+th,td{border-bottom:1px solid #d0d7de;padding:6px 8px;text-align:left;vertical-align:top}
+th{background:#f6f8fa;cursor:pointer;user-select:none;white-space:nowrap}
+th[data-dir=asc]::after{content:" ▲"}th[data-dir=desc]::after{content:" ▼"}
+td.num{text-align:right;white-space:nowrap}
+code{font-family:ui-monospace,Menlo,monospace;font-size:12px;background:#f6f8fa;padding:1px 4px;border-radius:4px}
+pre{background:#f6f8fa;padding:8px;overflow:auto;font-size:12px}
+.risk{display:inline-block;padding:1px 8px;border-radius:10px;color:#fff;font-size:12px}
+.risk-R0{background:#6e7781}.risk-R1{background:#1a7f37}.risk-R2{background:#b08800}.risk-R3{background:#cf222e;font-weight:bold}
+.treemap{position:relative;width:100%;height:360px;border:1px solid #d0d7de}
+.treemap a{position:absolute;box-sizing:border-box;border:1px solid #fff;overflow:hidden;color:#fff;font-size:12px;padding:2px 4px;text-decoration:none}
+.treemap a.risk-R0{background:#8c959f}
+details summary{cursor:pointer;color:#0969da}
+"#;
+
+const SCRIPT: &str = r#"
+document.querySelectorAll("table.sortable").forEach(function (table) {
+  var headers = table.querySelectorAll("th");
+  headers.forEach(function (th, col) {
+    th.addEventListener("click", function () {
+      var asc = th.getAttribute("data-dir") !== "asc";
+      headers.forEach(function (h) { h.removeAttribute("data-dir"); });
+      th.setAttribute("data-dir", asc ? "asc" : "desc");
+      var num = th.getAttribute("data-type") === "num";
+      var body = table.tBodies[0];
+      var rows = Array.prototype.slice.call(body.rows);
+      var key = function (row) {
+        var cell = row.cells[col];
+        var v = cell.getAttribute("data-sort");
+        return v === null ? cell.textContent.trim() : v;
+      };
+      rows.sort(function (a, b) {
+        var x = key(a), y = key(b);
+        var c = num ? parseFloat(x) - parseFloat(y) : x.localeCompare(y);
+        return asc ? c : -c;
+      });
+      rows.forEach(function (row) { body.appendChild(row); });
+    });
+  });
+});
+"#;
+
+// 1 ファイルで完結する（CSS/JS はインライン、外部読み込みなし）。Report の中身は
+// format_markdown_summary と同じものを出す（evidence は include_evidence のときのみ）。
+pub(crate) fn format_html_report(report: &Report, include_evidence: bool) -> String {
+    let mut out = String::new();
+    let _ = writeln!(out, "<!DOCTYPE html>");
+    let _ = writeln!(out, "<html lang=\"ja\">");
+    let _ = writeln!(out, "<head>");
+    let _ = writeln!(out, "<meta charset=\"utf-8\">");
+    let _ = writeln!(
+        out,
+        "<meta name=\"viewport\" content=\"width=device-width, initial-scale=1\">"
+    );
+    let _ = writeln!(
+        out,
+        "<title>macdiet レポート {}</title>",
+        escape(&report.generated_at)
+    );
+    let _ = writeln!(out, "<style>{STYLE}</style>");
+    let _ = writeln!(out, "</head>");
+    let _ = writeln!(out, "<body>");
+    let _ = writeln!(out, "<h1>macdiet レポート</h1>");
+
+    let _ = writeln!(out, "<ul class=\"meta\">");
+    let _ = writeln!(
+        out,
+        "<li>ツールバージョン: {}</li>",
+        escape(&report.tool_version)
+    );
+    let _ = writeln!(out, "<li>生成日時: {}</li>", escape(&report.generated_at));
+    let _ = writeln!(
+        out,
+        "<li>OS: {} {}</li>",
+        escape(&report.os.name),
+        escape(&report.os.version)
+    );
+    let _ = writeln!(
+        out,
+        "<li>推定合計: {}</li>",
+        crate::ui::format_bytes(report.summary.estimated_total_bytes)
+    );
+    if report.summary.unobserved_bytes > 0 {
+        let _ = writeln!(
+            out,
+            "<li>未観測: {}</li>",
+            crate::ui::format_bytes(report.summary.unobserved_bytes)
+        );
+    }
+    for note in &report.summary.notes {
+        let _ = writeln!(out, "<li>注記: {}</li>", escape(note));
+    }
+    let _ = writeln!(out, "</ul>");
+
+    let mut findings: Vec<&Finding> = report.findings.iter().collect();
+    findings.sort_by_key(|f| (std::cmp::Reverse(f.estimated_bytes), f.id.as_str()));
+
+    write_treemap(&mut out, &findings);
+    write_volumes(&mut out, report);
+    write_classification(&mut out, report);
+    write_findings(&mut out, &findings, include_evidence);
+    write_actions(&mut out, &report.actions);
+
+    let _ = writeln!(out, "<script>{SCRIPT}</script>");
+    let _ = writeln!(out, "</body>");
+    let _ = writeln!(out, "</html>");
+    out
+}
+
+fn write_treemap(out: &mut String, findings: &[&Finding]) {
+    let sized: Vec<&Finding> = findings
+        .iter()
+        .copied()
+        .filter(|f| f.estimated_bytes > 0)
+        .collect();
+    if sized.is_empty() {
+        return;
+    }
+    let shown = &sized[..sized.len().min(TREEMAP_MAX_TILES)];
+    let rest: u64 = sized[shown.len()..].iter().map(|f| f.estimated_bytes).sum();
+    let mut values: Vec<f64> = shown.iter().map(|f| f.estimated_bytes as f64).collect();
+    if rest > 0 {
+        values.push(rest as f64);
+    }
+
+    let _ = writeln!(out, "<h2>容量マップ</h2>");
+    let _ = writeln!(out, "<div class=\"treemap\">");
+    for (i, rect) in squarify(&values, TREEMAP_WIDTH, TREEMAP_HEIGHT)
+        .iter()
+        .enumerate()
+    {
+        let style = format!(
+            "left:{:.3}%;top:{:.3}%;width:{:.3}%;height:{:.3}%",
+            rect.x / TREEMAP_WIDTH * 100.0,
+            rect.y / TREEMAP_HEIGHT * 100.0,
+            rect.w / TREEMAP_WIDTH * 100.0,
+            rect.h / TREEMAP_HEIGHT * 100.0
+        );
+        match shown.get(i) {
+            Some(f) => {
+                let label = format!(
+                    "{}（{}）",
+                    f.title,
+                    crate::ui::format_bytes(f.estimated_bytes)
+                );
+                let _ = writeln!(
+                    out,
+                    "<a class=\"risk-{}\" href=\"#{}\" style=\"{style}\" title=\"{}\">{}</a>",
+                    f.risk_level,
+                    finding_anchor(&f.id),
+                    escape(&label),
+                    escape(&label)
+                );
+            }
+            None => {
+                let label = format!(
+                    "その他 {}件（{}）",
+                    sized.len() - shown.len(),
+                    crate::ui::format_bytes(rest)
+                );
+                let _ = writeln!(
+                    out,
+                    "<a class=\"risk-R0\" style=\"{style}\" title=\"{0}\">{0}</a>",
+                    escape(&label)
+                );
+            }
+        }
+    }
+    let _ = writeln!(out, "</div>");
+}
+
+fn write_volumes(out: &mut String, report: &Report) {
+    if report.volumes.is_empty() {
+        return;
+    }
+    let _ = writeln!(out, "<h2>ボリューム</h2>");
+    let _ = writeln!(out, "<table class=\"sortable\">");
+    let _ = writeln!(
+        out,
+        "<thead><tr><th>パス</th><th>マウントポイント</th><th>デバイス</th><th>種別</th><th data-type=\"num\">合計</th><th data-type=\"num\">ファイル数</th></tr></thead>"
+    );
+    let _ = writeln!(out, "<tbody>");
+    for v in &report.volumes {
+        let _ = writeln!(
+            out,
+            "<tr><td><code>{}</code></td><td><code>{}</code></td><td><code>{}</code></td><td>{}</td><td class=\"num\" data-sort=\"{}\">{}</td><td class=\"num\">{}</td></tr>",
+            escape(&v.path),
+            escape(&v.mount_point),
+            escape(&v.device),
+            escape(&v.fs_type),
+            v.total_bytes,
+            crate::ui::format_bytes(v.total_bytes),
+            v.file_count
+        );
+    }
+    let _ = writeln!(out, "</tbody></table>");
+}
+
+fn write_classification(out: &mut String, report: &Report) {
+    let Some(classification) = &report.classification else {
+        return;
+    };
+    let _ = writeln!(out, "<h2>種類別</h2>");
+    let _ = writeln!(out, "<table class=\"sortable\">");
+    let _ = writeln!(
+        out,
+        "<thead><tr><th>種類</th><th data-type=\"num\">容量</th><th data-type=\"num\">ファイル数</th></tr></thead>"
+    );
+    let _ = writeln!(out, "<tbody>");
+    for c in &classification.categories {
+        let _ = writeln!(
+            out,
+            "<tr><td>{} (<code>{}</code>)</td><td class=\"num\" data-sort=\"{}\">{}</td><td class=\"num\">{}</td></tr>",
+            escape(crate::ui::file_category_label(&c.category)),
+            escape(&c.category),
+            c.bytes,
+            crate::ui::format_bytes(c.bytes),
+            c.files
+        );
+    }
+    let _ = writeln!(out, "</tbody></table>");
+
+    let _ = writeln!(out, "<h2>経過日数別</h2>");
+    let _ = writeln!(out, "<table>");
+    let _ = writeln!(
+        out,
+        "<thead><tr><th>経過</th><th>更新（mtime）</th><th>アクセス（atime）</th></tr></thead>"
+    );
+    let _ = writeln!(out, "<tbody>");
+    for a in &classification.ages {
+        let _ = writeln!(
+            out,
+            "<tr><td>{}</td><td class=\"num\">{}</td><td class=\"num\">{}</td></tr>",
+            escape(&a.bucket),
+            crate::ui::format_bytes(a.mtime_bytes),
+            crate::ui::format_bytes(a.atime_bytes)
+        );
+    }
+    let _ = writeln!(out, "</tbody></table>");
+}
+
+fn write_findings(out: &mut String, findings: &[&Finding], include_evidence: bool) {
+    let _ = writeln!(out, "<h2>所見 ({})</h2>", findings.len());
+    if findings.is_empty() {
+        let _ = writeln!(out, "<p><em>所見はありません。</em></p>");
+        return;
+    }
+    let _ = writeln!(out, "<table class=\"sortable\" id=\"findings\">");
+    let _ = writeln!(
+        out,
+        "<thead><tr><th data-type=\"num\">推定</th><th data-type=\"num\">リスク</th><th data-type=\"num\">確度</th><th>所見</th><th>id</th><th>推奨アクション</th></tr></thead>"
+    );
+    let _ = writeln!(out, "<tbody>");
+    for f in findings {
+        let _ = write!(
+            out,
+            "<tr id=\"{}\"><td class=\"num\" data-sort=\"{}\">{}</td><td data-sort=\"{}\">{}</td><td class=\"num\">{:.2}</td><td>{}",
+            finding_anchor(&f.id),
+            f.estimated_bytes,
+            crate::ui::format_bytes(f.estimated_bytes),
+            risk_rank(f.risk_level),
+            risk_badge(f.risk_level),
+            f.confidence,
+            escape(&f.title)
+        );
+        if include_evidence && !f.evidence.is_empty() {
+            let _ = write!(
+                out,
+                "<details><summary>根拠 ({})</summary><ul>",
+                f.evidence.len()
+            );
+            for ev in &f.evidence {
+                let kind = super::evidence_kind_name(&ev.kind);
+                let value = ev.value.trim_end();
+                if value.contains('\n') {
+                    let _ = write!(out, "<li>{kind}:<pre>{}</pre></li>", escape(value));
+                } else {
+                    let _ = write!(out, "<li>{kind}: <code>{}</code></li>", escape(value));
+                }
+            }
+            let _ = write!(out, "</ul></details>");
+        }
+        let mut ids: Vec<&str> = f
+            .recommended_actions
+            .iter()
+            .map(|a| a.id.as_str())
+            .collect();
+        ids.sort();
+        let ids: Vec<String> = ids
+            .iter()
+            .map(|id| format!("<code>{}</code>", escape(id)))
+            .collect();
+        let _ = writeln!(
+            out,
+            "</td><td><code>{}</code></td><td>{}</td></tr>",
+            escape(&f.id),
+            ids.join(" ")
+        );
+    }
+    let _ = writeln!(out, "</tbody></table>");
+}
+
+fn write_actions(out: &mut String, actions: &[ActionPlan]) {
+    let _ = writeln!(out, "<h2>アクション ({})</h2>", actions.len());
+    if actions.is_empty() {
+        let _ = writeln!(out, "<p><em>アクションはありません。</em></p>");
+        return;
+    }
+    let mut actions: Vec<&ActionPlan> = actions.iter().collect();
+    actions.sort_by_key(|a| {
+        (
+            a.risk_level,
+            std::cmp::Reverse(a.estimated_reclaimed_bytes),
+            a.id.as_str(),
+        )
+    });
+
+    let _ = writeln!(out, "<table class=\"sortable\" id=\"actions\">");
+    let _ = writeln!(
+        out,
+        "<thead><tr><th data-type=\"num\">リスク</th><th data-type=\"num\">推定</th><th>アクション</th><th>id</th><th>種類</th><th>対象</th></tr></thead>"
+    );
+    let _ = writeln!(out, "<tbody>");
+    for a in actions {
+        let _ = write!(
+            out,
+            "<tr><td data-sort=\"{}\">{}</td><td class=\"num\" data-sort=\"{}\">{}</td><td>{}",
+            risk_rank(a.risk_level),
+            risk_badge(a.risk_level),
+            a.estimated_reclaimed_bytes,
+            crate::ui::format_bytes(a.estimated_reclaimed_bytes),
+            escape(&a.title)
+        );
+        let mut details = String::new();
+        match &a.kind {
+            ActionKind::TrashMove { paths } | ActionKind::Delete { paths } => {
+                let mut sorted: Vec<&str> = paths.iter().map(|p| p.as_str()).collect();
+                sorted.sort();
+                for p in sorted {
+                    let _ = write!(details, "<li>パス: <code>{}</code></li>", escape(p));
+                }
+            }
+            ActionKind::RunCmd { cmd, args } => {
+                let _ = write!(
+                    details,
+                    "<li>コマンド: <code>{}</code></li>",
+                    escape(&super::format_cmdline(cmd, args))
+                );
+            }
+            ActionKind::OpenInFinder { path } => {
+                let _ = write!(details, "<li>開く: <code>{}</code></li>", escape(path));
+            }
+            ActionKind::ShowInstructions { markdown } => {
+                let md = markdown.trim();
+                if !md.is_empty() {
+                    let _ = write!(details, "<li>手順:<pre>{}</pre></li>", escape(md));
+                }
+            }
+        }
+        for note in &a.notes {
+            let _ = write!(details, "<li>影響: {}</li>", escape(note));
+        }
+        if !details.is_empty() {
+            let _ = write!(
+                out,
+                "<details><summary>詳細</summary><ul>{details}</ul></details>"
+            );
+        }
+
+        let mut related: Vec<&str> = a.related_findings.iter().map(|s| s.as_str()).collect();
+        related.sort();
+        let related: Vec<String> = related
+            .iter()
+            .map(|id| {
+                format!(
+                    "<a href=\"#{}\"><code>{}</code></a>",
+                    finding_anchor(id),
+                    escape(id)
+                )
+            })
+            .collect();
+        let _ = writeln!(
+            out,
+            "</td><td><code>{}</code></td><td>{}</td><td>{}</td></tr>",
+            escape(&a.id),
+            super::action_kind_name(&a.kind),
+            related.join(" ")
+        );
+    }
+    let _ = writeln!(out, "</tbody></table>");
+}
+
+fn risk_rank(risk: RiskLevel) -> u8 {
+    match risk {
+        RiskLevel::R0 => 0,
+        RiskLevel::R1 => 1,
+        RiskLevel::R2 => 2,
+        RiskLevel::R3 => 3,
+    }
+}
+
+fn risk_badge(risk: RiskLevel) -> String {
+    format!("<span class=\"risk risk-{risk}\">{risk}</span>")
+}
+
+// 英数字と `-` 以外はバイトごとに `_XX`（16 進）にする。`_` 自体も変換するので、異なる id が同じ anchor にならない。
+fn finding_anchor(id: &str) -> String {
+    let mut out = String::from("finding-");
+    for b in id.bytes() {
+        if b.is_ascii_alphanumeric() || b == b'-' {
+            out.push(char::from(b));
+        } else {
+            let _ = write!(out, "_{b:02X}");
+        }
+    }
+    out
+}
+
+fn escape(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&#39;"),
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Rect {
+    x: f64,
+    y: f64,
+    w: f64,
+    h: f64,
+}
+
+// squarified treemap（Bruls et al.）。values は降順を前提に、面積比を保ったまま縦横比が
+// 1 に近い長方形へ分割する。
+fn squarify(values: &[f64], width: f64, height: f64) -> Vec<Rect> {
+    let total: f64 = values.iter().sum();
+    if total <= 0.0 {
+        return vec![];
+    }
+    let scale = width * height / total;
+    let areas: Vec<f64> = values.iter().map(|v| v * scale).collect();
+
+    let worst = |row: &[f64], side: f64| -> f64 {
+        let sum: f64 = row.iter().sum();
+        let max = row.iter().cloned().fold(f64::MIN, f64::max);
+        let min = row.iter().cloned().fold(f64::MAX, f64::min);
+        let side2 = side * side;
+        let sum2 = sum * sum;
+        (side2 * max / sum2).max(sum2 / (side2 * min))
+    };
+
+    let mut rects = Vec::with_capacity(areas.len());
+    let (mut x, mut y, mut w, mut h) = (0.0, 0.0, width, height);
+    let mut start = 0;
+    while start < areas.len() {
+        let side = w.min(h);
+        let mut end = start + 1;
+        while end < areas.len()
+            && worst(&areas[start..=end], side) <= worst(&areas[start..end], side)
+        {
+            end += 1;
+        }
+        let row = &areas[start..end];
+        let sum: f64 = row.iter().sum();
+        if w >= h {
+            let col_w = if h > 0.0 { sum / h } else { 0.0 };
+            let mut cy = y;
+            for a in row {
+                let rh = if col_w > 0.0 { a / col_w } else { 0.0 };
+                rects.push(Rect {
+                    x,
+                    y: cy,
+                    w: col_w,
+                    h: rh,
+                });
+                cy += rh;
+            }
+            x += col_w;
+            w -= col_w;
+        } else {
+            let row_h = if w > 0.0 { sum / w } else { 0.0 };
+            let mut cx = x;
+            for a in row {
+                let rw = if row_h > 0.0 { a / row_h } else { 0.0 };
+                rects.push(Rect {
+                    x: cx,
+                    y,
+                    w: rw,
+                    h: row_h,
+                });
+                cx += rw;
+            }
+            y += row_h;
+            h -= row_h;
+        }
+        start = end;
+    }
+    rects
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn squarify_preserves_area_ratios_within_bounds() {
+        let values = [600.0, 200.0, 100.0, 60.0, 40.0];
+        let rects = squarify(&values, 1000.0, 360.0);
+        assert_eq!(rects.len(), values.len());
+        let total: f64 = values.iter().sum();
+        for (v, r) in values.iter().zip(&rects) {
+            let expected = v / total * 1000.0 * 360.0;
+            assert!((r.w * r.h - expected).abs() < 1e-6, "{r:?}");
+            assert!(r.x >= -1e-9 && r.y >= -1e-9);
+            assert!(
+                r.x + r.w <= 1000.0 + 1e-6 && r.y + r.h <= 360.0 + 1e-6,
+                "{r:?}"
+            );
+        }
+        assert!(squarify(&[], 10.0, 10.0).is_empty());
+        assert_eq!(
+            escape("<a href=\"x\">&'"),
+            "&lt;a href=&quot;x&quot;&gt;&amp;&#39;"
+        );
+    }
+
+    #[test]
+    fn finding_anchor_keeps_distinct_ids_distinct() {
+        assert_eq!(finding_anchor("npm-cache"), "finding-npm-cache");
+        let ids = [
+            "lang-version:pyenv:python:3.8",
+            "lang-version-pyenv-python-3-8",
+            "lang-version_3A",
+            "docker.image/a b",
+            "docker-image-a-b",
+            "シミュレータ",
+        ];
+        let anchors: std::collections::HashSet<String> =
+            ids.iter().map(|id| finding_anchor(id)).collect();
+        assert_eq!(anchors.len(), ids.len(), "anchors={anchors:?}");
+        assert!(
+            anchors.iter().all(|a| a
+                .bytes()
+                .all(|b| b.is_ascii_alphanumeric() || b"-_".contains(&b))),
+            "anchors={anchors:?}"
+        );
+    }
+}
@@ -0,0 +1,107 @@
+use std::fmt::Write as _;
+
+use crate::core::{Finding, Report};
+
+// node_exporter の textfile collector で読めるよう、OpenMetrics のうち Prometheus テキスト形式と
+// 互換な部分だけを使う（`# UNIT` / `# EOF` は Prometheus 側ではコメントとして無視される）。
+pub(crate) fn format_openmetrics(report: &Report) -> String {
+    let mut out = String::new();
+
+    write_header(
+        &mut out,
+        "macdiet_estimated_total_bytes",
+        "所見の推定合計サイズ",
+        true,
+    );
+    let _ = writeln!(
+        out,
+        "macdiet_estimated_total_bytes {}",
+        report.summary.estimated_total_bytes
+    );
+    write_header(
+        &mut out,
+        "macdiet_unobserved_bytes",
+        "権限不足などで観測できなかった推定サイズ",
+        true,
+    );
+    let _ = writeln!(
+        out,
+        "macdiet_unobserved_bytes {}",
+        report.summary.unobserved_bytes
+    );
+
+    let mut findings: Vec<&Finding> = report.findings.iter().collect();
+    findings.sort_by_key(|f| f.id.as_str());
+
+    write_header(
+        &mut out,
+        "macdiet_finding_bytes",
+        "所見ごとの推定サイズ",
+        true,
+    );
+    for f in &findings {
+        let _ = writeln!(
+            out,
+            "macdiet_finding_bytes{{{}}} {}",
+            finding_labels(f),
+            f.estimated_bytes
+        );
+    }
+    write_header(
+        &mut out,
+        "macdiet_finding_confidence",
+        "所見ごとの推定の確度（0〜1）",
+        false,
+    );
+    for f in &findings {
+        let _ = writeln!(
+            out,
+            "macdiet_finding_confidence{{{}}} {}",
+            finding_labels(f),
+            f.confidence
+        );
+    }
+    let _ = writeln!(out, "# EOF");
+    out
+}
+
+fn write_header(out: &mut String, name: &str, help: &str, bytes: bool) {
+    let _ = writeln!(out, "# HELP {name} {help}");
+    let _ = writeln!(out, "# TYPE {name} gauge");
+    if bytes {
+        let _ = writeln!(out, "# UNIT {name} bytes");
+    }
+}
+
+fn finding_labels(f: &Finding) -> String {
+    format!(
+        "id=\"{}\",type=\"{}\",risk=\"{}\"",
+        escape_label(&f.id),
+        escape_label(&f.finding_type),
+        f.risk_level
+    )
+}
+
+fn escape_label(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '\\' => out.push_str("\\\\"),
+            '"' => out.push_str("\\\""),
+            '\n' => out.push_str("\\n"),
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn escape_label_escapes_backslash_quote_and_newline() {
+        assert_eq!(escape_label("a\\b\"c\nd"), "a\\\\b\\\"c\\nd");
+        assert_eq!(escape_label("plain-id"), "plain-id");
+    }
+}
@@ -3,14 +3,17 @@ use std::io::IsTerminal;
 use std::path::PathBuf;
 use std::time::Duration;
 
-use anyhow::Result;
+use anyhow::{Context, Result};
 use clap::{Args, CommandFactory, Parser, Subcommand};
 
 use crate::core::RiskLevel;
-use crate::engine::{Engine, EngineOptions, ScanRequest};
+use crate::engine::{Engine, EngineOptions, GrowthRequest, ScanRequest};
 use crate::ui::UiConfig;
 
+mod html;
 mod interactive;
+mod metrics;
+mod table;
 
 #[derive(Debug, Parser)]
 #[command(
@@ -32,7 +35,13 @@ pub struct Cli {
     #[arg(long, default_value_t = 30, global = true)]
     pub timeout: u64,
     #[arg(long, global = true)]
+    pub no_cache: bool,
+    #[arg(long, global = true)]
     pub dry_run: bool,
+    #[arg(long, global = true, value_name = "FORMAT")]
+    pub events: Option<String>,
+    #[arg(long = "events-fd", global = true, value_name = "FD")]
+    pub events_fd: Option<i32>,
 
     #[command(subcommand)]
     pub command: Commands,
@@ -42,9 +51,14 @@ pub struct Cli {
 pub enum Commands {
     Doctor(DoctorArgs),
     Scan(ScanArgs),
+    Growth(GrowthArgs),
     Snapshots(SnapshotsArgs),
     Fix(FixArgs),
+    Undo(UndoArgs),
     Report(ReportArgs),
+    History(HistoryArgs),
+    Fleet(FleetArgs),
+    Schema(SchemaArgs),
     Ui(UiArgs),
     Completion(CompletionArgs),
     Config(ConfigArgs),
@@ -63,11 +77,43 @@ pub struct ScanArgs {
     #[arg(long)]
     pub deep: bool,
     #[arg(long)]
+    pub duplicates: bool,
+    #[arg(long)]
+    pub classify: bool,
+    #[arg(long, num_args = 0..=1, default_missing_value = "true", value_name = "BOOL")]
+    pub one_file_system: Option<bool>,
+    #[arg(long, value_name = "MOUNT")]
+    pub volume: Vec<String>,
+    #[arg(long)]
+    pub markdown: bool,
+    #[arg(long)]
     pub max_depth: Option<usize>,
     #[arg(long)]
     pub top_dirs: Option<usize>,
     #[arg(long)]
     pub exclude: Vec<String>,
+    #[arg(long, value_name = "PATH")]
+    pub export_ncdu: Option<PathBuf>,
+    #[arg(long, value_name = "PATH")]
+    pub import_ncdu: Option<PathBuf>,
+    #[arg(long)]
+    pub format: Option<String>,
+}
+
+#[derive(Debug, Args)]
+pub struct GrowthArgs {
+    #[arg(long, default_value = "60s")]
+    pub interval: String,
+    #[arg(long, default_value = "30m")]
+    pub duration: String,
+    #[arg(long)]
+    pub scope: Option<String>,
+    #[arg(long)]
+    pub max_depth: Option<usize>,
+    #[arg(long, default_value_t = 20)]
+    pub top: usize,
+    #[arg(long)]
+    pub exclude: Vec<String>,
 }
 
 #[derive(Debug, Args)]
@@ -101,19 +147,96 @@ pub struct FixArgs {
     pub risk: Option<RiskLevel>,
     #[arg(long)]
     pub target: Vec<String>,
+    #[arg(long, value_name = "PATH")]
+    pub from_report: Option<PathBuf>,
+}
+
+#[derive(Debug, Args)]
+pub struct UndoArgs {
+    #[arg(value_name = "LOG|latest")]
+    pub log: String,
 }
 
 #[derive(Debug, Args)]
 pub struct ReportArgs {
+    #[command(subcommand)]
+    pub command: Option<ReportCommand>,
     #[arg(long)]
     pub markdown: bool,
+    #[arg(long, value_name = "PATH")]
+    pub html: Option<PathBuf>,
+    #[arg(long)]
+    pub csv: bool,
+    #[arg(long)]
+    pub tsv: bool,
+    #[arg(long, value_name = "DIR")]
+    pub csv_dir: Option<PathBuf>,
+    #[arg(long, value_name = "TABLE")]
+    pub table: Option<String>,
+    #[arg(long, value_name = "PATH")]
+    pub openmetrics: Option<PathBuf>,
     #[arg(long)]
     pub include_evidence: bool,
+    #[arg(long)]
+    pub host: bool,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum ReportCommand {
+    Diff {
+        old: PathBuf,
+        new: PathBuf,
+        #[arg(long)]
+        markdown: bool,
+    },
+    Validate {
+        file: PathBuf,
+    },
+}
+
+#[derive(Debug, Args)]
+pub struct FleetArgs {
+    #[command(subcommand)]
+    pub command: FleetCommand,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum FleetCommand {
+    Summarize {
+        #[arg(required = true)]
+        files: Vec<PathBuf>,
+        #[arg(long)]
+        markdown: bool,
+        #[arg(long, default_value_t = 3)]
+        top: usize,
+    },
+}
+
+#[derive(Debug, Args)]
+pub struct SchemaArgs {
+    #[command(subcommand)]
+    pub command: SchemaCommand,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum SchemaCommand {
+    Report,
+    Log { kind: String },
 }
 
 #[derive(Debug, Args)]
 pub struct UiArgs {}
 
+#[derive(Debug, Args)]
+pub struct HistoryArgs {
+    #[arg(long, default_value = "doctor")]
+    pub command: String,
+    #[arg(long = "finding", value_name = "ID")]
+    pub findings: Vec<String>,
+    #[arg(long, default_value_t = 8)]
+    pub limit: usize,
+}
+
 #[derive(Debug, Args)]
 pub struct CompletionArgs {
     pub shell: String,
@@ -157,13 +280,33 @@ pub fn run() -> Result<()> {
     let engine = Engine::new(EngineOptions {
         timeout: Duration::from_secs(cli.timeout),
         privacy_mask_home: cfg.privacy.mask_home,
+        redaction: cfg.privacy.redaction,
         include_evidence: false,
-        show_progress: ui_cfg.stderr_is_tty && !cli.quiet && !cli.json && !is_ui_mode,
+        show_progress: ui_cfg.stderr_is_tty
+            && !cli.quiet
+            && !cli.json
+            && !is_ui_mode
+            && cli.events.is_none(),
+        source_roots: cfg
+            .scan
+            .source_roots
+            .iter()
+            .map(|p| crate::config::expand_home_path(p, &home_dir))
+            .collect(),
+        use_cache: !cli.no_cache,
+        sizer: cfg.scan.sizer,
+        cargo_stale_crate_days: cfg.rules.cargo_stale_crate_days,
     })?;
+    let engine = match event_sink(cli.events.as_deref(), cli.events_fd, is_ui_mode)? {
+        Some(sink) => engine.with_events(sink),
+        None => engine,
+    };
 
     match cli.command {
         Commands::Doctor(args) => {
-            let report = engine.doctor()?;
+            let mut report = engine.doctor()?;
+            engine.redactor().report(&mut report);
+            record_history(&cfg, &home_dir, "doctor", &report, &ui_cfg);
             if cli.json {
                 write_json(&report)?;
             } else {
@@ -172,31 +315,128 @@ pub fn run() -> Result<()> {
         }
         Commands::Scan(args) => {
             let top_n = args.top_dirs.unwrap_or(20);
+            if !args.volume.is_empty() && args.scope.is_some() {
+                return Err(crate::exit::invalid_args(
+                    "scan: --volume と --scope は併用できません",
+                ));
+            }
             let scope = args.scope.or_else(|| Some(cfg.scan.default_scope.clone()));
             let mut exclude = cfg.scan.exclude.clone();
             exclude.extend(args.exclude);
             exclude.sort();
             exclude.dedup();
-            if args.deep {
+            if args.export_ncdu.is_some() && !args.deep {
+                return Err(crate::exit::invalid_args(
+                    "scan: --export-ncdu は --deep と併用してください",
+                ));
+            }
+            if args.import_ncdu.is_some()
+                && (args.deep || args.duplicates || args.export_ncdu.is_some())
+            {
+                return Err(crate::exit::invalid_args(
+                    "scan: --import-ncdu は --deep/--duplicates/--export-ncdu と併用できません",
+                ));
+            }
+            if args.classify && !args.deep {
+                return Err(crate::exit::invalid_args(
+                    "scan: --classify は --deep と併用してください",
+                ));
+            }
+            let format = parse_scan_format(args.format.as_deref())?;
+            if format == ScanFormat::Folded {
+                if !args.deep && args.import_ncdu.is_none() {
+                    return Err(crate::exit::invalid_args(
+                        "scan: --format folded は --deep（または --import-ncdu）と併用してください",
+                    ));
+                }
+                if cli.json
+                    || args.duplicates
+                    || args.export_ncdu.is_some()
+                    || args.classify
+                    || args.markdown
+                {
+                    return Err(crate::exit::invalid_args(
+                        "scan: --format folded は --json/--markdown/--duplicates/--export-ncdu/--classify と併用できません",
+                    ));
+                }
+            }
+            if args.deep || args.duplicates || args.import_ncdu.is_some() {
                 crate::scan::validate_excludes(&exclude).map_err(crate::exit::invalid_args_err)?;
             }
-            let report = engine.scan(ScanRequest {
+            let req = ScanRequest {
                 scope,
                 deep: args.deep,
+                duplicates: args.duplicates,
+                classify: args.classify,
+                one_file_system: args.one_file_system,
+                volumes: args.volume,
                 max_depth: args.max_depth.unwrap_or(3),
                 top_dirs: top_n,
                 exclude,
-                show_progress: ui_cfg.stderr_is_tty && !cli.quiet && !cli.json,
-            })?;
+                export_ncdu: args.export_ncdu,
+                import_ncdu: args.import_ncdu,
+                show_progress: ui_cfg.stderr_is_tty
+                    && !cli.quiet
+                    && !cli.json
+                    && cli.events.is_none(),
+            };
+            if format == ScanFormat::Folded {
+                let folded = engine.scan_folded(req)?;
+                if !cli.quiet {
+                    for note in &folded.notes {
+                        eprintln!("{note}");
+                    }
+                }
+                return write_lines(&folded.lines);
+            }
+            let mut report = engine.scan(req)?;
+            engine.redactor().report(&mut report);
+            record_history(&cfg, &home_dir, "scan", &report, &ui_cfg);
             if cli.json {
                 write_json(&report)?;
+            } else if args.markdown {
+                write_markdown_summary(&report, cfg.report.include_evidence)?;
             } else {
                 crate::ui::print_doctor(&report, &ui_cfg, top_n);
             }
         }
+        Commands::Growth(args) => {
+            let interval = parse_duration_arg("--interval", &args.interval)?;
+            let duration = parse_duration_arg("--duration", &args.duration)?;
+            if interval > duration {
+                return Err(crate::exit::invalid_args(
+                    "growth: --interval は --duration 以下にしてください",
+                ));
+            }
+            let mut exclude = cfg.scan.exclude.clone();
+            exclude.extend(args.exclude);
+            exclude.sort();
+            exclude.dedup();
+            crate::scan::validate_excludes(&exclude).map_err(crate::exit::invalid_args_err)?;
+            let req = GrowthRequest {
+                scope: args.scope.or_else(|| Some(cfg.scan.default_scope.clone())),
+                interval,
+                duration,
+                max_depth: args.max_depth.unwrap_or(3),
+                top_dirs: args.top,
+                exclude,
+                show_progress: ui_cfg.stderr_is_tty
+                    && !cli.quiet
+                    && !cli.json
+                    && cli.events.is_none(),
+            };
+            let mut report = engine.growth(req, crate::platform::interrupt_flag())?;
+            engine.redactor().report(&mut report);
+            if cli.json {
+                write_json(&report)?;
+            } else {
+                crate::ui::print_doctor(&report, &ui_cfg, args.top);
+            }
+        }
         Commands::Snapshots(args) => match args.command {
             SnapshotsCommand::Status => {
-                let report = engine.snapshots_status()?;
+                let mut report = engine.snapshots_status()?;
+                engine.redactor().report(&mut report);
                 if cli.json {
                     write_json(&report)?;
                 } else {
@@ -284,6 +524,7 @@ pub fn run() -> Result<()> {
                             let err_s = err.to_string();
                             let log_path = crate::logs::write_snapshots_thin_log(
                                 &home_dir,
+                                engine.redactor(),
                                 started_at,
                                 finished_at,
                                 bytes,
@@ -310,6 +551,7 @@ pub fn run() -> Result<()> {
 
                     let log_path = crate::logs::write_snapshots_thin_log(
                         &home_dir,
+                        engine.redactor(),
                         started_at,
                         finished_at,
                         bytes,
@@ -412,6 +654,7 @@ pub fn run() -> Result<()> {
                                 let finished_at = time::OffsetDateTime::now_utc();
                                 let log_path = crate::logs::write_snapshots_delete_log(
                                     &home_dir,
+                                    engine.redactor(),
                                     started_at,
                                     finished_at,
                                     id,
@@ -447,6 +690,7 @@ pub fn run() -> Result<()> {
                             let finished_at = time::OffsetDateTime::now_utc();
                             let log_path = crate::logs::write_snapshots_delete_log(
                                 &home_dir,
+                                engine.redactor(),
                                 started_at,
                                 finished_at,
                                 id,
@@ -601,6 +845,7 @@ pub fn run() -> Result<()> {
                             let err_s = err.to_string();
                             let log_path = crate::logs::write_snapshots_delete_log(
                                 &home_dir,
+                                engine.redactor(),
                                 started_at,
                                 finished_at,
                                 id,
@@ -631,6 +876,7 @@ pub fn run() -> Result<()> {
 
                     let log_path = crate::logs::write_snapshots_delete_log(
                         &home_dir,
+                        engine.redactor(),
                         started_at,
                         finished_at,
                         id,
@@ -728,7 +974,48 @@ pub fn run() -> Result<()> {
                     ));
                 }
             }
-            let mut report = engine.doctor()?;
+            let (mut report, report_generated_at, current) = match &_args.from_report {
+                Some(path) => {
+                    let report = read_report_file(path)?;
+                    let generated_at = time::OffsetDateTime::parse(
+                        &report.generated_at,
+                        &time::format_description::well_known::Rfc3339,
+                    )
+                    .map_err(|_| {
+                        crate::exit::invalid_args(format!(
+                            "fix --from-report: generated_at を解釈できません: {}",
+                            report.generated_at
+                        ))
+                    })?;
+                    // リスクや RUN_CMD の前提（インストール済み・未参照など）はファイルを信用せず、
+                    // 現在の診断結果から取り直す。
+                    let current = engine.doctor()?.actions;
+                    (
+                        report,
+                        Some(std::time::SystemTime::from(generated_at)),
+                        Some(current),
+                    )
+                }
+                None => (engine.doctor()?, None, None),
+            };
+            let mut mismatched: std::collections::HashMap<String, String> =
+                std::collections::HashMap::new();
+            if let Some(current) = &current {
+                for a in &mut report.actions {
+                    match crate::actions::current_risk(a, current) {
+                        Ok(risk) => a.risk_level = risk,
+                        Err(reason) => {
+                            mismatched.insert(a.id.clone(), reason);
+                        }
+                    }
+                }
+            }
+            if report_generated_at.is_some() {
+                report.summary.notes.push(format!(
+                    "fix: 保存済みレポートから計画（生成日時={}）",
+                    report.generated_at
+                ));
+            }
             report
                 .summary
                 .notes
@@ -808,6 +1095,43 @@ pub fn run() -> Result<()> {
                 .collect();
             actions.sort_by_key(|a| (a.risk_level, std::cmp::Reverse(a.estimated_reclaimed_bytes)));
 
+            if let Some(generated_at) = report_generated_at {
+                crate::actions::validate_actions(&actions, &home_dir)
+                    .map_err(crate::exit::invalid_args_err)?;
+                let mut refused = Vec::new();
+                actions.retain(|a| {
+                    let reason = match &a.kind {
+                        crate::core::ActionKind::RunCmd { .. }
+                            if crate::actions::allowlisted_run_cmd(a).is_none() =>
+                        {
+                            Some("許可リストにないコマンドです".to_string())
+                        }
+                        _ => mismatched.get(&a.id).cloned().or_else(|| {
+                            crate::actions::target_drift(
+                                a,
+                                &home_dir,
+                                generated_at,
+                                Duration::from_secs(cli.timeout),
+                                cfg.scan.sizer,
+                            )
+                        }),
+                    };
+                    match reason {
+                        Some(reason) => {
+                            refused.push(format!("{}: {reason}", a.id));
+                            false
+                        }
+                        None => true,
+                    }
+                });
+                for line in refused {
+                    if !ui_cfg.quiet {
+                        eprintln!("除外: {line}");
+                    }
+                    report.summary.notes.push(format!("fix: 除外 {line}"));
+                }
+            }
+
             crate::actions::validate_actions(&actions, &home_dir)?;
 
             if _args.interactive {
@@ -922,6 +1246,7 @@ pub fn run() -> Result<()> {
                     let finished_at = time::OffsetDateTime::now_utc();
                     let log_path = crate::logs::write_fix_apply_log(
                         &home_dir,
+                        engine.redactor(),
                         started_at,
                         finished_at,
                         crate::core::RiskLevel::R1,
@@ -1020,6 +1345,7 @@ pub fn run() -> Result<()> {
                             let err_s = err.to_string();
                             let log_path = crate::logs::write_fix_run_cmd_log(
                                 &home_dir,
+                                engine.redactor(),
                                 started_at,
                                 finished_at,
                                 action,
@@ -1043,6 +1369,7 @@ pub fn run() -> Result<()> {
 
                     let log_path = crate::logs::write_fix_run_cmd_log(
                         &home_dir,
+                        engine.redactor(),
                         started_at,
                         finished_at,
                         action,
@@ -1118,18 +1445,115 @@ pub fn run() -> Result<()> {
                 report.findings.iter().map(|f| f.estimated_bytes).sum();
 
             if cli.json {
+                engine.redactor().report(&mut report);
                 write_json(&report)?;
             } else {
                 crate::ui::print_fix_plan(&actions, &ui_cfg, max_risk);
             }
         }
+        Commands::Undo(args) => {
+            if cli.json {
+                return Err(crate::exit::invalid_args("undo は --json と併用できません"));
+            }
+            undo(
+                &args.log,
+                &home_dir,
+                engine.redactor(),
+                &ui_cfg,
+                cli.dry_run,
+            )?;
+        }
         Commands::Report(args) => {
+            if let Some(ReportCommand::Validate { file }) = &args.command {
+                return validate_report_file(file, cli.json);
+            }
+            if let Some(ReportCommand::Diff { old, new, markdown }) = args.command {
+                if cli.json && markdown {
+                    return Err(crate::exit::invalid_args(
+                        "report diff: --json と --markdown は併用できません",
+                    ));
+                }
+                let old = read_report_file(&old)?;
+                let new = read_report_file(&new)?;
+                let diff = crate::core::diff_reports(&old, &new);
+                if cli.json {
+                    let mut buf = serde_json::to_vec_pretty(&diff)?;
+                    buf.push(b'\n');
+                    return write_stdout(&buf);
+                } else if markdown {
+                    return write_stdout(format_markdown_diff(&diff).as_bytes());
+                }
+                crate::ui::print_report_diff(&diff, &ui_cfg);
+                return Ok(());
+            }
+            if args.csv && args.tsv {
+                return Err(crate::exit::invalid_args(
+                    "report: --csv と --tsv は併用できません",
+                ));
+            }
+            let table_format = if args.tsv {
+                Some(table::TableFormat::Tsv)
+            } else if args.csv || args.csv_dir.is_some() {
+                Some(table::TableFormat::Csv)
+            } else {
+                None
+            };
+            // 標準出力には 1 つの表だけを出す（2 つの表を続けると CSV として読めない）。
+            let stdout_table = parse_report_table(args.table.as_deref())?;
+            if args.table.is_some() && (table_format.is_none() || args.csv_dir.is_some()) {
+                return Err(crate::exit::invalid_args(
+                    "report: --table は --csv / --tsv と併用してください（--csv-dir は両方の表を書き出します）",
+                ));
+            }
+            let formats = [
+                cli.json,
+                args.markdown,
+                args.html.is_some(),
+                table_format.is_some(),
+                args.openmetrics.is_some(),
+            ];
+            if formats.iter().filter(|f| **f).count() > 1 {
+                return Err(crate::exit::invalid_args(
+                    "report: --json / --markdown / --html / --csv（--tsv, --csv-dir）/ --openmetrics はどれか 1 つだけ指定してください",
+                ));
+            }
             let include_evidence = args.include_evidence || cfg.report.include_evidence;
             let mut report = engine.report()?;
             if !include_evidence {
                 strip_evidence(&mut report);
             }
-            if cli.json {
+            if args.host || cfg.report.include_host {
+                report.host = crate::platform::hostname();
+            }
+            engine.redactor().report(&mut report);
+            if let Some(path) = &args.html {
+                write_html_report(path, &report, include_evidence)?;
+                if !ui_cfg.quiet {
+                    eprintln!("HTML レポートを書き出しました: {}", path.display());
+                }
+            } else if let Some(path) = &args.openmetrics {
+                let metrics = metrics::format_openmetrics(&report);
+                write_file_atomic(path, metrics.as_bytes())
+                    .context("OpenMetrics の書き込みに失敗しました")?;
+                if !ui_cfg.quiet {
+                    eprintln!("OpenMetrics を書き出しました: {}", path.display());
+                }
+            } else if let Some(format) = table_format {
+                if let Some(dir) = &args.csv_dir {
+                    let findings = table::format_findings_table(&report, format);
+                    let actions = table::format_actions_table(&report, format);
+                    write_table_files(dir, format, &findings, &actions)?;
+                    if !ui_cfg.quiet {
+                        eprintln!("表を書き出しました: {}", dir.display());
+                    }
+                } else {
+                    let out = match stdout_table {
+                        ReportTable::Findings => table::format_findings_table(&report, format),
+                        ReportTable::Actions => table::format_actions_table(&report, format),
+                    };
+                    write_stdout(out.as_bytes())?;
+                }
+            } else if cli.json {
                 write_json(&report)?;
             } else if args.markdown {
                 write_markdown_summary(&report, include_evidence)?;
@@ -1137,6 +1561,87 @@ pub fn run() -> Result<()> {
                 crate::ui::print_doctor(&report, &ui_cfg, 10);
             }
         }
+        Commands::Fleet(args) => {
+            let FleetCommand::Summarize {
+                files,
+                markdown,
+                top,
+            } = args.command;
+            if cli.json && markdown {
+                return Err(crate::exit::invalid_args(
+                    "fleet summarize: --json と --markdown は併用できません",
+                ));
+            }
+            let mut inputs = Vec::with_capacity(files.len());
+            for path in &files {
+                inputs.push(crate::fleet::FleetInput {
+                    source: path
+                        .file_name()
+                        .map(|n| n.to_string_lossy().to_string())
+                        .unwrap_or_else(|| path.display().to_string()),
+                    report: read_report_file(path)?,
+                });
+            }
+            let summary = crate::fleet::summarize(&inputs, top);
+            if cli.json {
+                let mut buf = serde_json::to_vec_pretty(&summary)?;
+                buf.push(b'\n');
+                write_stdout(&buf)?;
+            } else if markdown {
+                write_stdout(format_markdown_fleet(&summary).as_bytes())?;
+            } else {
+                crate::ui::print_fleet_summary(&summary, &ui_cfg);
+            }
+        }
+        Commands::Schema(args) => {
+            let schema = match &args.command {
+                SchemaCommand::Report => crate::schema::report_schema(),
+                SchemaCommand::Log { kind } => {
+                    crate::schema::log_schema(kind.trim()).ok_or_else(|| {
+                        crate::exit::invalid_args(format!(
+                            "schema log: 未対応のログ種別です: {kind}（{} のいずれかを指定してください）",
+                            crate::logs::LOG_KINDS.join("|")
+                        ))
+                    })?
+                }
+            };
+            let mut buf = serde_json::to_vec_pretty(&schema)?;
+            buf.push(b'\n');
+            write_stdout(&buf)?;
+        }
+        Commands::History(args) => {
+            let command = match args.command.trim() {
+                "doctor" | "scan" => Some(args.command.trim()),
+                "all" => None,
+                other => {
+                    return Err(crate::exit::invalid_args(format!(
+                        "history: 未対応の --command です: {other}（doctor|scan|all を指定してください）"
+                    )));
+                }
+            };
+            if args.limit == 0 {
+                return Err(crate::exit::invalid_args(
+                    "history: --limit は 1 以上を指定してください",
+                ));
+            }
+            let mut loaded = crate::history::load_runs(&home_dir, command)?;
+            let keep_from = loaded.runs.len().saturating_sub(args.limit);
+            loaded.runs.drain(..keep_from);
+            let trend = crate::history::trend(&loaded.runs, &args.findings);
+            if cli.json {
+                let mut buf = serde_json::to_vec_pretty(&trend)?;
+                buf.push(b'\n');
+                write_stdout(&buf)?;
+            } else {
+                crate::ui::print_history(&trend, &ui_cfg, cfg.history.enabled);
+            }
+            if loaded.skipped > 0 && !ui_cfg.quiet {
+                eprintln!(
+                    "history: 読み取れない/スキーマの異なる履歴を {}件スキップしました",
+                    loaded.skipped
+                );
+            }
+        }
         Commands::Ui(_args) => {
             if cli.json {
                 return Err(crate::exit::invalid_args("ui は --json と併用できません"));
@@ -1178,18 +1683,60 @@ pub fn run() -> Result<()> {
     Ok(())
 }
 
+// `--events ndjson` の書き出し先。ui は画面を専有するので併用できない。
+fn event_sink(
+    format: Option<&str>,
+    fd: Option<i32>,
+    is_ui_mode: bool,
+) -> Result<Option<crate::events::EventSink>> {
+    let Some(format) = format else {
+        if fd.is_some() {
+            return Err(crate::exit::invalid_args(
+                "--events-fd は --events ndjson と併用してください",
+            ));
+        }
+        return Ok(None);
+    };
+    if format.trim() != "ndjson" {
+        return Err(crate::exit::invalid_args(format!(
+            "--events が不正です: {format}（ndjson を指定してください）"
+        )));
+    }
+    if is_ui_mode {
+        return Err(crate::exit::invalid_args("ui は --events と併用できません"));
+    }
+    let sink = match fd {
+        // 0〜2 は標準入出力。stdout に混ざると --json などの出力が壊れるので、stderr は --events-fd なしで使う。
+        Some(fd @ 0..=2) => {
+            return Err(crate::exit::invalid_args(format!(
+                "--events-fd: fd {fd} は標準入出力のため指定できません（stderr に出す場合は --events-fd を省略してください）"
+            )));
+        }
+        Some(fd) => {
+            let file = crate::platform::inherited_fd_writer(fd).ok_or_else(|| {
+                crate::exit::invalid_args(format!("--events-fd: fd {fd} は開かれていません"))
+            })?;
+            crate::events::EventSink::ndjson(file)
+        }
+        None => crate::events::EventSink::ndjson(std::io::stderr()),
+    };
+    Ok(Some(sink))
+}
+
 fn write_json(report: &crate::core::Report) -> Result<()> {
-    use std::io::Write;
+    let mut buf = serde_json::to_vec_pretty(report)?;
+    buf.push(b'\n');
+    write_stdout(&buf)
+}
 
-    let buf = serde_json::to_vec_pretty(report)?;
+fn write_lines(lines: &[String]) -> Result<()> {
+    use std::io::Write;
 
-    let mut stdout = std::io::stdout().lock();
-    match stdout.write_all(&buf) {
-        Ok(()) => {}
-        Err(err) if err.kind() == std::io::ErrorKind::BrokenPipe => return Ok(()),
-        Err(err) => return Err(err.into()),
+    let mut buf = lines.join("\n");
+    if !buf.is_empty() {
+        buf.push('\n');
     }
-    match stdout.write_all(b"\n") {
+    match std::io::stdout().lock().write_all(buf.as_bytes()) {
         Ok(()) => Ok(()),
         Err(err) if err.kind() == std::io::ErrorKind::BrokenPipe => Ok(()),
         Err(err) => Err(err.into()),
@@ -1214,6 +1761,312 @@ fn write_markdown_summary(report: &crate::core::Report, include_evidence: bool)
     }
 }
 
+// 履歴の保存失敗で本来の出力を妨げないよう、警告だけ出して続行する。
+// 履歴も共有される出力と同じく、privacy.redaction を適用した後の Report を保存する。
+fn record_history(
+    cfg: &crate::config::EffectiveConfig,
+    home_dir: &std::path::Path,
+    command: &str,
+    report: &crate::core::Report,
+    ui_cfg: &UiConfig,
+) {
+    if !cfg.history.enabled {
+        return;
+    }
+    let now = time::OffsetDateTime::now_utc();
+    if let Err(err) = crate::history::save_run(home_dir, command, report, &cfg.history, now)
+        && !ui_cfg.quiet
+    {
+        eprintln!("警告: 履歴の保存に失敗しました: {err:#}");
+    }
+}
+
+fn write_stdout(buf: &[u8]) -> Result<()> {
+    use std::io::Write;
+
+    let mut stdout = std::io::stdout().lock();
+    match stdout.write_all(buf) {
+        Ok(()) => Ok(()),
+        Err(err) if err.kind() == std::io::ErrorKind::BrokenPipe => Ok(()),
+        Err(err) => Err(err.into()),
+    }
+}
+
+// 保存済みの `report --json` を読む。スキーマが異なるものは比較できないため、
+// 古い schema_version は現行版へ移行して読む。未対応の版はフィールド不一致の解析エラーより先に分かりやすく失敗させる。
+fn read_report_file(path: &std::path::Path) -> Result<crate::core::Report> {
+    use crate::schema::MigrateError;
+
+    let bytes = std::fs::read(path)
+        .with_context(|| format!("レポートを読み取れません: {}", path.display()))?;
+    let value: serde_json::Value = serde_json::from_slice(&bytes)
+        .with_context(|| format!("レポートの JSON が不正です: {}", path.display()))
+        .map_err(crate::exit::invalid_args_err)?;
+    crate::schema::migrate_report(value).map_err(|err| {
+        let version = match &err {
+            MigrateError::Invalid(_) => {
+                return crate::exit::invalid_args_err(
+                    anyhow::Error::new(err)
+                        .context(format!("レポートの形式が不正です: {}", path.display())),
+                );
+            }
+            MigrateError::Unsupported(version) => version.as_str(),
+            MigrateError::MissingVersion => "(なし)",
+        };
+        crate::exit::invalid_args(format!(
+            "レポートのスキーマバージョンが一致しません: {}（schema_version={version}、対応: {}）",
+            path.display(),
+            crate::schema::SUPPORTED_REPORT_VERSIONS.join(", ")
+        ))
+    })
+}
+
+#[derive(serde::Serialize)]
+struct ReportValidation {
+    path: String,
+    schema_version: Option<String>,
+    valid: bool,
+    errors: Vec<String>,
+}
+
+fn validate_report_file(path: &std::path::Path, json: bool) -> Result<()> {
+    let bytes = std::fs::read(path)
+        .with_context(|| format!("レポートを読み取れません: {}", path.display()))?;
+    let value: serde_json::Value = serde_json::from_slice(&bytes)
+        .with_context(|| format!("レポートの JSON が不正です: {}", path.display()))
+        .map_err(crate::exit::invalid_args_err)?;
+
+    let schema_version = value
+        .get("schema_version")
+        .and_then(|v| v.as_str())
+        .map(str::to_string);
+    let mut errors = Vec::new();
+    match &schema_version {
+        Some(v) if crate::schema::SUPPORTED_REPORT_VERSIONS.contains(&v.as_str()) => {}
+        other => errors.push(format!(
+            "/schema_version: 未対応のバージョンです: {}（対応: {}）",
+            other.as_deref().unwrap_or("(なし)"),
+            crate::schema::SUPPORTED_REPORT_VERSIONS.join(", ")
+        )),
+    }
+    errors.extend(crate::schema::validate(
+        &crate::schema::report_schema(),
+        &value,
+    ));
+    let result = ReportValidation {
+        path: path.display().to_string(),
+        schema_version,
+        valid: errors.is_empty(),
+        errors,
+    };
+
+    if json {
+        let mut buf = serde_json::to_vec_pretty(&result)?;
+        buf.push(b'\n');
+        write_stdout(&buf)?;
+    } else if result.valid {
+        let version = result.schema_version.as_deref().unwrap_or_default();
+        let mut line = format!("OK: {}（schema_version={version}）", result.path);
+        if version != crate::core::REPORT_SCHEMA_VERSION {
+            line.push_str(&format!(
+                "。{} として読み込めます",
+                crate::core::REPORT_SCHEMA_VERSION
+            ));
+        }
+        line.push('\n');
+        write_stdout(line.as_bytes())?;
+    } else {
+        let lines: Vec<String> = result.errors.iter().map(|e| format!("- {e}")).collect();
+        write_lines(&lines)?;
+    }
+    if !result.valid {
+        return Err(crate::exit::invalid_args(format!(
+            "レポートがスキーマに適合しません: {}（{} 件）",
+            result.path,
+            result.errors.len()
+        )));
+    }
+    Ok(())
+}
+
+fn format_markdown_fleet(summary: &crate::fleet::FleetSummary) -> String {
+    use std::fmt::Write as _;
+
+    let mut out = String::new();
+    let _ = writeln!(out, "# macdiet フリート集計");
+    let _ = writeln!(out);
+    let _ = writeln!(out, "- マシン数: {}", summary.machines.len());
+    if summary.superseded_reports > 0 {
+        let _ = writeln!(
+            out,
+            "- 同じホストの古いレポート: {}件（最新のみ集計）",
+            summary.superseded_reports
+        );
+    }
+    let _ = writeln!(
+        out,
+        "- 推定合計: {}（1 台あたり中央値 {}）",
+        crate::ui::format_bytes(summary.estimated_total_bytes),
+        crate::ui::format_bytes(summary.median_total_bytes)
+    );
+
+    let _ = writeln!(out);
+    let _ = writeln!(out, "## 所見の種類 ({})", summary.types.len());
+    let _ = writeln!(out);
+    if summary.types.is_empty() {
+        let _ = writeln!(out, "_所見はありません。_");
+    } else {
+        let _ = writeln!(
+            out,
+            "| 所見 | type | マシン数 | 合計 | 中央値 | 上位のマシン |"
+        );
+        let _ = writeln!(out, "| --- | --- | ---: | ---: | ---: | --- |");
+        for t in &summary.types {
+            let worst: Vec<String> = t
+                .worst
+                .iter()
+                .map(|w| {
+                    format!(
+                        "{} ({})",
+                        w.host.replace('|', "\\|"),
+                        crate::ui::format_bytes(w.bytes)
+                    )
+                })
+                .collect();
+            let _ = writeln!(
+                out,
+                "| {} | `{}` | {} / {} | {} | {} | {} |",
+                t.title.replace('|', "\\|"),
+                t.finding_type,
+                t.machines,
+                summary.machines.len(),
+                crate::ui::format_bytes(t.total_bytes),
+                crate::ui::format_bytes(t.median_bytes),
+                worst.join(", ")
+            );
+        }
+    }
+
+    let _ = writeln!(out);
+    let _ = writeln!(out, "## マシン");
+    let _ = writeln!(out);
+    let _ = writeln!(out, "| ホスト | 生成日時 | 推定合計 |");
+    let _ = writeln!(out, "| --- | --- | ---: |");
+    let mut machines: Vec<&crate::fleet::FleetMachine> = summary.machines.iter().collect();
+    machines.sort_by_key(|m| (std::cmp::Reverse(m.estimated_total_bytes), m.host.as_str()));
+    for m in machines {
+        let _ = writeln!(
+            out,
+            "| {} | {} | {} |",
+            m.host.replace('|', "\\|"),
+            m.generated_at,
+            crate::ui::format_bytes(m.estimated_total_bytes)
+        );
+    }
+    out
+}
+
+fn format_markdown_diff(diff: &crate::core::ReportDiff) -> String {
+    use crate::core::FindingChange;
+    use std::fmt::Write as _;
+
+    let mut out = String::new();
+    let _ = writeln!(out, "# macdiet レポート差分");
+    let _ = writeln!(out);
+    let _ = writeln!(
+        out,
+        "- 比較: {} → {}",
+        diff.old_generated_at, diff.new_generated_at
+    );
+    for (label, d) in [
+        ("推定合計", diff.estimated_total_bytes),
+        ("未観測", diff.unobserved_bytes),
+    ] {
+        let _ = writeln!(
+            out,
+            "- {label}: {} → {}（{}）",
+            crate::ui::format_bytes(d.old),
+            crate::ui::format_bytes(d.new),
+            crate::ui::format_delta_bytes(d.delta)
+        );
+    }
+    let _ = writeln!(out, "- 変化なし: {}件", diff.unchanged_count);
+
+    for change in [
+        FindingChange::Grown,
+        FindingChange::Shrunk,
+        FindingChange::New,
+        FindingChange::Disappeared,
+    ] {
+        let rows: Vec<&crate::core::FindingDelta> = diff.changed(change).collect();
+        let _ = writeln!(out);
+        let _ = writeln!(
+            out,
+            "## {} ({})",
+            crate::ui::finding_change_label(change),
+            rows.len()
+        );
+        let _ = writeln!(out);
+        if rows.is_empty() {
+            let _ = writeln!(out, "_該当なし。_");
+            continue;
+        }
+        let _ = writeln!(out, "| 所見 | id | 前回 | 今回 | 差分 |");
+        let _ = writeln!(out, "| --- | --- | ---: | ---: | ---: |");
+        for f in rows {
+            let _ = writeln!(
+                out,
+                "| {} | `{}` | {} | {} | {} |",
+                f.title.replace('|', "\\|"),
+                f.id,
+                crate::ui::format_bytes(f.bytes.old),
+                crate::ui::format_bytes(f.bytes.new),
+                crate::ui::format_delta_bytes(f.bytes.delta)
+            );
+        }
+    }
+    out
+}
+
+fn write_html_report(
+    path: &std::path::Path,
+    report: &crate::core::Report,
+    include_evidence: bool,
+) -> Result<()> {
+    let html = html::format_html_report(report, include_evidence);
+    write_file_atomic(path, html.as_bytes()).context("HTML レポートの書き込みに失敗しました")
+}
+
+// 同じディレクトリの一時ファイルに書いてから rename する（cron などから読まれても中途半端な内容を見せない）。
+fn write_file_atomic(path: &std::path::Path, bytes: &[u8]) -> Result<()> {
+    let mut tmp_name = path.file_name().unwrap_or_default().to_os_string();
+    tmp_name.push(format!(".tmp-{}", std::process::id()));
+    let tmp = path.with_file_name(tmp_name);
+    std::fs::write(&tmp, bytes)
+        .with_context(|| format!("書き込みに失敗しました: {}", tmp.display()))?;
+    if let Err(err) = std::fs::rename(&tmp, path) {
+        let _ = std::fs::remove_file(&tmp);
+        return Err(err).with_context(|| format!("書き込みに失敗しました: {}", path.display()));
+    }
+    Ok(())
+}
+
+fn write_table_files(
+    dir: &std::path::Path,
+    format: table::TableFormat,
+    findings: &str,
+    actions: &str,
+) -> Result<()> {
+    std::fs::create_dir_all(dir)
+        .with_context(|| format!("出力ディレクトリの作成に失敗しました: {}", dir.display()))?;
+    for (name, body) in [("findings", findings), ("actions", actions)] {
+        let path = dir.join(format!("{name}.{}", format.extension()));
+        std::fs::write(&path, body)
+            .with_context(|| format!("表の書き込みに失敗しました: {}", path.display()))?;
+    }
+    Ok(())
+}
+
 fn format_markdown_summary(report: &crate::core::Report, include_evidence: bool) -> String {
     use std::fmt::Write as _;
 
@@ -1240,6 +2093,61 @@ fn format_markdown_summary(report: &crate::core::Report, include_evidence: bool)
         let _ = writeln!(out, "- 注記: {note}");
     }
 
+    if !report.volumes.is_empty() {
+        let _ = writeln!(out);
+        let _ = writeln!(out, "## ボリューム");
+        let _ = writeln!(out);
+        let _ = writeln!(
+            out,
+            "| パス | マウントポイント | デバイス | 種別 | 合計 | ファイル数 |"
+        );
+        let _ = writeln!(out, "| --- | --- | --- | --- | ---: | ---: |");
+        for v in &report.volumes {
+            let _ = writeln!(
+                out,
+                "| `{}` | `{}` | `{}` | {} | {} | {} |",
+                v.path,
+                v.mount_point,
+                v.device,
+                v.fs_type,
+                crate::ui::format_bytes(v.total_bytes),
+                v.file_count
+            );
+        }
+    }
+
+    if let Some(classification) = &report.classification {
+        let _ = writeln!(out);
+        let _ = writeln!(out, "## 種類別");
+        let _ = writeln!(out);
+        let _ = writeln!(out, "| 種類 | 容量 | ファイル数 |");
+        let _ = writeln!(out, "| --- | ---: | ---: |");
+        for c in &classification.categories {
+            let _ = writeln!(
+                out,
+                "| {} (`{}`) | {} | {} |",
+                crate::ui::file_category_label(&c.category),
+                c.category,
+                crate::ui::format_bytes(c.bytes),
+                c.files
+            );
+        }
+        let _ = writeln!(out);
+        let _ = writeln!(out, "## 経過日数別");
+        let _ = writeln!(out);
+        let _ = writeln!(out, "| 経過 | 更新（mtime） | アクセス（atime） |");
+        let _ = writeln!(out, "| --- | ---: | ---: |");
+        for a in &classification.ages {
+            let _ = writeln!(
+                out,
+                "| {} | {} | {} |",
+                a.bucket,
+                crate::ui::format_bytes(a.mtime_bytes),
+                crate::ui::format_bytes(a.atime_bytes)
+            );
+        }
+    }
+
     let _ = writeln!(out);
     let _ = writeln!(out, "## 所見 ({})", report.findings.len());
     if report.findings.is_empty() {
@@ -1523,6 +2431,120 @@ fn print_fix_apply_partition(part: &FixApplyPartition, cfg: &crate::ui::UiConfig
     let _ = writeln!(out);
 }
 
+// fix --apply のログ（`latest` は最新）に記録された移動を ~/.Trash から元の場所へ戻す。
+fn undo(
+    log: &str,
+    home_dir: &std::path::Path,
+    redactor: &crate::privacy::Redactor,
+    ui_cfg: &UiConfig,
+    dry_run: bool,
+) -> Result<()> {
+    let show = |p: &std::path::Path| {
+        p.strip_prefix(home_dir)
+            .map(|rest| format!("~/{}", rest.display()))
+            .unwrap_or_else(|_| p.display().to_string())
+    };
+
+    let log_path = if log.trim() == "latest" {
+        crate::logs::latest_log(home_dir, "fix-apply").ok_or_else(|| {
+            crate::exit::invalid_args(format!(
+                "undo: fix --apply のログがありません（{}）",
+                show(&crate::logs::logs_dir(home_dir))
+            ))
+        })?
+    } else {
+        crate::config::expand_home_path(log.trim(), home_dir)
+    };
+    let moved = crate::logs::read_fix_apply_log(&log_path, home_dir)
+        .map_err(crate::exit::invalid_args_err)?;
+
+    if !ui_cfg.quiet {
+        println!("ログ: {}", show(&log_path));
+        for record in &moved {
+            let note = if crate::privacy::is_redacted_path(&record.from.to_string_lossy()) {
+                "（パスが伏せられているため戻せません）"
+            } else if std::fs::symlink_metadata(&record.to).is_err() {
+                "（ゴミ箱に見つかりません）"
+            } else if std::fs::symlink_metadata(&record.from).is_ok() {
+                "（元の場所に既にあるため戻しません）"
+            } else {
+                ""
+            };
+            println!("  {} -> {}{note}", show(&record.to), show(&record.from));
+        }
+    }
+    if moved.is_empty() {
+        if !ui_cfg.quiet {
+            println!("元に戻す項目がありません（ゴミ箱へ移動した記録がありません）。");
+        }
+        return Ok(());
+    }
+    if dry_run {
+        if !ui_cfg.quiet {
+            println!("dry-run: ファイルシステムへの変更は行っていません");
+        }
+        return Ok(());
+    }
+    if !(ui_cfg.stdin_is_tty && ui_cfg.stdout_is_tty) {
+        return Err(crate::exit::invalid_args(
+            "undo は TTY が必要です（stdin + stdout）",
+        ));
+    }
+    if !confirm_exact(
+        "上記をゴミ箱（~/.Trash）から元の場所へ戻します。続行するには 'restore' と入力してください: ",
+        "restore",
+    )? {
+        if !ui_cfg.quiet {
+            eprintln!("キャンセルしました。");
+        }
+        return Ok(());
+    }
+
+    let started_at = time::OffsetDateTime::now_utc();
+    let outcome = crate::actions::restore_from_trash(&moved, home_dir);
+    let finished_at = time::OffsetDateTime::now_utc();
+    let undo_log = crate::logs::write_undo_log(
+        home_dir,
+        redactor,
+        started_at,
+        finished_at,
+        &log_path,
+        &outcome,
+    )
+    .map_err(|e| {
+        anyhow::anyhow!("undo: 復元は完了しましたが、ログの書き込みに失敗しました: {e}")
+    })?;
+    if !ui_cfg.quiet {
+        println!(
+            "元に戻した: {} 件 / ゴミ箱に見つからず: {} 件 / 元の場所に既にあり: {} 件 / エラー: {} 件",
+            outcome.restored.len(),
+            outcome.skipped_missing.len(),
+            outcome.conflicts.len(),
+            outcome.errors.len()
+        );
+        println!("ログ: {}", show(&undo_log));
+    }
+    if ui_cfg.verbose {
+        for record in &outcome.restored {
+            println!("復元: {} -> {}", record.from.display(), record.to.display());
+        }
+        for conflict in &outcome.conflicts {
+            println!("既にあり: {}", conflict.display());
+        }
+        for err in &outcome.errors {
+            println!("エラー: {}: {}", err.path.display(), err.error);
+        }
+    }
+    if !outcome.errors.is_empty() || !outcome.conflicts.is_empty() {
+        return Err(anyhow::anyhow!(
+            "undo: 元に戻せない項目がありました（conflicts={}, errors={}）",
+            outcome.conflicts.len(),
+            outcome.errors.len()
+        ));
+    }
+    Ok(())
+}
+
 fn confirm_exact(prompt: &str, expected: &str) -> Result<bool> {
     use std::io::{BufRead, Write};
 
@@ -1539,6 +2561,74 @@ fn confirm_exact(prompt: &str, expected: &str) -> Result<bool> {
     Ok(input.trim() == expected)
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ScanFormat {
+    Table,
+    Folded,
+}
+
+fn parse_scan_format(s: Option<&str>) -> Result<ScanFormat> {
+    let Some(s) = s else {
+        return Ok(ScanFormat::Table);
+    };
+    match s.trim().to_ascii_lowercase().as_str() {
+        "table" => Ok(ScanFormat::Table),
+        "folded" => Ok(ScanFormat::Folded),
+        other => Err(crate::exit::invalid_args(format!(
+            "scan: 未対応の --format です: {other}（table|folded を指定してください）"
+        ))),
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ReportTable {
+    Findings,
+    Actions,
+}
+
+fn parse_report_table(s: Option<&str>) -> Result<ReportTable> {
+    let Some(s) = s else {
+        return Ok(ReportTable::Findings);
+    };
+    match s.trim().to_ascii_lowercase().as_str() {
+        "findings" => Ok(ReportTable::Findings),
+        "actions" => Ok(ReportTable::Actions),
+        other => Err(crate::exit::invalid_args(format!(
+            "report: 未対応の --table です: {other}（findings|actions を指定してください）"
+        ))),
+    }
+}
+
+// `90`（秒）/ `90s` / `15m` / `2h` を受け付ける。
+// growth の監視時間として意味のある上限（366 日）。これを超える値は桁の打ち間違いとして拒否する。
+const DURATION_ARG_MAX_SECS: u64 = 366 * 24 * 60 * 60;
+
+fn parse_duration_arg(flag: &str, s: &str) -> Result<Duration> {
+    let s = s.trim().to_ascii_lowercase();
+    let (digits, unit) = match s.find(|c: char| !c.is_ascii_digit()) {
+        Some(i) => s.split_at(i),
+        None => (s.as_str(), "s"),
+    };
+    let secs_per_unit = match unit {
+        "s" => 1,
+        "m" => 60,
+        "h" => 60 * 60,
+        _ => 0,
+    };
+    match digits.parse::<u64>() {
+        Ok(n) if n > 0 && secs_per_unit > 0 => match n.checked_mul(secs_per_unit) {
+            Some(secs) if secs <= DURATION_ARG_MAX_SECS => Ok(Duration::from_secs(secs)),
+            _ => Err(crate::exit::invalid_args(format!(
+                "{flag} が大きすぎます: {s}（上限 {}h）",
+                DURATION_ARG_MAX_SECS / 3600
+            ))),
+        },
+        _ => Err(crate::exit::invalid_args(format!(
+            "{flag} の値が不正です: {s}（例: 30s, 15m, 2h）"
+        ))),
+    }
+}
+
 fn parse_shell(s: &str) -> Result<clap_complete::Shell> {
     let s = s.trim().to_ascii_lowercase();
     match s.as_str() {
@@ -1646,4 +2736,41 @@ mod tests {
         );
         assert!(part.skipped_actions.iter().any(|s| s.action.id == "info"));
     }
+
+    #[test]
+    fn parse_duration_arg_accepts_units_and_rejects_garbage() {
+        assert_eq!(
+            parse_duration_arg("--interval", "90").unwrap(),
+            Duration::from_secs(90)
+        );
+        assert_eq!(
+            parse_duration_arg("--interval", "60s").unwrap(),
+            Duration::from_secs(60)
+        );
+        assert_eq!(
+            parse_duration_arg("--duration", "30m").unwrap(),
+            Duration::from_secs(1800)
+        );
+        assert_eq!(
+            parse_duration_arg("--duration", "2H").unwrap(),
+            Duration::from_secs(7200)
+        );
+        for bad in [
+            "",
+            "0s",
+            "10d",
+            "m",
+            "1.5m",
+            "-1s",
+            "999999999999999999h",
+            "18446744073709551615s",
+            "8785h",
+        ] {
+            assert!(parse_duration_arg("--interval", bad).is_err(), "{bad}");
+        }
+        assert_eq!(
+            parse_duration_arg("--duration", "8784h").unwrap(),
+            Duration::from_secs(DURATION_ARG_MAX_SECS)
+        );
+    }
 }
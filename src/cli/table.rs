@@ -0,0 +1,173 @@
+use crate::core::{ActionKind, ActionPlan, Finding, Report};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum TableFormat {
+    Csv,
+    Tsv,
+}
+
+impl TableFormat {
+    pub(crate) fn extension(self) -> &'static str {
+        match self {
+            TableFormat::Csv => "csv",
+            TableFormat::Tsv => "tsv",
+        }
+    }
+
+    // CSV は RFC 4180（CRLF 改行、`,` / `"` / 改行を含むフィールドは `"` で囲み `""` でエスケープ）。
+    // TSV は引用符を持たないため、タブと改行は空白に置き換える。
+    fn write_row(self, out: &mut String, fields: &[String]) {
+        for (i, field) in fields.iter().enumerate() {
+            match self {
+                TableFormat::Csv => {
+                    if i > 0 {
+                        out.push(',');
+                    }
+                    if field.contains([',', '"', '\r', '\n']) {
+                        out.push('"');
+                        out.push_str(&field.replace('"', "\"\""));
+                        out.push('"');
+                    } else {
+                        out.push_str(field);
+                    }
+                }
+                TableFormat::Tsv => {
+                    if i > 0 {
+                        out.push('\t');
+                    }
+                    out.extend(field.chars().map(|c| match c {
+                        '\t' | '\r' | '\n' => ' ',
+                        c => c,
+                    }));
+                }
+            }
+        }
+        out.push_str(match self {
+            TableFormat::Csv => "\r\n",
+            TableFormat::Tsv => "\n",
+        });
+    }
+}
+
+pub(crate) fn format_findings_table(report: &Report, format: TableFormat) -> String {
+    let mut findings: Vec<&Finding> = report.findings.iter().collect();
+    findings.sort_by_key(|f| (std::cmp::Reverse(f.estimated_bytes), f.id.as_str()));
+
+    let mut out = String::new();
+    format.write_row(
+        &mut out,
+        &[
+            "id",
+            "type",
+            "title",
+            "estimated_bytes",
+            "confidence",
+            "risk_level",
+            "recommended_actions",
+        ]
+        .map(String::from),
+    );
+    for f in findings {
+        let mut actions: Vec<&str> = f
+            .recommended_actions
+            .iter()
+            .map(|a| a.id.as_str())
+            .collect();
+        actions.sort();
+        format.write_row(
+            &mut out,
+            &[
+                f.id.clone(),
+                f.finding_type.clone(),
+                f.title.clone(),
+                f.estimated_bytes.to_string(),
+                format!("{:.2}", f.confidence),
+                f.risk_level.to_string(),
+                actions.join(";"),
+            ],
+        );
+    }
+    out
+}
+
+pub(crate) fn format_actions_table(report: &Report, format: TableFormat) -> String {
+    let mut actions: Vec<&ActionPlan> = report.actions.iter().collect();
+    actions.sort_by_key(|a| {
+        (
+            a.risk_level,
+            std::cmp::Reverse(a.estimated_reclaimed_bytes),
+            a.id.as_str(),
+        )
+    });
+
+    let mut out = String::new();
+    format.write_row(
+        &mut out,
+        &[
+            "id",
+            "title",
+            "risk_level",
+            "estimated_reclaimed_bytes",
+            "kind",
+            "command",
+            "paths",
+            "related_findings",
+        ]
+        .map(String::from),
+    );
+    for a in actions {
+        let (kind, command, paths) = match &a.kind {
+            ActionKind::TrashMove { paths } => ("TRASH_MOVE", String::new(), sorted_join(paths)),
+            ActionKind::Delete { paths } => ("DELETE", String::new(), sorted_join(paths)),
+            ActionKind::RunCmd { cmd, args } => {
+                ("RUN_CMD", super::format_cmdline(cmd, args), String::new())
+            }
+            ActionKind::OpenInFinder { path } => ("OPEN_IN_FINDER", String::new(), path.clone()),
+            ActionKind::ShowInstructions { .. } => {
+                ("SHOW_INSTRUCTIONS", String::new(), String::new())
+            }
+        };
+        format.write_row(
+            &mut out,
+            &[
+                a.id.clone(),
+                a.title.clone(),
+                a.risk_level.to_string(),
+                a.estimated_reclaimed_bytes.to_string(),
+                kind.to_string(),
+                command,
+                paths,
+                sorted_join(&a.related_findings),
+            ],
+        );
+    }
+    out
+}
+
+fn sorted_join(items: &[String]) -> String {
+    let mut items: Vec<&str> = items.iter().map(|s| s.as_str()).collect();
+    items.sort();
+    items.join(";")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn write_row_quotes_csv_per_rfc4180_and_flattens_tsv() {
+        let fields = [
+            "plain".to_string(),
+            "a,b".to_string(),
+            "say \"hi\"".to_string(),
+            "two\nlines".to_string(),
+        ];
+        let mut csv = String::new();
+        TableFormat::Csv.write_row(&mut csv, &fields);
+        assert_eq!(csv, "plain,\"a,b\",\"say \"\"hi\"\"\",\"two\nlines\"\r\n");
+
+        let mut tsv = String::new();
+        TableFormat::Tsv.write_row(&mut tsv, &["a\tb".to_string(), "c\nd".to_string()]);
+        assert_eq!(tsv, "a b\tc d\n");
+    }
+}
@@ -12,6 +12,8 @@ pub struct EffectiveConfig {
     pub fix: FixConfig,
     pub privacy: PrivacyConfig,
     pub report: ReportConfig,
+    pub history: HistoryConfig,
+    pub rules: RulesConfig,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub config_path: Option<String>,
 }
@@ -26,6 +28,8 @@ pub struct UiConfig {
 pub struct ScanConfig {
     pub default_scope: String,
     pub exclude: Vec<String>,
+    pub source_roots: Vec<String>,
+    pub sizer: crate::scan::Sizer,
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -36,11 +40,45 @@ pub struct FixConfig {
 #[derive(Debug, Clone, Serialize)]
 pub struct PrivacyConfig {
     pub mask_home: bool,
+    // 未指定なら mask_home から決まる（true=home / false=none）。指定時は mask_home より優先。
+    pub redaction: crate::privacy::Redaction,
+}
+
+impl PrivacyConfig {
+    fn set_mask_home(&mut self, mask_home: bool) {
+        // mask_home = true は strict を弱めない。
+        self.mask_home = mask_home;
+        if !mask_home {
+            self.redaction = crate::privacy::Redaction::None;
+        } else if self.redaction == crate::privacy::Redaction::None {
+            self.redaction = crate::privacy::Redaction::Home;
+        }
+    }
+
+    fn set_redaction(&mut self, redaction: crate::privacy::Redaction) {
+        self.redaction = redaction;
+        self.mask_home = redaction != crate::privacy::Redaction::None;
+    }
 }
 
 #[derive(Debug, Clone, Serialize)]
 pub struct ReportConfig {
     pub include_evidence: bool,
+    pub include_host: bool,
+}
+
+// doctor/scan のレポートを ~/.config/macdiet/history/ に残すか。max_runs / max_age_days は 0 で無制限。
+#[derive(Debug, Clone, Serialize)]
+pub struct HistoryConfig {
+    pub enabled: bool,
+    pub max_runs: usize,
+    pub max_age_days: u64,
+}
+
+// 所見ルールのしきい値。
+#[derive(Debug, Clone, Serialize)]
+pub struct RulesConfig {
+    pub cargo_stale_crate_days: u64,
 }
 
 impl Default for EffectiveConfig {
@@ -53,13 +91,27 @@ impl Default for EffectiveConfig {
             scan: ScanConfig {
                 default_scope: "dev".to_string(),
                 exclude: vec!["**/node_modules/**".to_string()],
+                source_roots: vec![],
+                sizer: crate::scan::Sizer::Native,
             },
             fix: FixConfig {
                 default_risk_max: RiskLevel::R1,
             },
-            privacy: PrivacyConfig { mask_home: true },
+            privacy: PrivacyConfig {
+                mask_home: true,
+                redaction: crate::privacy::Redaction::Home,
+            },
             report: ReportConfig {
                 include_evidence: false,
+                include_host: false,
+            },
+            history: HistoryConfig {
+                enabled: false,
+                max_runs: 100,
+                max_age_days: 180,
+            },
+            rules: RulesConfig {
+                cargo_stale_crate_days: 90,
             },
             config_path: None,
         }
@@ -73,6 +125,8 @@ struct RawConfig {
     fix: Option<RawFixConfig>,
     privacy: Option<RawPrivacyConfig>,
     report: Option<RawReportConfig>,
+    history: Option<RawHistoryConfig>,
+    rules: Option<RawRulesConfig>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -85,6 +139,8 @@ struct RawUiConfig {
 struct RawScanConfig {
     default_scope: Option<String>,
     exclude: Option<Vec<String>>,
+    source_roots: Option<Vec<String>>,
+    sizer: Option<crate::scan::Sizer>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -95,17 +151,43 @@ struct RawFixConfig {
 #[derive(Debug, Deserialize)]
 struct RawPrivacyConfig {
     mask_home: Option<bool>,
+    redaction: Option<crate::privacy::Redaction>,
 }
 
 #[derive(Debug, Deserialize)]
 struct RawReportConfig {
     include_evidence: Option<bool>,
+    include_host: Option<bool>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RawHistoryConfig {
+    enabled: Option<bool>,
+    max_runs: Option<usize>,
+    max_age_days: Option<u64>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RawRulesConfig {
+    cargo_stale_crate_days: Option<u64>,
 }
 
 pub fn default_config_path(home_dir: &Path) -> PathBuf {
     home_dir.join(".config/macdiet/config.toml")
 }
 
+pub fn expand_home_path(path: &str, home_dir: &Path) -> PathBuf {
+    let path = path.trim();
+    if path == "~" {
+        return home_dir.to_path_buf();
+    }
+    if let Some(rest) = path.strip_prefix("~/") {
+        return home_dir.join(rest);
+    }
+    let p = PathBuf::from(path);
+    if p.is_absolute() { p } else { home_dir.join(p) }
+}
+
 pub fn load(config_path: Option<&Path>, home_dir: &Path) -> Result<EffectiveConfig> {
     let mut cfg = EffectiveConfig::default();
 
@@ -144,6 +226,12 @@ fn apply_raw_config(cfg: &mut EffectiveConfig, raw: RawConfig) {
         if let Some(exclude) = scan.exclude {
             cfg.scan.exclude = exclude;
         }
+        if let Some(source_roots) = scan.source_roots {
+            cfg.scan.source_roots = source_roots;
+        }
+        if let Some(sizer) = scan.sizer {
+            cfg.scan.sizer = sizer;
+        }
     }
 
     if let Some(fix) = raw.fix {
@@ -154,7 +242,10 @@ fn apply_raw_config(cfg: &mut EffectiveConfig, raw: RawConfig) {
 
     if let Some(privacy) = raw.privacy {
         if let Some(mask_home) = privacy.mask_home {
-            cfg.privacy.mask_home = mask_home;
+            cfg.privacy.set_mask_home(mask_home);
+        }
+        if let Some(redaction) = privacy.redaction {
+            cfg.privacy.set_redaction(redaction);
         }
     }
 
@@ -162,6 +253,27 @@ fn apply_raw_config(cfg: &mut EffectiveConfig, raw: RawConfig) {
         if let Some(include_evidence) = report.include_evidence {
             cfg.report.include_evidence = include_evidence;
         }
+        if let Some(include_host) = report.include_host {
+            cfg.report.include_host = include_host;
+        }
+    }
+
+    if let Some(history) = raw.history {
+        if let Some(enabled) = history.enabled {
+            cfg.history.enabled = enabled;
+        }
+        if let Some(max_runs) = history.max_runs {
+            cfg.history.max_runs = max_runs;
+        }
+        if let Some(max_age_days) = history.max_age_days {
+            cfg.history.max_age_days = max_age_days;
+        }
+    }
+
+    if let Some(rules) = raw.rules
+        && let Some(days) = rules.cargo_stale_crate_days
+    {
+        cfg.rules.cargo_stale_crate_days = days;
     }
 }
 
@@ -192,6 +304,23 @@ fn apply_env_overrides(cfg: &mut EffectiveConfig) -> Result<()> {
             cfg.scan.exclude = parts;
         }
     }
+    if let Ok(v) = std::env::var("MACDIET_SCAN_SOURCE_ROOTS") {
+        let parts: Vec<String> = v
+            .split(',')
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+            .map(|s| s.to_string())
+            .collect();
+        if !parts.is_empty() {
+            cfg.scan.source_roots = parts;
+        }
+    }
+    if let Ok(v) = std::env::var("MACDIET_SCAN_SIZER") {
+        cfg.scan.sizer = v
+            .parse::<crate::scan::Sizer>()
+            .map_err(anyhow::Error::msg)
+            .with_context(|| "MACDIET_SCAN_SIZER")?;
+    }
     if let Ok(v) = std::env::var("MACDIET_FIX_DEFAULT_RISK_MAX") {
         cfg.fix.default_risk_max = v
             .parse::<RiskLevel>()
@@ -199,12 +328,44 @@ fn apply_env_overrides(cfg: &mut EffectiveConfig) -> Result<()> {
             .with_context(|| "MACDIET_FIX_DEFAULT_RISK_MAX")?;
     }
     if let Ok(v) = std::env::var("MACDIET_PRIVACY_MASK_HOME") {
-        cfg.privacy.mask_home = parse_bool(&v).with_context(|| "MACDIET_PRIVACY_MASK_HOME")?;
+        cfg.privacy
+            .set_mask_home(parse_bool(&v).with_context(|| "MACDIET_PRIVACY_MASK_HOME")?);
+    }
+    if let Ok(v) = std::env::var("MACDIET_PRIVACY_REDACTION") {
+        cfg.privacy.set_redaction(
+            v.parse::<crate::privacy::Redaction>()
+                .map_err(anyhow::Error::msg)
+                .with_context(|| "MACDIET_PRIVACY_REDACTION")?,
+        );
     }
     if let Ok(v) = std::env::var("MACDIET_REPORT_INCLUDE_EVIDENCE") {
         cfg.report.include_evidence =
             parse_bool(&v).with_context(|| "MACDIET_REPORT_INCLUDE_EVIDENCE")?;
     }
+    if let Ok(v) = std::env::var("MACDIET_REPORT_INCLUDE_HOST") {
+        cfg.report.include_host = parse_bool(&v).with_context(|| "MACDIET_REPORT_INCLUDE_HOST")?;
+    }
+    if let Ok(v) = std::env::var("MACDIET_HISTORY_ENABLED") {
+        cfg.history.enabled = parse_bool(&v).with_context(|| "MACDIET_HISTORY_ENABLED")?;
+    }
+    if let Ok(v) = std::env::var("MACDIET_HISTORY_MAX_RUNS") {
+        cfg.history.max_runs = v
+            .trim()
+            .parse::<usize>()
+            .with_context(|| "MACDIET_HISTORY_MAX_RUNS")?;
+    }
+    if let Ok(v) = std::env::var("MACDIET_HISTORY_MAX_AGE_DAYS") {
+        cfg.history.max_age_days = v
+            .trim()
+            .parse::<u64>()
+            .with_context(|| "MACDIET_HISTORY_MAX_AGE_DAYS")?;
+    }
+    if let Ok(v) = std::env::var("MACDIET_RULES_CARGO_STALE_CRATE_DAYS") {
+        cfg.rules.cargo_stale_crate_days = v
+            .trim()
+            .parse::<u64>()
+            .with_context(|| "MACDIET_RULES_CARGO_STALE_CRATE_DAYS")?;
+    }
 
     Ok(())
 }
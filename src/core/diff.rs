@@ -0,0 +1,128 @@
+use std::collections::HashMap;
+
+use crate::core::{Finding, Report};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FindingChange {
+    Grown,
+    Shrunk,
+    New,
+    Disappeared,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BytesDelta {
+    pub old: u64,
+    pub new: u64,
+    pub delta: i64,
+}
+
+impl BytesDelta {
+    pub fn between(old: u64, new: u64) -> Self {
+        let delta = i128::from(new) - i128::from(old);
+        Self {
+            old,
+            new,
+            delta: i64::try_from(delta).unwrap_or(if delta < 0 { i64::MIN } else { i64::MAX }),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FindingDelta {
+    pub id: String,
+    #[serde(rename = "type")]
+    pub finding_type: String,
+    pub title: String,
+    pub change: FindingChange,
+    pub bytes: BytesDelta,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReportDiff {
+    pub schema_version: String,
+    pub old_generated_at: String,
+    pub new_generated_at: String,
+    pub estimated_total_bytes: BytesDelta,
+    pub unobserved_bytes: BytesDelta,
+    pub findings: Vec<FindingDelta>,
+    pub unchanged_count: usize,
+}
+
+impl ReportDiff {
+    pub fn changed(&self, change: FindingChange) -> impl Iterator<Item = &FindingDelta> {
+        self.findings.iter().filter(move |f| f.change == change)
+    }
+}
+
+// 所見は id で突き合わせる。サイズが同じものは unchanged_count にだけ数え、
+// 変化したものは差分の絶対値が大きい順に並べる。
+pub fn diff_reports(old: &Report, new: &Report) -> ReportDiff {
+    let old_by_id: HashMap<&str, &Finding> =
+        old.findings.iter().map(|f| (f.id.as_str(), f)).collect();
+    let new_by_id: HashMap<&str, &Finding> =
+        new.findings.iter().map(|f| (f.id.as_str(), f)).collect();
+
+    let mut findings = Vec::new();
+    let mut unchanged_count = 0;
+    for f in &new.findings {
+        let (change, old_bytes) = match old_by_id.get(f.id.as_str()) {
+            None => (FindingChange::New, 0),
+            Some(prev) if f.estimated_bytes > prev.estimated_bytes => {
+                (FindingChange::Grown, prev.estimated_bytes)
+            }
+            Some(prev) if f.estimated_bytes < prev.estimated_bytes => {
+                (FindingChange::Shrunk, prev.estimated_bytes)
+            }
+            Some(_) => {
+                unchanged_count += 1;
+                continue;
+            }
+        };
+        findings.push(FindingDelta {
+            id: f.id.clone(),
+            finding_type: f.finding_type.clone(),
+            title: f.title.clone(),
+            change,
+            bytes: BytesDelta::between(old_bytes, f.estimated_bytes),
+        });
+    }
+    for f in old
+        .findings
+        .iter()
+        .filter(|f| !new_by_id.contains_key(f.id.as_str()))
+    {
+        findings.push(FindingDelta {
+            id: f.id.clone(),
+            finding_type: f.finding_type.clone(),
+            title: f.title.clone(),
+            change: FindingChange::Disappeared,
+            bytes: BytesDelta::between(f.estimated_bytes, 0),
+        });
+    }
+    findings.sort_by(|a, b| {
+        b.bytes
+            .delta
+            .unsigned_abs()
+            .cmp(&a.bytes.delta.unsigned_abs())
+            .then_with(|| a.id.cmp(&b.id))
+    });
+
+    ReportDiff {
+        schema_version: new.schema_version.clone(),
+        old_generated_at: old.generated_at.clone(),
+        new_generated_at: new.generated_at.clone(),
+        estimated_total_bytes: BytesDelta::between(
+            old.summary.estimated_total_bytes,
+            new.summary.estimated_total_bytes,
+        ),
+        unobserved_bytes: BytesDelta::between(
+            old.summary.unobserved_bytes,
+            new.summary.unobserved_bytes,
+        ),
+        findings,
+        unchanged_count,
+    }
+}
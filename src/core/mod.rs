@@ -1,11 +1,16 @@
 mod action;
+mod diff;
 mod evidence;
 mod finding;
 mod report;
 mod risk;
 
 pub use action::{ActionKind, ActionPlan, ActionRef};
+pub use diff::{BytesDelta, FindingChange, FindingDelta, ReportDiff, diff_reports};
 pub use evidence::{Evidence, EvidenceKind};
 pub use finding::Finding;
-pub use report::{OsInfo, Report, ReportSummary};
+pub use report::{
+    AgeBytes, CategoryBytes, Classification, OsInfo, REPORT_SCHEMA_VERSION, Report, ReportSummary,
+    VolumeSummary,
+};
 pub use risk::RiskLevel;
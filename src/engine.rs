@@ -1,4 +1,5 @@
 use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::time::{Duration, Instant};
 
 use anyhow::{Context, Result};
@@ -13,20 +14,51 @@ use crate::rules::{RuleContext, RuleOutput};
 pub struct EngineOptions {
     pub timeout: Duration,
     pub privacy_mask_home: bool,
+    pub redaction: crate::privacy::Redaction,
     pub include_evidence: bool,
     pub show_progress: bool,
+    pub source_roots: Vec<PathBuf>,
+    pub use_cache: bool,
+    pub sizer: crate::scan::Sizer,
+    pub cargo_stale_crate_days: u64,
 }
 
 #[derive(Clone)]
 pub struct Engine {
     opts: EngineOptions,
     home_dir: PathBuf,
+    redactor: crate::privacy::Redactor,
+    events: Option<crate::events::EventSink>,
 }
 
 #[derive(Debug, Clone)]
 pub struct ScanRequest {
     pub scope: Option<String>,
     pub deep: bool,
+    pub duplicates: bool,
+    pub classify: bool,
+    // None のときはスコープに応じて決める（all-readable のみ既定で有効）。
+    pub one_file_system: Option<bool>,
+    pub volumes: Vec<String>,
+    pub max_depth: usize,
+    pub top_dirs: usize,
+    pub exclude: Vec<String>,
+    pub export_ncdu: Option<PathBuf>,
+    pub import_ncdu: Option<PathBuf>,
+    pub show_progress: bool,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct FoldedStacks {
+    pub lines: Vec<String>,
+    pub notes: Vec<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct GrowthRequest {
+    pub scope: Option<String>,
+    pub interval: Duration,
+    pub duration: Duration,
     pub max_depth: usize,
     pub top_dirs: usize,
     pub exclude: Vec<String>,
@@ -36,7 +68,21 @@ pub struct ScanRequest {
 impl Engine {
     pub fn new(opts: EngineOptions) -> Result<Self> {
         let home_dir = crate::platform::effective_home_dir()?;
-        Ok(Self { opts, home_dir })
+        let redactor = crate::privacy::Redactor::load(opts.redaction, &home_dir)?;
+        Ok(Self {
+            opts,
+            home_dir,
+            redactor,
+            events: None,
+        })
+    }
+
+    // 進捗イベントの送り先を付けた Engine を返す（`--events` と TUI の実行中画面で使う）。
+    pub fn with_events(&self, events: crate::events::EventSink) -> Self {
+        Self {
+            events: Some(events),
+            ..self.clone()
+        }
     }
 
     pub fn timeout(&self) -> Duration {
@@ -47,13 +93,24 @@ impl Engine {
         &self.home_dir
     }
 
+    // 共有される出力（レポート・ログ）に使う privacy.redaction。
+    pub fn redactor(&self) -> &crate::privacy::Redactor {
+        &self.redactor
+    }
+
     pub fn doctor(&self) -> Result<Report> {
         let deadline = Instant::now() + self.opts.timeout;
+        let size_cache = self.load_size_cache();
         let ctx = RuleContext {
             home_dir: self.home_dir.clone(),
             timeout: std::cmp::min(self.opts.timeout, Duration::from_secs(8)),
             deadline: Some(deadline),
             privacy_mask_home: self.opts.privacy_mask_home,
+            source_roots: self.opts.source_roots.clone(),
+            size_cache: size_cache.clone(),
+            sizer: self.opts.sizer,
+            cargo_stale_crate_days: self.opts.cargo_stale_crate_days,
+            events: self.events.clone(),
         };
         use std::io::IsTerminal;
         let progress_enabled = self.opts.show_progress && std::io::stderr().is_terminal();
@@ -73,15 +130,14 @@ impl Engine {
         if let Some(pb) = pb {
             pb.finish_and_clear();
         }
-        Ok(self.report_from_outputs(
-            outputs,
-            vec![
-                "System Data は、他カテゴリに属さない Apple/サードパーティのファイルをまとめた一般カテゴリです（Appleの定義に従う）。"
-                    .to_string(),
-                "中身は雑多で変動するため、macdiet は開発者環境で頻出の原因を推定し、原因カテゴリへ再分類して提示します。"
-                    .to_string(),
-            ],
-        ))
+        let mut notes = vec![
+            "System Data は、他カテゴリに属さない Apple/サードパーティのファイルをまとめた一般カテゴリです（Appleの定義に従う）。"
+                .to_string(),
+            "中身は雑多で変動するため、macdiet は開発者環境で頻出の原因を推定し、原因カテゴリへ再分類して提示します。"
+                .to_string(),
+        ];
+        self.finish_size_cache(size_cache.as_deref(), &mut notes);
+        Ok(self.ready(self.report_from_outputs(outputs, notes)))
     }
 
     pub fn snapshots_status(&self) -> Result<Report> {
@@ -90,13 +146,18 @@ impl Engine {
             timeout: self.opts.timeout,
             deadline: None,
             privacy_mask_home: self.opts.privacy_mask_home,
+            source_roots: self.opts.source_roots.clone(),
+            size_cache: None,
+            sizer: self.opts.sizer,
+            cargo_stale_crate_days: self.opts.cargo_stale_crate_days,
+            events: self.events.clone(),
         };
         let outputs = crate::rules::snapshots_rules(&ctx);
-        Ok(self.report_from_outputs(outputs, vec![
+        Ok(self.ready(self.report_from_outputs(outputs, vec![
             "ローカルスナップショットは容量が必要な場合などに自動削除されることがあります（Apple の説明に従う）。".to_string(),
             "APFS スナップショットは Disk Utility で閲覧/削除できます（ツールはまずGUI導線を提示する）。".to_string(),
             "CLI からの thin/delete は R3（強い同意と慎重な運用が必要）。".to_string(),
-        ]))
+        ])))
     }
 
     pub fn report(&self) -> Result<Report> {
@@ -104,24 +165,54 @@ impl Engine {
     }
 
     pub fn scan(&self, req: ScanRequest) -> Result<Report> {
-        if !req.deep {
+        if let Some(path) = &req.import_ncdu {
+            return self.scan_import_ncdu(&req, path).map(|r| self.ready(r));
+        }
+        if !req.deep && !req.duplicates {
             return self.doctor();
         }
 
         use std::io::IsTerminal;
         let progress_enabled = req.show_progress && std::io::stderr().is_terminal();
 
-        let roots = self.resolve_scan_roots(req.scope.as_deref());
+        let deadline = Instant::now() + self.opts.timeout;
+        let limits = crate::scan::WalkLimits {
+            deadline: Some(deadline),
+            one_file_system: self.one_file_system(&req),
+            cancel: None,
+        };
+        let size_cache = self.load_size_cache();
         let mut findings = Vec::new();
         let mut notes = vec![
             format!(
-                "スキャン: deep=true max_depth={} top_dirs={}",
-                req.max_depth, req.top_dirs
+                "スキャン: deep={} duplicates={} max_depth={} top_dirs={} one_file_system={}",
+                req.deep, req.duplicates, req.max_depth, req.top_dirs, limits.one_file_system
             ),
             format!("スキャン: excludes={:?}", req.exclude),
         ];
+        let volumes = self.resolve_volumes(&req.volumes, &mut notes);
+        let roots = if req.volumes.is_empty() {
+            self.resolve_scan_roots(req.scope.as_deref())
+        } else {
+            volumes.iter().map(|v| v.0.clone()).collect()
+        };
+        let mut volume_summaries = Vec::new();
 
-        for root in roots {
+        if req.duplicates {
+            findings.extend(self.scan_duplicates(
+                &req,
+                &roots,
+                limits,
+                progress_enabled,
+                &mut notes,
+            )?);
+        }
+
+        let mut export_trees: Option<Vec<(PathBuf, crate::scan::TreeNode)>> =
+            req.export_ncdu.as_ref().map(|_| Vec::new());
+        let mut any_truncated = false;
+        let deep_roots = if req.deep { roots.clone() } else { Vec::new() };
+        for root in deep_roots {
             if !root.exists() {
                 notes.push(format!(
                     "スキャン: スコープが存在しません: {}",
@@ -130,59 +221,670 @@ impl Engine {
                 continue;
             }
 
+            let masked_root = mask_home(&root, &self.home_dir, true);
             let pb = if progress_enabled {
                 let pb = indicatif::ProgressBar::new_spinner();
                 pb.set_draw_target(indicatif::ProgressDrawTarget::stderr());
-                pb.set_message(format!(
-                    "スキャン中 {}",
-                    mask_home(&root, &self.home_dir, true)
-                ));
+                pb.set_message(format!("スキャン中 {masked_root}"));
                 pb.enable_steady_tick(Duration::from_millis(120));
                 Some(pb)
             } else {
                 None
             };
+            let progress = (pb.is_some() || self.events.is_some()).then(|| {
+                let pb = pb.clone();
+                let events = self.events.clone();
+                let masked_root = masked_root.clone();
+                let event_root = self.redactor.path(&masked_root);
+                move |files: u64, bytes: u64| {
+                    if let Some(pb) = &pb {
+                        pb.set_message(format!("スキャン中 {masked_root}（{files} files）"));
+                    }
+                    if let Some(events) = &events {
+                        events.emit(crate::events::Event::ScanProgress {
+                            root: event_root.clone(),
+                            files,
+                            bytes,
+                        });
+                    }
+                }
+            });
 
-            let result =
-                crate::scan::top_directories(&root, req.max_depth, req.top_dirs, &req.exclude)
+            let result = match export_trees.as_mut() {
+                Some(trees) => {
+                    let built = crate::scan::build_tree(&root, &req.exclude, limits)
+                        .with_context(|| format!("スキャン: {}", root.display()))?;
+                    let mut result = crate::scan::top_directories_from_tree(
+                        &built.tree,
+                        &root,
+                        req.max_depth,
+                        req.top_dirs,
+                        &req.exclude,
+                    )
                     .with_context(|| format!("スキャン: {}", root.display()))?;
+                    result.error_count = built.error_count;
+                    result.method = built.method;
+                    trees.push((root.clone(), built.tree));
+                    result
+                }
+                None => crate::scan::top_directories(
+                    &root,
+                    req.max_depth,
+                    req.top_dirs,
+                    &req.exclude,
+                    limits,
+                    progress.as_ref().map(|f| f as &(dyn Fn(u64, u64) + Sync)),
+                    size_cache.as_deref(),
+                )
+                .with_context(|| format!("スキャン: {}", root.display()))?,
+            };
 
             if let Some(pb) = pb {
                 pb.finish_and_clear();
             }
 
-            notes.push(format!(
-                "スキャン: {} files={} errors={}",
-                mask_home(&result.root, &self.home_dir, true),
-                result.file_count,
-                result.error_count
-            ));
+            if result.method == crate::scan::SizeEstimateMethod::WalkDirTruncated {
+                any_truncated = true;
+                notes.push(format!(
+                    "スキャン: 時間制限（--timeout {}s）に達したため {} の走査を途中で打ち切りました。結果は下限推定です。",
+                    self.opts.timeout.as_secs(),
+                    mask_home(&result.root, &self.home_dir, true)
+                ));
+            }
 
-            for entry in result.entries {
-                let masked = mask_home(&entry.path, &self.home_dir, true);
-                let id = format!("scan-top:{masked}");
-                findings.push(crate::core::Finding {
-                    id,
-                    finding_type: "SCAN_TOP_DIR".to_string(),
-                    title: format!("上位ディレクトリ: {masked}"),
-                    estimated_bytes: entry.bytes,
-                    confidence: if result.error_count == 0 { 0.9 } else { 0.5 },
-                    risk_level: crate::core::RiskLevel::R0,
-                    evidence: vec![
-                        crate::core::Evidence::path(masked, true),
-                        crate::core::Evidence::stat(format!(
-                            "root={} max_depth={}",
-                            mask_home(&result.root, &self.home_dir, true),
-                            req.max_depth
-                        )),
-                    ],
-                    recommended_actions: vec![],
+            if let Some((_, info)) = volumes.iter().find(|(path, _)| *path == root) {
+                volume_summaries.push(crate::core::VolumeSummary {
+                    path: mask_home(&root, &self.home_dir, true),
+                    mount_point: info
+                        .as_ref()
+                        .map(|i| i.mount_point.display().to_string())
+                        .unwrap_or_default(),
+                    device: info.as_ref().map(|i| i.device.clone()).unwrap_or_default(),
+                    fs_type: info.as_ref().map(|i| i.fs_type.clone()).unwrap_or_default(),
+                    total_bytes: result.total_bytes,
+                    file_count: result.file_count,
+                    error_count: result.error_count,
                 });
             }
+            findings.extend(self.top_dir_findings(&req, result, &mut notes));
         }
 
+        if let (Some(path), Some(trees)) = (&req.export_ncdu, export_trees) {
+            self.write_ncdu_export(path, trees, any_truncated, &mut notes)?;
+        }
+
+        // 分類は全ファイルをもう一度走査するため、走査本体の残り時間を削らないよう別枠の時間制限で行う。
+        let classification = if req.deep && req.classify {
+            let limits = crate::scan::WalkLimits {
+                deadline: Some(Instant::now() + self.opts.timeout),
+                ..limits
+            };
+            Some(self.classify_roots(&req, &roots, limits, &mut notes)?)
+        } else {
+            None
+        };
+
         findings.sort_by_key(|f| std::cmp::Reverse(f.estimated_bytes));
+        self.finish_size_cache(size_cache.as_deref(), &mut notes);
+
+        let mut report = self.report_from_outputs(
+            findings
+                .into_iter()
+                .map(|finding| RuleOutput {
+                    finding,
+                    actions: vec![],
+                })
+                .collect(),
+            notes,
+        );
+        report.classification = classification;
+        report.volumes = volume_summaries;
+        Ok(self.ready(report))
+    }
+
+    fn one_file_system(&self, req: &ScanRequest) -> bool {
+        // ボリューム単位の集計は重複しないよう常にマウント境界で止める。
+        if !req.volumes.is_empty() {
+            return true;
+        }
+        req.one_file_system
+            .unwrap_or_else(|| req.scope.as_deref().map(str::trim) == Some("all-readable"))
+    }
+
+    fn resolve_volumes(
+        &self,
+        specs: &[String],
+        notes: &mut Vec<String>,
+    ) -> Vec<(PathBuf, Option<crate::platform::MountInfo>)> {
+        let mut paths = Vec::new();
+        for spec in specs {
+            let spec = spec.trim();
+            if spec == "all" {
+                paths.extend(crate::platform::external_volumes());
+            } else if spec.starts_with('/') {
+                paths.push(PathBuf::from(spec));
+            } else {
+                paths.push(PathBuf::from("/Volumes").join(spec));
+            }
+        }
+
+        let mut volumes: Vec<(PathBuf, Option<crate::platform::MountInfo>)> = Vec::new();
+        for path in paths {
+            if !path.is_dir() {
+                notes.push(format!("ボリューム: 見つかりません: {}", path.display()));
+                continue;
+            }
+            if volumes.iter().any(|(p, _)| *p == path) {
+                continue;
+            }
+            let info = crate::platform::mount_info(&path);
+            if info.is_none() {
+                notes.push(format!(
+                    "ボリューム: マウント情報を取得できませんでした: {}",
+                    path.display()
+                ));
+            }
+            volumes.push((path, info));
+        }
+        volumes
+    }
+
+    fn classify_roots(
+        &self,
+        req: &ScanRequest,
+        roots: &[PathBuf],
+        limits: crate::scan::WalkLimits,
+        notes: &mut Vec<String>,
+    ) -> Result<crate::core::Classification> {
+        let roots: Vec<PathBuf> = roots.iter().filter(|r| r.exists()).cloned().collect();
+        let result =
+            crate::scan::classify_files(&roots, &req.exclude, limits, std::time::SystemTime::now())
+                .context("スキャン: 分類")?;
+
+        // errors= は走査本体のノートで計上済みのため、ここでは別表記にして二重計上を避ける。
+        notes.push(format!(
+            "分類: files={} 読み取り失敗={}",
+            result.file_count, result.error_count
+        ));
+        notes.push(
+            "分類: atime はボリュームのマウント設定により更新されないことがあります（参考値）。"
+                .to_string(),
+        );
+        notes.push(format!(
+            "分類: 走査本体とは別に --timeout {}s の時間枠で集計しました（全体の所要時間は最大で約 2 倍になります）。",
+            self.opts.timeout.as_secs()
+        ));
+        if result.truncated {
+            notes.push(format!(
+                "分類: 時間制限（--timeout {}s）に達したため途中で打ち切りました。結果は一部です。",
+                self.opts.timeout.as_secs()
+            ));
+        }
+
+        let mut categories: Vec<crate::core::CategoryBytes> = crate::scan::FileCategory::ALL
+            .iter()
+            .filter_map(|c| {
+                let v = result.categories.get(c)?;
+                Some(crate::core::CategoryBytes {
+                    category: c.as_str().to_string(),
+                    bytes: v.bytes,
+                    files: v.files,
+                })
+            })
+            .collect();
+        categories.sort_by_key(|c| std::cmp::Reverse(c.bytes));
+        let ages = crate::scan::AGE_BUCKET_LABELS
+            .iter()
+            .enumerate()
+            .map(|(i, label)| crate::core::AgeBytes {
+                bucket: label.to_string(),
+                mtime_bytes: result.by_mtime[i].bytes,
+                atime_bytes: result.by_atime[i].bytes,
+            })
+            .collect();
+        Ok(crate::core::Classification { categories, ages })
+    }
+
+    // flamegraph.pl / inferno 向けの folded stack（`a;b;c bytes`）。値は各ディレクトリ直下の
+    // ファイル合計で、max_depth より深い分は max_depth の祖先に寄せる（親の合計は描画側で積み上がる）。
+    pub fn scan_folded(&self, req: ScanRequest) -> Result<FoldedStacks> {
+        let limits = crate::scan::WalkLimits {
+            deadline: Some(Instant::now() + self.opts.timeout),
+            one_file_system: self.one_file_system(&req),
+            cancel: None,
+        };
+        let size_cache = self.load_size_cache();
+        let mut out = FoldedStacks::default();
+
+        let results = match &req.import_ncdu {
+            Some(path) => {
+                let export = crate::ncdu::read_export(path)?;
+                let base = PathBuf::from(&export.tree.name);
+                let mut results = Vec::new();
+                for root in &export.roots {
+                    let Some(subtree) = root
+                        .strip_prefix(&base)
+                        .ok()
+                        .and_then(|rel| export.tree.find(rel))
+                    else {
+                        continue;
+                    };
+                    results.push(crate::scan::top_directories_from_tree(
+                        subtree,
+                        root,
+                        req.max_depth,
+                        req.top_dirs,
+                        &req.exclude,
+                    )?);
+                }
+                results
+            }
+            None => {
+                let roots = if req.volumes.is_empty() {
+                    self.resolve_scan_roots(req.scope.as_deref())
+                } else {
+                    self.resolve_volumes(&req.volumes, &mut out.notes)
+                        .into_iter()
+                        .map(|(path, _)| path)
+                        .collect()
+                };
+                let mut results = Vec::new();
+                for root in roots {
+                    if !root.exists() {
+                        continue;
+                    }
+                    let result = crate::scan::top_directories(
+                        &root,
+                        req.max_depth,
+                        req.top_dirs,
+                        &req.exclude,
+                        limits,
+                        None,
+                        size_cache.as_deref(),
+                    )
+                    .with_context(|| format!("スキャン: {}", root.display()))?;
+                    if result.method == crate::scan::SizeEstimateMethod::WalkDirTruncated {
+                        out.notes.push(format!(
+                            "スキャン: 時間制限（--timeout {}s）に達したため {} の走査を途中で打ち切りました。結果は下限推定です。",
+                            self.opts.timeout.as_secs(),
+                            mask_home(&result.root, &self.home_dir, true)
+                        ));
+                    }
+                    results.push(result);
+                }
+                results
+            }
+        };
+
+        for result in &results {
+            for entry in result.buckets(req.max_depth) {
+                let frames = self.folded_frames(&entry.path);
+                if entry.bytes > 0 && !frames.is_empty() {
+                    out.lines.push(format!("{frames} {}", entry.bytes));
+                }
+            }
+        }
+        self.finish_size_cache(size_cache.as_deref(), &mut out.notes);
+        Ok(out)
+    }
+
+    // interval ごとにスコープを走査し、最初と最後の有効なサンプルの差分から増加の速い
+    // ディレクトリを求める。stop が立ったら（Ctrl-C）その時点までのサンプルでレポートを返す。
+    pub fn growth(&self, req: GrowthRequest, stop: &'static AtomicBool) -> Result<Report> {
+        use std::io::IsTerminal;
+        let progress_enabled = req.show_progress && std::io::stderr().is_terminal();
+        let pb = if progress_enabled {
+            let pb = indicatif::ProgressBar::new_spinner();
+            pb.set_draw_target(indicatif::ProgressDrawTarget::stderr());
+            pb.enable_steady_tick(Duration::from_millis(120));
+            Some(pb)
+        } else {
+            None
+        };
+
+        let one_file_system = req.scope.as_deref().map(str::trim) == Some("all-readable");
+        let roots = self.resolve_scan_roots(req.scope.as_deref());
+        let mut notes = vec![format!(
+            "成長監視: interval={}s duration={}s max_depth={} top_dirs={} one_file_system={}",
+            req.interval.as_secs(),
+            req.duration.as_secs(),
+            req.max_depth,
+            req.top_dirs,
+            one_file_system
+        )];
+        for root in roots.iter().filter(|r| !r.exists()) {
+            notes.push(format!(
+                "成長監視: スコープが存在しません: {}",
+                root.display()
+            ));
+        }
+        let roots: Vec<PathBuf> = roots.into_iter().filter(|r| r.exists()).collect();
+
+        let started = Instant::now();
+        let end = started
+            .checked_add(req.duration)
+            .ok_or_else(|| crate::exit::invalid_args("growth: --duration が大きすぎます"))?;
+        let mut first: Option<(Instant, Vec<crate::scan::TopDirEntry>)> = None;
+        let mut last: Option<(Instant, Vec<crate::scan::TopDirEntry>)> = None;
+        let mut taken = 0u64;
+        let mut discarded = 0u64;
+        let mut interrupted = false;
+        for k in 1u32.. {
+            if let Some(pb) = &pb {
+                pb.set_message(format!(
+                    "成長監視中 サンプル {}（残り {}s）",
+                    taken + 1,
+                    end.saturating_duration_since(Instant::now()).as_secs()
+                ));
+            }
+            let at = Instant::now();
+            let limits = crate::scan::WalkLimits {
+                deadline: Some(at + self.opts.timeout),
+                one_file_system,
+                cancel: Some(stop),
+            };
+            match self.growth_sample(&req, &roots, limits)? {
+                Some(entries) => {
+                    taken += 1;
+                    if first.is_none() {
+                        first = Some((at, entries));
+                    } else {
+                        last = Some((at, entries));
+                    }
+                }
+                None => discarded += 1,
+            }
+            if stop.load(Ordering::SeqCst) {
+                interrupted = true;
+                break;
+            }
+            let Some(next) = req
+                .interval
+                .checked_mul(k)
+                .and_then(|d| started.checked_add(d))
+                .filter(|next| *next <= end)
+            else {
+                break;
+            };
+            if !sleep_until(next, stop) {
+                interrupted = true;
+                break;
+            }
+        }
+        if let Some(pb) = pb {
+            pb.finish_and_clear();
+        }
+
+        notes.push(format!(
+            "成長監視: samples={taken} 破棄={discarded} elapsed_secs={}",
+            started.elapsed().as_secs()
+        ));
+        if discarded > 0 {
+            notes.push(format!(
+                "成長監視: 時間制限（--timeout {}s）または中断で走査が途中までだったサンプルは比較に使っていません。",
+                self.opts.timeout.as_secs()
+            ));
+        }
+        if interrupted {
+            notes.push(
+                "中断: Ctrl-C を受け取ったため、それまでのサンプルで集計しました。".to_string(),
+            );
+        }
+
+        let mut findings = Vec::new();
+        match (first, last) {
+            (Some((start_at, start)), Some((end_at, end))) => {
+                let elapsed = end_at.saturating_duration_since(start_at);
+                let elapsed_secs = elapsed.as_secs_f64().max(1.0);
+                for entry in crate::scan::rank_growth(&start, &end, req.top_dirs) {
+                    let masked = mask_home(&entry.path, &self.home_dir, true);
+                    let growth = entry.growth_bytes();
+                    let rate = (growth as f64 * 3600.0 / elapsed_secs) as u64;
+                    findings.push(crate::core::Finding {
+                        id: format!("growth:{masked}"),
+                        finding_type: "GROWTH_HOTSPOT".to_string(),
+                        title: format!("増加中のディレクトリ: {masked}"),
+                        estimated_bytes: growth,
+                        confidence: 0.8,
+                        risk_level: crate::core::RiskLevel::R0,
+                        evidence: vec![
+                            crate::core::Evidence::path(masked.clone(), true),
+                            crate::core::Evidence::stat(format!(
+                                "start_bytes={} end_bytes={} elapsed_secs={} samples={} rate_bytes_per_hour={}",
+                                entry.start_bytes,
+                                entry.end_bytes,
+                                elapsed.as_secs(),
+                                taken,
+                                rate
+                            )),
+                        ],
+                        recommended_actions: vec![],
+                    });
+                }
+                if findings.is_empty() {
+                    notes.push(
+                        "成長監視: 監視期間中に増加したディレクトリはありませんでした。"
+                            .to_string(),
+                    );
+                }
+            }
+            _ => notes.push(
+                "成長監視: 有効なサンプルが 2 件未満のため、増加量を算出できませんでした。"
+                    .to_string(),
+            ),
+        }
+
+        let report = self.report_from_outputs(
+            findings
+                .into_iter()
+                .map(|finding| RuleOutput {
+                    finding,
+                    actions: vec![],
+                })
+                .collect(),
+            notes,
+        );
+        Ok(self.ready(report))
+    }
+
+    // 1 回分のサンプル。打ち切られた走査は下限値で増加量を誤るため None を返す。
+    fn growth_sample(
+        &self,
+        req: &GrowthRequest,
+        roots: &[PathBuf],
+        limits: crate::scan::WalkLimits,
+    ) -> Result<Option<Vec<crate::scan::TopDirEntry>>> {
+        let mut entries = Vec::new();
+        for root in roots {
+            let result = crate::scan::top_directories(
+                root,
+                req.max_depth,
+                req.top_dirs,
+                &req.exclude,
+                limits,
+                None,
+                None,
+            )
+            .with_context(|| format!("成長監視: {}", root.display()))?;
+            if result.method == crate::scan::SizeEstimateMethod::WalkDirTruncated {
+                return Ok(None);
+            }
+            entries.extend(result.buckets(req.max_depth));
+        }
+        Ok(Some(entries))
+    }
+
+    fn folded_frames(&self, path: &std::path::Path) -> String {
+        self.redactor
+            .path(&mask_home(
+                path,
+                &self.home_dir,
+                self.opts.privacy_mask_home,
+            ))
+            .split('/')
+            .filter(|c| !c.is_empty())
+            .map(|c| c.replace([';', '\n'], "_"))
+            .collect::<Vec<_>>()
+            .join(";")
+    }
+
+    fn top_dir_findings(
+        &self,
+        req: &ScanRequest,
+        result: crate::scan::TopDirsResult,
+        notes: &mut Vec<String>,
+    ) -> Vec<crate::core::Finding> {
+        notes.push(format!(
+            "スキャン: {} files={} errors={}",
+            mask_home(&result.root, &self.home_dir, true),
+            result.file_count,
+            result.error_count
+        ));
+        if !result.skipped_mounts.is_empty() {
+            let shown: Vec<String> = result
+                .skipped_mounts
+                .iter()
+                .take(5)
+                .map(|p| mask_home(p, &self.home_dir, true))
+                .collect();
+            notes.push(format!(
+                "スキャン: 別ボリュームのマウントポイントには降りませんでした（--one-file-system、{}件）: {}",
+                result.skipped_mounts.len(),
+                shown.join(", ")
+            ));
+        }
+        let truncated = result.method == crate::scan::SizeEstimateMethod::WalkDirTruncated;
+        let mut findings = Vec::new();
+        let cache_note = result.cached_at.map(|t| {
+            format!(
+                "cache_hits={} cache_age={}",
+                result.cache_hits,
+                crate::scan::format_cache_age(crate::cache::unix_now().saturating_sub(t))
+            )
+        });
+        for entry in result.entries {
+            let masked = mask_home(&entry.path, &self.home_dir, true);
+            let mut evidence = vec![
+                crate::core::Evidence::path(masked.clone(), true),
+                crate::core::Evidence::stat(format!(
+                    "root={} max_depth={} method={:?}",
+                    mask_home(&result.root, &self.home_dir, true),
+                    req.max_depth,
+                    result.method
+                )),
+            ];
+            if let Some(note) = &cache_note {
+                evidence.push(crate::core::Evidence::stat(note.clone()));
+            }
+            let id = format!("scan-top:{masked}");
+            findings.push(crate::core::Finding {
+                id,
+                finding_type: "SCAN_TOP_DIR".to_string(),
+                title: format!("上位ディレクトリ: {masked}"),
+                estimated_bytes: entry.bytes,
+                confidence: if truncated {
+                    0.3
+                } else if result.error_count == 0 {
+                    0.9
+                } else {
+                    0.5
+                },
+                risk_level: crate::core::RiskLevel::R0,
+                evidence,
+                recommended_actions: vec![],
+            });
+        }
+        findings
+    }
+
+    fn write_ncdu_export(
+        &self,
+        path: &std::path::Path,
+        mut trees: Vec<(PathBuf, crate::scan::TreeNode)>,
+        truncated: bool,
+        notes: &mut Vec<String>,
+    ) -> Result<()> {
+        let roots: Vec<PathBuf> = trees.iter().map(|(root, _)| root.clone()).collect();
+        let tree = if trees.len() == 1 {
+            trees.remove(0).1
+        } else {
+            // 複数 root は共通の親（home か /）の下に接ぎ木して 1 つのツリーにする。
+            let base = if roots.iter().all(|r| r.starts_with(&self.home_dir)) {
+                self.home_dir.clone()
+            } else {
+                PathBuf::from("/")
+            };
+            let mut tree = crate::scan::TreeNode {
+                name: base.display().to_string(),
+                ..crate::scan::TreeNode::default()
+            };
+            for (root, subtree) in trees {
+                if let Ok(rel) = root.strip_prefix(&base) {
+                    tree.graft(rel, subtree);
+                }
+            }
+            tree
+        };
 
+        crate::ncdu::write_export(
+            path,
+            &crate::ncdu::NcduExport {
+                tree,
+                roots,
+                timestamp: Some(crate::cache::unix_now() as i64),
+                truncated,
+            },
+        )?;
+        notes.push(format!(
+            "ncdu エクスポート: {} に書き出しました（`ncdu -f` で閲覧できます）。",
+            mask_home(path, &self.home_dir, true)
+        ));
+        Ok(())
+    }
+
+    fn scan_import_ncdu(&self, req: &ScanRequest, path: &std::path::Path) -> Result<Report> {
+        let export = crate::ncdu::read_export(path)?;
+        let base = PathBuf::from(&export.tree.name);
+        let mut findings = Vec::new();
+        let mut notes = vec![
+            format!(
+                "スキャン: ncdu エクスポート {} から集計しました（ディスクは再走査していません）。",
+                mask_home(path, &self.home_dir, true)
+            ),
+            format!(
+                "スキャン: max_depth={} top_dirs={}",
+                req.max_depth, req.top_dirs
+            ),
+            format!("スキャン: excludes={:?}", req.exclude),
+        ];
+
+        for root in &export.roots {
+            let Some(subtree) = root
+                .strip_prefix(&base)
+                .ok()
+                .and_then(|rel| export.tree.find(rel))
+            else {
+                notes.push(format!(
+                    "スキャン: エクスポートに含まれないため省略しました: {}",
+                    mask_home(root, &self.home_dir, true)
+                ));
+                continue;
+            };
+            let mut result = crate::scan::top_directories_from_tree(
+                subtree,
+                root,
+                req.max_depth,
+                req.top_dirs,
+                &req.exclude,
+            )?;
+            if export.truncated {
+                result.method = crate::scan::SizeEstimateMethod::WalkDirTruncated;
+                result.error_count = result.error_count.max(1);
+            }
+            findings.extend(self.top_dir_findings(req, result, &mut notes));
+        }
+
+        findings.sort_by_key(|f| std::cmp::Reverse(f.estimated_bytes));
         Ok(self.report_from_outputs(
             findings
                 .into_iter()
@@ -195,6 +897,124 @@ impl Engine {
         ))
     }
 
+    fn load_size_cache(&self) -> Option<std::sync::Arc<crate::cache::SizeCache>> {
+        self.opts
+            .use_cache
+            .then(|| std::sync::Arc::new(crate::cache::SizeCache::load(&self.home_dir)))
+    }
+
+    fn finish_size_cache(&self, cache: Option<&crate::cache::SizeCache>, notes: &mut Vec<String>) {
+        let Some(cache) = cache else {
+            return;
+        };
+        let stats = cache.stats();
+        if let Some(oldest) = stats.oldest_cached_at {
+            notes.push(format!(
+                "キャッシュ: {} ディレクトリの集計を再利用しました（最古 {} 前）。最新の値が必要な場合は `--no-cache` を指定してください。",
+                stats.hits,
+                crate::scan::format_cache_age(crate::cache::unix_now().saturating_sub(oldest))
+            ));
+        }
+        if let Err(err) = cache.save() {
+            notes.push(format!("キャッシュ: 保存に失敗しました: {err}"));
+        }
+    }
+
+    fn scan_duplicates(
+        &self,
+        req: &ScanRequest,
+        roots: &[PathBuf],
+        limits: crate::scan::WalkLimits,
+        progress_enabled: bool,
+        notes: &mut Vec<String>,
+    ) -> Result<Vec<crate::core::Finding>> {
+        let roots: Vec<PathBuf> = roots.iter().filter(|r| r.exists()).cloned().collect();
+
+        let pb = if progress_enabled {
+            let pb = indicatif::ProgressBar::new_spinner();
+            pb.set_draw_target(indicatif::ProgressDrawTarget::stderr());
+            pb.set_message("重複ファイルを検索中...");
+            pb.enable_steady_tick(Duration::from_millis(120));
+            Some(pb)
+        } else {
+            None
+        };
+
+        let result = crate::scan::find_duplicates(
+            &roots,
+            crate::scan::DUPLICATE_MIN_BYTES,
+            &req.exclude,
+            limits,
+        )
+        .context("スキャン: 重複ファイル検出")?;
+
+        if let Some(pb) = pb {
+            pb.finish_and_clear();
+        }
+
+        notes.push(format!(
+            "重複検出: files={} errors={} sets={} min_bytes={}",
+            result.file_count,
+            result.error_count,
+            result.sets.len(),
+            crate::scan::DUPLICATE_MIN_BYTES
+        ));
+        if result.truncated {
+            notes.push(format!(
+                "重複検出: 時間制限（--timeout {}s）に達したため途中で打ち切りました。結果は一部です。",
+                self.opts.timeout.as_secs()
+            ));
+        }
+
+        let mut findings = Vec::new();
+        for set in result.sets.into_iter().take(req.top_dirs.max(1)) {
+            let masked: Vec<String> = set
+                .paths
+                .iter()
+                .map(|p| mask_home(p, &self.home_dir, true))
+                .collect();
+            let mut evidence: Vec<crate::core::Evidence> = masked
+                .iter()
+                .map(|p| crate::core::Evidence::path(p.clone(), true))
+                .collect();
+            evidence.push(crate::core::Evidence::stat(format!(
+                "copies={} file_bytes={} wasted_bytes={} blake3={}",
+                set.paths.len(),
+                set.file_bytes,
+                set.wasted_bytes(),
+                set.hash
+            )));
+            findings.push(crate::core::Finding {
+                id: format!("scan-duplicates:{}", &set.hash[..16]),
+                finding_type: "SCAN_DUPLICATE_FILES".to_string(),
+                title: format!(
+                    "重複ファイル {} 件（各 {}）: {}",
+                    set.paths.len(),
+                    crate::ui::format_bytes(set.file_bytes),
+                    masked[0]
+                ),
+                estimated_bytes: set.wasted_bytes(),
+                confidence: 0.95,
+                risk_level: crate::core::RiskLevel::R0,
+                evidence,
+                recommended_actions: vec![],
+            });
+        }
+        Ok(findings)
+    }
+
+    fn ready(&self, report: Report) -> Report {
+        if let Some(events) = &self.events {
+            events.emit(crate::events::Event::ReportReady {
+                findings: report.findings.len(),
+                actions: report.actions.len(),
+                estimated_total_bytes: report.summary.estimated_total_bytes,
+                unobserved_bytes: report.summary.unobserved_bytes,
+            });
+        }
+        report
+    }
+
     fn report_from_outputs(&self, mut outputs: Vec<RuleOutput>, mut notes: Vec<String>) -> Report {
         outputs.sort_by_key(|o| std::cmp::Reverse(o.finding.estimated_bytes));
 
@@ -240,8 +1060,9 @@ impl Engine {
         notes.dedup();
 
         Report {
-            schema_version: "1.0".to_string(),
+            schema_version: crate::core::REPORT_SCHEMA_VERSION.to_string(),
             tool_version: env!("CARGO_PKG_VERSION").to_string(),
+            host: None,
             os,
             generated_at,
             summary: ReportSummary {
@@ -251,6 +1072,8 @@ impl Engine {
             },
             findings,
             actions,
+            classification: None,
+            volumes: vec![],
         }
     }
 
@@ -280,6 +1103,20 @@ impl Engine {
     }
 }
 
+// deadline まで待つ。stop が立ったら false を返す。
+fn sleep_until(deadline: Instant, stop: &AtomicBool) -> bool {
+    loop {
+        if stop.load(Ordering::SeqCst) {
+            return false;
+        }
+        let left = deadline.saturating_duration_since(Instant::now());
+        if left.is_zero() {
+            return true;
+        }
+        std::thread::sleep(left.min(Duration::from_millis(200)));
+    }
+}
+
 fn mask_home(path: &std::path::Path, home_dir: &std::path::Path, mask_home: bool) -> String {
     if !mask_home {
         return path.display().to_string();
@@ -0,0 +1,250 @@
+use std::io::Write;
+use std::sync::{Arc, Mutex};
+
+use serde::Serialize;
+
+// doctor / scan の進捗イベント。`--events ndjson` では 1 行 1 JSON で書き出し、
+// TUI の実行中画面も同じイベントから Progress を組み立てる。
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum Event {
+    RuleStarted {
+        rule: String,
+    },
+    RuleFinished {
+        rule: String,
+        findings: usize,
+        bytes: u64,
+        duration_ms: u64,
+    },
+    ScanProgress {
+        root: String,
+        files: u64,
+        bytes: u64,
+    },
+    ReportReady {
+        findings: usize,
+        actions: usize,
+        estimated_total_bytes: u64,
+        unobserved_bytes: u64,
+    },
+}
+
+#[derive(Clone)]
+pub struct EventSink(Arc<dyn Fn(&Event) + Send + Sync>);
+
+impl EventSink {
+    pub fn new(f: impl Fn(&Event) + Send + Sync + 'static) -> Self {
+        Self(Arc::new(f))
+    }
+
+    // 書き込みに失敗しても本処理は止めない（読み手が先に終了した場合など）。
+    pub fn ndjson(out: impl Write + Send + 'static) -> Self {
+        let out = Mutex::new(out);
+        Self::new(move |event| {
+            let Ok(mut line) = serde_json::to_vec(event) else {
+                return;
+            };
+            line.push(b'\n');
+            if let Ok(mut out) = out.lock() {
+                let _ = out.write_all(&line).and_then(|()| out.flush());
+            }
+        })
+    }
+
+    pub fn emit(&self, event: Event) {
+        (self.0)(&event);
+    }
+}
+
+impl std::fmt::Debug for EventSink {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("EventSink")
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Progress {
+    pub current_rule: Option<String>,
+    pub rules_finished: usize,
+    pub findings: usize,
+    pub bytes: u64,
+    pub scan_root: Option<String>,
+    pub scan_files: u64,
+    pub scan_bytes: u64,
+    pub ready: bool,
+}
+
+impl Progress {
+    pub fn apply(&mut self, event: &Event) {
+        match event {
+            Event::RuleStarted { rule } => self.current_rule = Some(rule.clone()),
+            Event::RuleFinished {
+                rule,
+                findings,
+                bytes,
+                ..
+            } => {
+                if self.current_rule.as_deref() == Some(rule.as_str()) {
+                    self.current_rule = None;
+                }
+                self.rules_finished += 1;
+                self.findings += findings;
+                self.bytes = self.bytes.saturating_add(*bytes);
+            }
+            Event::ScanProgress { root, files, bytes } => {
+                self.scan_root = Some(root.clone());
+                self.scan_files = *files;
+                self.scan_bytes = *bytes;
+            }
+            Event::ReportReady { .. } => {
+                self.current_rule = None;
+                self.ready = true;
+            }
+        }
+    }
+
+    // 実行中画面に出す 1 行。まだ何も届いていなければ None。
+    pub fn status_line(&self) -> Option<String> {
+        if self.ready {
+            return Some("レポートを作成しました".to_string());
+        }
+        if let Some(root) = &self.scan_root {
+            return Some(format!(
+                "スキャン中 {root}（{} files / {}）",
+                self.scan_files,
+                crate::ui::format_bytes(self.scan_bytes)
+            ));
+        }
+        if self.rules_finished == 0 && self.current_rule.is_none() {
+            return None;
+        }
+        let current = self
+            .current_rule
+            .as_deref()
+            .map(|r| format!(" / 実行中: {r}"))
+            .unwrap_or_default();
+        Some(format!(
+            "ルール {} 件完了（所見 {} 件、{}）{current}",
+            self.rules_finished,
+            self.findings,
+            crate::ui::format_bytes(self.bytes)
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn events_serialize_as_tagged_ndjson_and_fold_into_progress() {
+        let lines = Arc::new(Mutex::new(Vec::<u8>::new()));
+        let buf = lines.clone();
+        let sink = EventSink::new(move |event| {
+            let mut buf = buf.lock().expect("buf");
+            serde_json::to_writer(&mut *buf, event).expect("serialize");
+            buf.push(b'\n');
+        });
+
+        let events = [
+            Event::RuleStarted {
+                rule: "npm-cache".to_string(),
+            },
+            Event::RuleFinished {
+                rule: "npm-cache".to_string(),
+                findings: 1,
+                bytes: 2048,
+                duration_ms: 3,
+            },
+            Event::RuleStarted {
+                rule: "yarn-cache".to_string(),
+            },
+        ];
+        let mut progress = Progress::default();
+        assert_eq!(progress.status_line(), None);
+        for event in &events {
+            sink.emit(event.clone());
+            progress.apply(event);
+        }
+
+        let text = String::from_utf8(lines.lock().expect("buf").clone()).expect("utf8");
+        let first: Vec<serde_json::Value> = text
+            .lines()
+            .map(|l| serde_json::from_str(l).expect("line is json"))
+            .collect();
+        assert_eq!(first.len(), 3);
+        assert_eq!(first[0]["event"], "rule_started");
+        assert_eq!(first[1]["event"], "rule_finished");
+        assert_eq!(first[1]["bytes"], 2048);
+
+        assert_eq!(progress.rules_finished, 1);
+        assert_eq!(progress.current_rule.as_deref(), Some("yarn-cache"));
+        let line = progress.status_line().expect("status");
+        assert!(
+            line.contains("ルール 1 件完了") && line.contains("yarn-cache"),
+            "{line}"
+        );
+
+        progress.apply(&Event::ScanProgress {
+            root: "~".to_string(),
+            files: 10,
+            bytes: 4096,
+        });
+        assert!(progress.status_line().expect("status").contains("10 files"));
+        progress.apply(&Event::ReportReady {
+            findings: 1,
+            actions: 0,
+            estimated_total_bytes: 2048,
+            unobserved_bytes: 0,
+        });
+        assert!(progress.ready);
+    }
+
+    #[test]
+    fn ndjson_sink_writes_lines_to_a_duplicated_inherited_fd() {
+        use std::io::{Read, Seek};
+        use std::os::fd::AsRawFd;
+
+        let path = std::env::temp_dir().join(format!(
+            "macdiet-events-fd-test-{}.ndjson",
+            std::process::id()
+        ));
+        let mut file = std::fs::OpenOptions::new()
+            .create(true)
+            .truncate(true)
+            .read(true)
+            .write(true)
+            .open(&path)
+            .expect("open");
+
+        let writer = crate::platform::inherited_fd_writer(file.as_raw_fd()).expect("dup fd");
+        let sink = EventSink::ndjson(writer);
+        sink.emit(Event::RuleStarted {
+            rule: "npm-cache".to_string(),
+        });
+        sink.emit(Event::ScanProgress {
+            root: "~".to_string(),
+            files: 3,
+            bytes: 10,
+        });
+        drop(sink);
+
+        // sink を閉じても受け継いだ fd 自体は開いたまま。
+        assert_ne!(unsafe { libc::fcntl(file.as_raw_fd(), libc::F_GETFD) }, -1);
+        let mut text = String::new();
+        file.rewind().expect("rewind");
+        file.read_to_string(&mut text).expect("read");
+        let events: Vec<serde_json::Value> = text
+            .lines()
+            .map(|l| serde_json::from_str(l).expect("line is json"))
+            .collect();
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[0]["event"], "rule_started");
+        assert_eq!(events[1]["event"], "scan_progress");
+        assert_eq!(events[1]["files"], 3);
+        assert!(crate::platform::inherited_fd_writer(-1).is_none());
+
+        let _ = std::fs::remove_file(&path);
+    }
+}
@@ -0,0 +1,313 @@
+use std::collections::BTreeMap;
+
+use serde::Serialize;
+
+use crate::core::Report;
+
+#[derive(Debug, Clone)]
+pub struct FleetInput {
+    // host が無いレポートはファイル名をマシン名として使う。
+    pub source: String,
+    pub report: Report,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct FleetMachine {
+    pub host: String,
+    pub generated_at: String,
+    pub estimated_total_bytes: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct FleetOffender {
+    pub host: String,
+    pub bytes: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct FleetFindingType {
+    #[serde(rename = "type")]
+    pub finding_type: String,
+    pub title: String,
+    pub machines: usize,
+    pub total_bytes: u64,
+    pub median_bytes: u64,
+    pub worst: Vec<FleetOffender>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct FleetSummary {
+    pub machines: Vec<FleetMachine>,
+    // 同じ host のレポートが複数あった場合、古い方を数えずに捨てた件数。
+    pub superseded_reports: usize,
+    pub estimated_total_bytes: u64,
+    pub median_total_bytes: u64,
+    pub types: Vec<FleetFindingType>,
+}
+
+// 所見を type ごとにまとめ、マシン数・合計・中央値と上位のマシンを出す。
+// 1 台に同じ type の所見が複数あれば合算してから 1 台分として数える。
+pub fn summarize(inputs: &[FleetInput], top: usize) -> FleetSummary {
+    let mut latest: BTreeMap<String, &Report> = BTreeMap::new();
+    let mut superseded_reports = 0;
+    for input in inputs {
+        let host = input
+            .report
+            .host
+            .clone()
+            .filter(|h| !h.trim().is_empty())
+            .unwrap_or_else(|| input.source.clone());
+        match latest.get(&host) {
+            Some(existing) if existing.generated_at >= input.report.generated_at => {
+                superseded_reports += 1;
+            }
+            Some(_) => {
+                superseded_reports += 1;
+                latest.insert(host, &input.report);
+            }
+            None => {
+                latest.insert(host, &input.report);
+            }
+        }
+    }
+
+    let mut per_type: BTreeMap<&str, Vec<FleetOffender>> = BTreeMap::new();
+    for (host, report) in &latest {
+        let mut bytes_by_type: BTreeMap<&str, u64> = BTreeMap::new();
+        for f in &report.findings {
+            *bytes_by_type.entry(f.finding_type.as_str()).or_insert(0) += f.estimated_bytes;
+        }
+        for (finding_type, bytes) in bytes_by_type {
+            per_type
+                .entry(finding_type)
+                .or_default()
+                .push(FleetOffender {
+                    host: host.clone(),
+                    bytes,
+                });
+        }
+    }
+
+    let mut types: Vec<FleetFindingType> = per_type
+        .into_iter()
+        .map(|(finding_type, mut hosts)| {
+            hosts.sort_by(|a, b| b.bytes.cmp(&a.bytes).then_with(|| a.host.cmp(&b.host)));
+            let values: Vec<u64> = hosts.iter().map(|h| h.bytes).collect();
+            FleetFindingType {
+                finding_type: finding_type.to_string(),
+                title: type_label(finding_type).to_string(),
+                machines: hosts.len(),
+                total_bytes: values.iter().sum(),
+                median_bytes: median(&values),
+                worst: hosts
+                    .into_iter()
+                    .filter(|h| h.bytes > 0)
+                    .take(top)
+                    .collect(),
+            }
+        })
+        .collect();
+    types.sort_by(|a, b| {
+        b.total_bytes
+            .cmp(&a.total_bytes)
+            .then_with(|| a.finding_type.cmp(&b.finding_type))
+    });
+
+    let machines: Vec<FleetMachine> = latest
+        .iter()
+        .map(|(host, report)| FleetMachine {
+            host: host.clone(),
+            generated_at: report.generated_at.clone(),
+            estimated_total_bytes: report.summary.estimated_total_bytes,
+        })
+        .collect();
+    let totals: Vec<u64> = machines.iter().map(|m| m.estimated_total_bytes).collect();
+
+    FleetSummary {
+        estimated_total_bytes: totals.iter().sum(),
+        median_total_bytes: median(&totals),
+        machines,
+        superseded_reports,
+        types,
+    }
+}
+
+// 所見の title はパスや件数などマシンごとの値を含むため、type ごとに固定の表示名を使う。
+// 未知の type（新しい版のレポートなど）は type をそのまま表示する。
+fn type_label(finding_type: &str) -> &str {
+    match finding_type {
+        "XCODE_DERIVED_DATA_LARGE" => "Xcode DerivedData（ビルドキャッシュ）",
+        "CORESIMULATOR_DEVICES_LARGE" => "CoreSimulator Devices（シミュレータデータ）",
+        "XCODE_ARCHIVES_LARGE" => "Xcode Archives（アーカイブ）",
+        "DEVICE_SUPPORT_LARGE" => "Xcode iOS DeviceSupport（デバッグ用データ）",
+        "XCODE_DOCSETS_LARGE" => "Xcode DocSets（ドキュメント）",
+        "XCODE_DEVICE_LOGS_LARGE" => "Xcode iOS Device Logs（端末ログ）",
+        "XCTEST_DEVICES_LARGE" => "XCTestDevices（並列UIテスト用のクローンシミュレータ）",
+        "XCODE_PREVIEWS_LARGE" => "Xcode Previews（SwiftUI プレビュー用データ）",
+        "XCODE_CACHES_LARGE" => "Xcode caches（キャッシュ）",
+        "DOCKER_STORAGE_LARGE" => "Docker Desktop Data（コンテナ/イメージ/キャッシュ）",
+        "GRADLE_CACHES_LARGE" => "Gradle caches（ビルドキャッシュ）",
+        "HOMEBREW_CACHE_LARGE" => "Homebrew cache（キャッシュ）",
+        "HOMEBREW_OLD_KEGS" => "Homebrew の古いバージョン",
+        "HOMEBREW_AUTOREMOVE_CANDIDATE" => "Homebrew の不要な依存",
+        "RUST_CARGO_CACHE_LARGE" => "Cargo registry / git cache（Rust）",
+        "RUST_CARGO_REGISTRY_SRC_LARGE" => "Cargo registry src（展開済みソース、Rust）",
+        "RUST_CARGO_REGISTRY_CACHE_LARGE" => "Cargo registry cache（.crate アーカイブ、Rust）",
+        "RUST_CARGO_REGISTRY_INDEX_LARGE" => "Cargo registry index（Rust）",
+        "NODE_NPM_CACHE_LARGE" => "npm cache（キャッシュ）",
+        "NODE_YARN_CACHE_LARGE" => "Yarn cache（キャッシュ）",
+        "NODE_PNPM_STORE_LARGE" => "pnpm store（キャッシュ）",
+        "LANG_VERSION_INSTALLED" => "言語のインストール済みバージョン",
+        "INSTALLER_LEFTOVER" => "インストーラの残り",
+        "TM_LOCAL_SNAPSHOTS_PRESENT" => "Time Machine ローカルスナップショット",
+        "TM_LOCAL_SNAPSHOTS_NONE" => "Time Machine ローカルスナップショット: なし",
+        "TM_LOCAL_SNAPSHOTS_UNOBSERVED" => "Time Machine ローカルスナップショット: 取得できず",
+        "APFS_SNAPSHOTS_PRESENT" => "APFS スナップショット",
+        "APFS_SNAPSHOTS_NONE" => "APFS スナップショット: なし",
+        "APFS_SNAPSHOTS_UNOBSERVED" => "APFS スナップショット: 取得できず",
+        "SCAN_TOP_DIR" => "上位ディレクトリ",
+        "SCAN_DUPLICATE_FILES" => "重複ファイル",
+        "GROWTH_HOTSPOT" => "増加中のディレクトリ",
+        other => other,
+    }
+}
+
+// 偶数個のときは中央 2 つの平均（切り捨て）。
+fn median(values: &[u64]) -> u64 {
+    if values.is_empty() {
+        return 0;
+    }
+    let mut sorted = values.to_vec();
+    sorted.sort_unstable();
+    let mid = sorted.len() / 2;
+    if sorted.len() % 2 == 1 {
+        sorted[mid]
+    } else {
+        ((u128::from(sorted[mid - 1]) + u128::from(sorted[mid])) / 2) as u64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{Finding, OsInfo, ReportSummary, RiskLevel};
+
+    fn input(source: &str, host: Option<&str>, at: &str, findings: &[(&str, u64)]) -> FleetInput {
+        FleetInput {
+            source: source.to_string(),
+            report: Report {
+                schema_version: crate::core::REPORT_SCHEMA_VERSION.to_string(),
+                tool_version: "0.0.0".to_string(),
+                host: host.map(str::to_string),
+                os: OsInfo {
+                    name: "test".to_string(),
+                    version: "0".to_string(),
+                },
+                generated_at: at.to_string(),
+                summary: ReportSummary {
+                    estimated_total_bytes: findings.iter().map(|(_, b)| b).sum(),
+                    unobserved_bytes: 0,
+                    notes: vec![],
+                },
+                findings: findings
+                    .iter()
+                    .enumerate()
+                    .map(|(i, (finding_type, bytes))| Finding {
+                        id: format!("{finding_type}-{i}"),
+                        finding_type: finding_type.to_string(),
+                        title: finding_type.to_string(),
+                        estimated_bytes: *bytes,
+                        confidence: 1.0,
+                        risk_level: RiskLevel::R1,
+                        evidence: vec![],
+                        recommended_actions: vec![],
+                    })
+                    .collect(),
+                actions: vec![],
+                classification: None,
+                volumes: vec![],
+            },
+        }
+    }
+
+    #[test]
+    fn summarize_merges_by_type_with_median_and_worst_hosts() {
+        let inputs = [
+            input(
+                "a.json",
+                Some("mac-a"),
+                "2026-01-01T00:00:00Z",
+                &[("XCODE", 100)],
+            ),
+            input(
+                "a2.json",
+                Some("mac-a"),
+                "2026-02-01T00:00:00Z",
+                &[("XCODE", 900), ("NPM", 5)],
+            ),
+            input(
+                "b.json",
+                Some("mac-b"),
+                "2026-02-01T00:00:00Z",
+                &[("XCODE", 300), ("XCODE", 100)],
+            ),
+            input(
+                "c.json",
+                None,
+                "2026-02-01T00:00:00Z",
+                &[("XCODE", 50), ("NPM", 0)],
+            ),
+        ];
+        let summary = summarize(&inputs, 2);
+        assert_eq!(summary.superseded_reports, 1);
+        let hosts: Vec<&str> = summary.machines.iter().map(|m| m.host.as_str()).collect();
+        assert_eq!(hosts, vec!["c.json", "mac-a", "mac-b"]);
+        assert_eq!(summary.median_total_bytes, 400);
+
+        let xcode = &summary.types[0];
+        assert_eq!(xcode.finding_type, "XCODE");
+        assert_eq!(xcode.machines, 3);
+        assert_eq!(xcode.total_bytes, 1350);
+        assert_eq!(xcode.median_bytes, 400);
+        let worst: Vec<(&str, u64)> = xcode
+            .worst
+            .iter()
+            .map(|w| (w.host.as_str(), w.bytes))
+            .collect();
+        assert_eq!(worst, vec![("mac-a", 900), ("mac-b", 400)]);
+
+        let npm = &summary.types[1];
+        assert_eq!((npm.machines, npm.total_bytes, npm.median_bytes), (2, 5, 2));
+        assert_eq!(npm.worst.len(), 1);
+    }
+
+    #[test]
+    fn summarize_labels_types_without_host_specific_titles() {
+        let mut a = input(
+            "a.json",
+            Some("mac-a"),
+            "2026-02-01T00:00:00Z",
+            &[("SCAN_TOP_DIR", 10)],
+        );
+        a.report.findings[0].title = "上位ディレクトリ: ~/secret-project".to_string();
+        let b = input(
+            "b.json",
+            Some("mac-b"),
+            "2026-02-01T00:00:00Z",
+            &[("SCAN_TOP_DIR", 20), ("CUSTOM_TYPE", 1)],
+        );
+        let summary = summarize(&[a, b], 3);
+        let titles: Vec<(&str, &str)> = summary
+            .types
+            .iter()
+            .map(|t| (t.finding_type.as_str(), t.title.as_str()))
+            .collect();
+        assert_eq!(
+            titles,
+            vec![
+                ("SCAN_TOP_DIR", "上位ディレクトリ"),
+                ("CUSTOM_TYPE", "CUSTOM_TYPE")
+            ]
+        );
+    }
+}
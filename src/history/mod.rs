@@ -0,0 +1,336 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use serde::Serialize;
+use time::OffsetDateTime;
+
+use crate::config::HistoryConfig;
+use crate::core::Report;
+
+const SPARK_LEVELS: &[u8] = b"_.-:=+*#";
+
+pub fn history_dir(home_dir: &Path) -> PathBuf {
+    home_dir.join(".config/macdiet/history")
+}
+
+#[derive(Debug, Clone)]
+pub struct HistoryRun {
+    pub path: PathBuf,
+    pub command: String,
+    pub recorded_at_nanos: i128,
+    pub report: Report,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct LoadedRuns {
+    pub runs: Vec<HistoryRun>,
+    // JSON が壊れている / 未対応の schema_version のため読み飛ばしたファイル数。
+    pub skipped: usize,
+}
+
+// 1 run = 1 ファイル（中身は `report --json` と同じ Report）なので、`report diff` にもそのまま渡せる。
+// ファイル名 `<unix_nanos>-<command>.json` の時刻で並べ、保持期限/件数の判定にも使う。
+pub fn save_run(
+    home_dir: &Path,
+    command: &str,
+    report: &Report,
+    retention: &HistoryConfig,
+    now: OffsetDateTime,
+) -> Result<PathBuf> {
+    let dir = history_dir(home_dir);
+    std::fs::create_dir_all(&dir)
+        .with_context(|| format!("履歴ディレクトリの作成に失敗しました: {}", dir.display()))?;
+
+    let path = dir.join(format!("{}-{command}.json", now.unix_timestamp_nanos()));
+    let buf =
+        serde_json::to_vec_pretty(report).context("履歴(JSON)のシリアライズに失敗しました")?;
+    let tmp = path.with_extension(format!("json.tmp-{}", std::process::id()));
+    std::fs::write(&tmp, buf)
+        .with_context(|| format!("履歴の書き込みに失敗しました: {}", tmp.display()))?;
+    std::fs::rename(&tmp, &path)
+        .with_context(|| format!("履歴の書き込みに失敗しました: {}", path.display()))?;
+
+    prune(&dir, retention, now)?;
+    Ok(path)
+}
+
+fn run_files(dir: &Path) -> Result<Vec<(i128, String, PathBuf)>> {
+    let rd = match std::fs::read_dir(dir) {
+        Ok(rd) => rd,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(vec![]),
+        Err(err) => {
+            return Err(err)
+                .with_context(|| format!("履歴ディレクトリを読み取れません: {}", dir.display()));
+        }
+    };
+    let mut files = Vec::new();
+    for entry in rd.flatten() {
+        let path = entry.path();
+        let Some(name) = path.file_name().and_then(|n| n.to_str()) else {
+            continue;
+        };
+        let Some((ts, command)) = name
+            .strip_suffix(".json")
+            .and_then(|stem| stem.split_once('-'))
+        else {
+            continue;
+        };
+        let Ok(ts) = ts.parse::<i128>() else {
+            continue;
+        };
+        files.push((ts, command.to_string(), path));
+    }
+    files.sort();
+    Ok(files)
+}
+
+fn prune(dir: &Path, retention: &HistoryConfig, now: OffsetDateTime) -> Result<usize> {
+    let files = run_files(dir)?;
+    let cutoff = (retention.max_age_days > 0).then(|| {
+        now.unix_timestamp_nanos()
+            - i128::from(retention.max_age_days) * 24 * 60 * 60 * 1_000_000_000
+    });
+    let over = if retention.max_runs > 0 {
+        files.len().saturating_sub(retention.max_runs)
+    } else {
+        0
+    };
+
+    let mut removed = 0;
+    for (i, (ts, _, path)) in files.iter().enumerate() {
+        if i < over || cutoff.is_some_and(|c| *ts < c) {
+            std::fs::remove_file(path)
+                .with_context(|| format!("履歴の削除に失敗しました: {}", path.display()))?;
+            removed += 1;
+        }
+    }
+    Ok(removed)
+}
+
+pub fn load_runs(home_dir: &Path, command: Option<&str>) -> Result<LoadedRuns> {
+    let mut loaded = LoadedRuns::default();
+    for (ts, cmd, path) in run_files(&history_dir(home_dir))? {
+        if command.is_some_and(|c| c != cmd) {
+            continue;
+        }
+        let report = std::fs::read(&path)
+            .ok()
+            .and_then(|bytes| serde_json::from_slice(&bytes).ok())
+            .and_then(|value| crate::schema::migrate_report(value).ok());
+        match report {
+            Some(report) => loaded.runs.push(HistoryRun {
+                path,
+                command: cmd,
+                recorded_at_nanos: ts,
+                report,
+            }),
+            None => loaded.skipped += 1,
+        }
+    }
+    Ok(loaded)
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TrendRun {
+    pub command: String,
+    pub generated_at: String,
+    pub path: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TrendRow {
+    pub id: String,
+    pub title: String,
+    // runs と同じ並び。その run に所見が無かった場合は null。
+    pub bytes: Vec<Option<u64>>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct HistoryTrend {
+    pub runs: Vec<TrendRun>,
+    pub estimated_total_bytes: Vec<u64>,
+    pub findings: Vec<TrendRow>,
+}
+
+// 所見ごとの推移。並びは最新 run のサイズが大きい順（最新に無いものは最後に観測したサイズ順）。
+pub fn trend(runs: &[HistoryRun], ids: &[String]) -> HistoryTrend {
+    let mut rows: Vec<TrendRow> = Vec::new();
+    let mut index: HashMap<String, usize> = HashMap::new();
+    for (i, run) in runs.iter().enumerate() {
+        for f in &run.report.findings {
+            if !ids.is_empty() && !ids.contains(&f.id) {
+                continue;
+            }
+            let row = *index.entry(f.id.clone()).or_insert_with(|| {
+                rows.push(TrendRow {
+                    id: f.id.clone(),
+                    title: f.title.clone(),
+                    bytes: vec![None; runs.len()],
+                });
+                rows.len() - 1
+            });
+            rows[row].title = f.title.clone();
+            rows[row].bytes[i] = Some(f.estimated_bytes);
+        }
+    }
+    rows.sort_by_key(|r| {
+        let latest = r.bytes.last().copied().flatten();
+        let last_seen = r.bytes.iter().rev().find_map(|b| *b).unwrap_or(0);
+        (
+            std::cmp::Reverse(latest.is_some()),
+            std::cmp::Reverse(latest.unwrap_or(last_seen)),
+            r.id.clone(),
+        )
+    });
+
+    HistoryTrend {
+        runs: runs
+            .iter()
+            .map(|r| TrendRun {
+                command: r.command.clone(),
+                generated_at: r.report.generated_at.clone(),
+                path: r.path.display().to_string(),
+            })
+            .collect(),
+        estimated_total_bytes: runs
+            .iter()
+            .map(|r| r.report.summary.estimated_total_bytes)
+            .collect(),
+        findings: rows,
+    }
+}
+
+// 系列の最小〜最大を 8 段階の ASCII にする。値が無い run は空白。
+pub fn sparkline(values: &[Option<u64>]) -> String {
+    let present = values.iter().flatten();
+    let (Some(min), Some(max)) = (present.clone().min(), present.max()) else {
+        return " ".repeat(values.len());
+    };
+    let top = SPARK_LEVELS.len() - 1;
+    values
+        .iter()
+        .map(|v| match v {
+            None => ' ',
+            Some(_) if max == min => SPARK_LEVELS[top / 2] as char,
+            Some(v) => {
+                let level = (u128::from(v - min) * top as u128 / u128::from(max - min)) as usize;
+                SPARK_LEVELS[level] as char
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{Finding, OsInfo, ReportSummary, RiskLevel};
+    use std::sync::atomic::{AtomicU64, Ordering};
+
+    fn report_with(findings: &[(&str, u64)]) -> Report {
+        Report {
+            schema_version: crate::core::REPORT_SCHEMA_VERSION.to_string(),
+            tool_version: "0.0.0".to_string(),
+            host: None,
+            os: OsInfo {
+                name: "test".to_string(),
+                version: "0".to_string(),
+            },
+            generated_at: "2026-01-02T03:04:05Z".to_string(),
+            summary: ReportSummary {
+                estimated_total_bytes: findings.iter().map(|(_, b)| b).sum(),
+                unobserved_bytes: 0,
+                notes: vec![],
+            },
+            findings: findings
+                .iter()
+                .map(|(id, bytes)| Finding {
+                    id: id.to_string(),
+                    finding_type: "TEST".to_string(),
+                    title: id.to_string(),
+                    estimated_bytes: *bytes,
+                    confidence: 1.0,
+                    risk_level: RiskLevel::R1,
+                    evidence: vec![],
+                    recommended_actions: vec![],
+                })
+                .collect(),
+            actions: vec![],
+            classification: None,
+            volumes: vec![],
+        }
+    }
+
+    #[test]
+    fn save_run_prunes_by_count_and_age_and_trend_aligns_runs() {
+        static SEQ: AtomicU64 = AtomicU64::new(0);
+
+        let seq = SEQ.fetch_add(1, Ordering::Relaxed);
+        let home =
+            std::env::temp_dir().join(format!("macdiet-history-test-{}-{seq}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&home);
+        std::fs::create_dir_all(&home).expect("create home");
+
+        let retention = HistoryConfig {
+            enabled: true,
+            max_runs: 3,
+            max_age_days: 30,
+        };
+        let day = time::Duration::days(1);
+        let start = OffsetDateTime::from_unix_timestamp(1_800_000_000).expect("timestamp");
+        let reports = [
+            report_with(&[("derived-data", 100)]),
+            report_with(&[("derived-data", 100), ("npm-cache", 50)]),
+            report_with(&[("derived-data", 300), ("npm-cache", 40)]),
+            report_with(&[("derived-data", 500)]),
+        ];
+        for (i, report) in reports.iter().enumerate() {
+            save_run(&home, "doctor", report, &retention, start + day * i as i32)
+                .expect("save run");
+        }
+        save_run(&home, "scan", &reports[0], &retention, start + day * 40).expect("save run");
+
+        // 件数上限で最古の 1 件、期限切れで 40 日前より古いものが消え、scan の 1 件だけ残る。
+        let loaded = load_runs(&home, None).expect("load runs");
+        assert_eq!(loaded.runs.len(), 1);
+        assert_eq!(loaded.runs[0].command, "scan");
+
+        let _ = std::fs::remove_dir_all(history_dir(&home));
+        let retention = HistoryConfig {
+            max_age_days: 0,
+            ..retention
+        };
+        for (i, report) in reports.iter().enumerate() {
+            save_run(&home, "doctor", report, &retention, start + day * i as i32)
+                .expect("save run");
+        }
+        std::fs::write(history_dir(&home).join("1-doctor.json"), b"{").expect("write");
+        let loaded = load_runs(&home, Some("doctor")).expect("load runs");
+        assert_eq!(loaded.runs.len(), 3);
+        assert_eq!(loaded.skipped, 1);
+
+        let trend = trend(&loaded.runs, &[]);
+        assert_eq!(trend.estimated_total_bytes, vec![150, 340, 500]);
+        let rows: Vec<(&str, Vec<Option<u64>>)> = trend
+            .findings
+            .iter()
+            .map(|r| (r.id.as_str(), r.bytes.clone()))
+            .collect();
+        assert_eq!(
+            rows,
+            vec![
+                ("derived-data", vec![Some(100), Some(300), Some(500)]),
+                ("npm-cache", vec![Some(50), Some(40), None]),
+            ]
+        );
+
+        let _ = std::fs::remove_dir_all(&home);
+    }
+
+    #[test]
+    fn sparkline_scales_between_min_and_max() {
+        assert_eq!(sparkline(&[Some(0), Some(50), Some(100)]), "_:#");
+        assert_eq!(sparkline(&[Some(7), None, Some(7)]), ": :");
+        assert_eq!(sparkline(&[None, None]), "  ");
+    }
+}
@@ -1,13 +1,20 @@
 pub mod actions;
+pub mod cache;
 pub mod cli;
 pub mod config;
 pub mod core;
 pub mod engine;
+pub mod events;
 pub mod exit;
+pub mod fleet;
+pub mod history;
 pub mod logs;
+pub mod ncdu;
 pub mod platform;
+pub mod privacy;
 pub mod rules;
 pub mod scan;
+pub mod schema;
 pub mod snapshots;
 pub mod tui;
 pub mod ui;
@@ -1,16 +1,18 @@
 use std::path::{Path, PathBuf};
 
 use anyhow::{Context, Result};
-use serde::Serialize;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
 use time::OffsetDateTime;
 use time::format_description::well_known::Rfc3339;
 
-use crate::actions::ApplyOutcome;
+use crate::actions::{ApplyOutcome, RestoreOutcome, TrashMoveRecord};
 use crate::core::{ActionKind, ActionPlan, RiskLevel};
+use crate::privacy::Redactor;
 
 const MAX_CMD_OUTPUT_BYTES: usize = 64 * 1024;
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, JsonSchema)]
 struct FixApplyLog {
     schema_version: &'static str,
     tool_version: String,
@@ -24,7 +26,7 @@ struct FixApplyLog {
     outcome: FixApplyOutcome,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, JsonSchema)]
 struct FixApplyAction {
     id: String,
     title: String,
@@ -35,26 +37,58 @@ struct FixApplyAction {
     rollback_possible: bool,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, JsonSchema)]
 struct FixApplyOutcome {
     moved: Vec<FixApplyMoved>,
     skipped_missing: Vec<String>,
     errors: Vec<FixApplyError>,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
 struct FixApplyMoved {
     from: String,
     to: String,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, JsonSchema)]
 struct FixApplyError {
     path: String,
     error: String,
 }
 
-#[derive(Debug, Serialize)]
+// undo が読む部分だけ（fix-apply ログ）。
+#[derive(Debug, Deserialize)]
+struct StoredFixApplyLog {
+    command: String,
+    outcome: StoredFixApplyOutcome,
+}
+
+#[derive(Debug, Deserialize)]
+struct StoredFixApplyOutcome {
+    moved: Vec<FixApplyMoved>,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+struct UndoLog {
+    schema_version: &'static str,
+    tool_version: String,
+    command: &'static str,
+    started_at: String,
+    finished_at: String,
+    status: String,
+    source_log: String,
+    outcome: UndoOutcome,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+struct UndoOutcome {
+    restored: Vec<FixApplyMoved>,
+    skipped_missing: Vec<String>,
+    conflicts: Vec<String>,
+    errors: Vec<FixApplyError>,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
 struct CommandAttemptLog {
     cmd: String,
     args: Vec<String>,
@@ -68,7 +102,7 @@ struct CommandAttemptLog {
     error: Option<String>,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, JsonSchema)]
 struct SnapshotsThinLog {
     schema_version: &'static str,
     tool_version: String,
@@ -81,7 +115,7 @@ struct SnapshotsThinLog {
     attempt: CommandAttemptLog,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, JsonSchema)]
 struct SnapshotsDeleteLog {
     schema_version: &'static str,
     tool_version: String,
@@ -97,7 +131,7 @@ struct SnapshotsDeleteLog {
     delete_attempt: Option<CommandAttemptLog>,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, JsonSchema)]
 struct FixRunCmdLog {
     schema_version: &'static str,
     tool_version: String,
@@ -111,12 +145,34 @@ struct FixRunCmdLog {
     attempt: CommandAttemptLog,
 }
 
+// ログの種類（ファイル名の接頭辞と同じ）。`macdiet schema log <kind>` で JSON Schema を出す。
+pub const LOG_KINDS: &[&str] = &[
+    "fix-apply",
+    "fix-run-cmd",
+    "snapshots-thin",
+    "snapshots-delete",
+    "undo",
+];
+
+pub fn log_schema(kind: &str) -> Option<schemars::schema::RootSchema> {
+    let schema = match kind {
+        "fix-apply" => schemars::schema_for!(FixApplyLog),
+        "fix-run-cmd" => schemars::schema_for!(FixRunCmdLog),
+        "snapshots-thin" => schemars::schema_for!(SnapshotsThinLog),
+        "snapshots-delete" => schemars::schema_for!(SnapshotsDeleteLog),
+        "undo" => schemars::schema_for!(UndoLog),
+        _ => return None,
+    };
+    Some(schema)
+}
+
 pub fn logs_dir(home_dir: &Path) -> PathBuf {
     home_dir.join(".config/macdiet/logs")
 }
 
 pub fn write_fix_apply_log(
     home_dir: &Path,
+    redactor: &Redactor,
     started_at: OffsetDateTime,
     finished_at: OffsetDateTime,
     max_risk: RiskLevel,
@@ -141,8 +197,8 @@ pub fn write_fix_apply_log(
     let actions: Vec<FixApplyAction> = actions
         .iter()
         .map(|a| FixApplyAction {
-            id: a.id.clone(),
-            title: a.title.clone(),
+            id: redactor.text(&a.id),
+            title: redactor.text(&a.title),
             risk_level: a.risk_level.to_string(),
             kind: match &a.kind {
                 ActionKind::TrashMove { .. } => "TRASH_MOVE".to_string(),
@@ -152,7 +208,7 @@ pub fn write_fix_apply_log(
                 ActionKind::ShowInstructions { .. } => "SHOW_INSTRUCTIONS".to_string(),
             },
             paths: match &a.kind {
-                ActionKind::TrashMove { paths } => paths.clone(),
+                ActionKind::TrashMove { paths } => paths.iter().map(|p| redactor.path(p)).collect(),
                 _ => vec![],
             },
             rollback_possible: matches!(a.kind, ActionKind::TrashMove { .. }),
@@ -163,23 +219,23 @@ pub fn write_fix_apply_log(
         .moved
         .iter()
         .map(|m| FixApplyMoved {
-            from: mask_home(&m.from, home_dir),
-            to: mask_home(&m.to, home_dir),
+            from: redactor.local_path(&m.from),
+            to: redactor.local_path(&m.to),
         })
         .collect();
 
     let skipped_missing = outcome
         .skipped_missing
         .iter()
-        .map(|p| mask_home(p, home_dir))
+        .map(|p| redactor.local_path(p))
         .collect();
 
     let errors = outcome
         .errors
         .iter()
         .map(|e| FixApplyError {
-            path: mask_home(&e.path, home_dir),
-            error: e.error.clone(),
+            path: redactor.local_path(&e.path),
+            error: redactor.text(&e.error),
         })
         .collect();
 
@@ -210,8 +266,126 @@ pub fn write_fix_apply_log(
     Ok(path)
 }
 
+// 一番新しい `<kind>-<pid>-<ts>.json`（ファイル名の時刻順）。
+pub fn latest_log(home_dir: &Path, kind: &str) -> Option<PathBuf> {
+    let prefix = format!("{kind}-");
+    std::fs::read_dir(logs_dir(home_dir))
+        .ok()?
+        .filter_map(|e| e.ok())
+        .filter_map(|e| {
+            let name = e.file_name().to_string_lossy().into_owned();
+            let (_pid, ts) = name
+                .strip_prefix(&prefix)?
+                .strip_suffix(".json")?
+                .split_once('-')?;
+            Some((ts.parse::<i128>().ok()?, e.path()))
+        })
+        .max_by_key(|(ts, _)| *ts)
+        .map(|(_, path)| path)
+}
+
+// fix --apply のログから移動の記録（元の場所 -> ゴミ箱）を読む。`~/...` はホームに展開する。
+pub fn read_fix_apply_log(path: &Path, home_dir: &Path) -> Result<Vec<TrashMoveRecord>> {
+    let bytes =
+        std::fs::read(path).with_context(|| format!("ログを読み取れません: {}", path.display()))?;
+    let log: StoredFixApplyLog = serde_json::from_slice(&bytes)
+        .with_context(|| format!("fix --apply のログとして解釈できません: {}", path.display()))?;
+    if log.command != "fix" {
+        anyhow::bail!(
+            "fix --apply のログではありません（command={}）: {}",
+            log.command,
+            path.display()
+        );
+    }
+    let expand = |p: &str| match p.strip_prefix("~/") {
+        Some(rest) => home_dir.join(rest),
+        None if p == "~" => home_dir.to_path_buf(),
+        None => PathBuf::from(p),
+    };
+    Ok(log
+        .outcome
+        .moved
+        .iter()
+        .map(|m| TrashMoveRecord {
+            from: expand(&m.from),
+            to: expand(&m.to),
+        })
+        .collect())
+}
+
+pub fn write_undo_log(
+    home_dir: &Path,
+    redactor: &Redactor,
+    started_at: OffsetDateTime,
+    finished_at: OffsetDateTime,
+    source_log: &Path,
+    outcome: &RestoreOutcome,
+) -> Result<PathBuf> {
+    let dir = logs_dir(home_dir);
+    std::fs::create_dir_all(&dir)
+        .with_context(|| format!("ログディレクトリの作成に失敗しました: {}", dir.display()))?;
+
+    let pid = std::process::id();
+    let ts = finished_at.unix_timestamp_nanos();
+    let path = dir.join(format!("undo-{pid}-{ts}.json"));
+
+    let status = if outcome.errors.is_empty() && outcome.conflicts.is_empty() {
+        "ok".to_string()
+    } else {
+        "partial_error".to_string()
+    };
+
+    let log = UndoLog {
+        schema_version: "1.0",
+        tool_version: env!("CARGO_PKG_VERSION").to_string(),
+        command: "undo",
+        started_at: started_at
+            .format(&Rfc3339)
+            .unwrap_or_else(|_| "unknown".to_string()),
+        finished_at: finished_at
+            .format(&Rfc3339)
+            .unwrap_or_else(|_| "unknown".to_string()),
+        status,
+        source_log: redactor.local_path(source_log),
+        outcome: UndoOutcome {
+            restored: outcome
+                .restored
+                .iter()
+                .map(|m| FixApplyMoved {
+                    from: redactor.local_path(&m.from),
+                    to: redactor.local_path(&m.to),
+                })
+                .collect(),
+            skipped_missing: outcome
+                .skipped_missing
+                .iter()
+                .map(|p| redactor.local_path(p))
+                .collect(),
+            conflicts: outcome
+                .conflicts
+                .iter()
+                .map(|p| redactor.local_path(p))
+                .collect(),
+            errors: outcome
+                .errors
+                .iter()
+                .map(|e| FixApplyError {
+                    path: redactor.local_path(&e.path),
+                    error: redactor.text(&e.error),
+                })
+                .collect(),
+        },
+    };
+
+    let buf = serde_json::to_vec_pretty(&log).context("ログ(JSON)のシリアライズに失敗しました")?;
+    std::fs::write(&path, buf)
+        .with_context(|| format!("ログの書き込みに失敗しました: {}", path.display()))?;
+    Ok(path)
+}
+
 pub fn write_snapshots_thin_log(
     home_dir: &Path,
+    redactor: &Redactor,
     started_at: OffsetDateTime,
     finished_at: OffsetDateTime,
     bytes: u64,
@@ -230,7 +404,7 @@ pub fn write_snapshots_thin_log(
     let file_name = format!("snapshots-thin-{pid}-{ts}.json");
     let path = dir.join(file_name);
 
-    let attempt = command_attempt(cmd, args, output, error);
+    let attempt = command_attempt(redactor, cmd, args, output, error);
     let status = match (&attempt.error, attempt.exit_code) {
         (Some(_), _) => "error".to_string(),
         (None, Some(0)) => "ok".to_string(),
@@ -262,6 +436,7 @@ pub fn write_snapshots_thin_log(
 
 pub fn write_snapshots_delete_log(
     home_dir: &Path,
+    redactor: &Redactor,
     started_at: OffsetDateTime,
     finished_at: OffsetDateTime,
     requested_id: &str,
@@ -284,9 +459,15 @@ pub fn write_snapshots_delete_log(
     let file_name = format!("snapshots-delete-{pid}-{ts}.json");
     let path = dir.join(file_name);
 
-    let list_attempt = command_attempt(list_cmd, list_args, list_output, list_error);
+    let list_attempt = command_attempt(redactor, list_cmd, list_args, list_output, list_error);
     let delete_attempt = match (delete_cmd, delete_args) {
-        (Some(cmd), Some(args)) => Some(command_attempt(cmd, args, delete_output, delete_error)),
+        (Some(cmd), Some(args)) => Some(command_attempt(
+            redactor,
+            cmd,
+            args,
+            delete_output,
+            delete_error,
+        )),
         _ => None,
     };
 
@@ -332,6 +513,7 @@ pub fn write_snapshots_delete_log(
 
 pub fn write_fix_run_cmd_log(
     home_dir: &Path,
+    redactor: &Redactor,
     started_at: OffsetDateTime,
     finished_at: OffsetDateTime,
     action: &ActionPlan,
@@ -363,7 +545,7 @@ pub fn write_fix_run_cmd_log(
         }
     };
 
-    let attempt = command_attempt(cmd, args, output, error);
+    let attempt = command_attempt(redactor, cmd, args, output, error);
     let status = match (&attempt.error, output) {
         (Some(_), _) => "error".to_string(),
         (None, Some(out)) => match crate::actions::evaluate_allowlisted_run_cmd_output(action, out)
@@ -388,8 +570,8 @@ pub fn write_fix_run_cmd_log(
             .format(&Rfc3339)
             .unwrap_or_else(|_| "unknown".to_string()),
         status,
-        action_id: action.id.clone(),
-        action_title: action.title.clone(),
+        action_id: redactor.text(&action.id),
+        action_title: redactor.text(&action.title),
         risk_level: action.risk_level.to_string(),
         attempt,
     };
@@ -400,28 +582,19 @@ pub fn write_fix_run_cmd_log(
     Ok(path)
 }
 
-fn mask_home(path: &Path, home_dir: &Path) -> String {
-    let Ok(stripped) = path.strip_prefix(home_dir) else {
-        return path.display().to_string();
-    };
-    let stripped = stripped.display().to_string();
-    if stripped.is_empty() {
-        "~".to_string()
-    } else {
-        format!("~/{stripped}")
-    }
-}
-
 fn command_attempt(
+    redactor: &Redactor,
     cmd: &str,
     args: &[String],
     output: Option<&crate::platform::CommandOutput>,
     error: Option<String>,
 ) -> CommandAttemptLog {
+    let args: Vec<String> = args.iter().map(|a| redactor.text(a)).collect();
+    let error = error.map(|e| redactor.text(&e));
     let Some(output) = output else {
         return CommandAttemptLog {
             cmd: cmd.to_string(),
-            args: args.to_vec(),
+            args,
             exit_code: None,
             stdout: String::new(),
             stderr: String::new(),
@@ -431,10 +604,10 @@ fn command_attempt(
 
     CommandAttemptLog {
         cmd: cmd.to_string(),
-        args: args.to_vec(),
+        args,
         exit_code: Some(output.exit_code),
-        stdout: truncate_string(&output.stdout, MAX_CMD_OUTPUT_BYTES),
-        stderr: truncate_string(&output.stderr, MAX_CMD_OUTPUT_BYTES),
+        stdout: redactor.text(&truncate_string(&output.stdout, MAX_CMD_OUTPUT_BYTES)),
+        stderr: redactor.text(&truncate_string(&output.stderr, MAX_CMD_OUTPUT_BYTES)),
         error,
     }
 }
@@ -459,6 +632,10 @@ mod tests {
     use crate::platform::CommandOutput;
     use std::sync::atomic::{AtomicU64, Ordering};
 
+    fn home_redactor(home: &Path) -> Redactor {
+        Redactor::new(crate::privacy::Redaction::Home, home, [0; 32])
+    }
+
     #[test]
     fn write_fix_apply_log_writes_json_with_masked_paths() {
         static HOME_SEQ: AtomicU64 = AtomicU64::new(0);
@@ -498,6 +675,7 @@ mod tests {
         let finished_at = started_at;
         let log_path = write_fix_apply_log(
             &home,
+            &home_redactor(&home),
             started_at,
             finished_at,
             RiskLevel::R1,
@@ -538,6 +716,100 @@ mod tests {
             "errors={errors:?}"
         );
 
+        let strict = Redactor::new(crate::privacy::Redaction::Strict, &home, [1; 32]);
+        let outcome = ApplyOutcome {
+            moved: vec![TrashMoveRecord {
+                from: home.join("Clients/acme-app/node_modules"),
+                to: home.join(".Trash/node_modules"),
+            }],
+            skipped_missing: vec![],
+            errors: vec![],
+        };
+        let log_path = write_fix_apply_log(
+            &home,
+            &strict,
+            started_at,
+            finished_at,
+            RiskLevel::R1,
+            &actions,
+            &outcome,
+        )
+        .expect("write strict log");
+        let text = std::fs::read_to_string(&log_path).expect("read strict log");
+        assert!(
+            !text.contains("acme-app") && !text.contains("Clients"),
+            "{text}"
+        );
+        assert!(text.contains("/node_modules"), "{text}");
+
+        let _ = std::fs::remove_dir_all(&home);
+    }
+
+    #[test]
+    fn read_fix_apply_log_round_trips_moves_and_undo_log_records_restore() {
+        static HOME_SEQ: AtomicU64 = AtomicU64::new(0);
+
+        let seq = HOME_SEQ.fetch_add(1, Ordering::Relaxed);
+        let home = std::env::temp_dir().join(format!(
+            "macdiet-undo-log-test-{}-{seq}",
+            std::process::id()
+        ));
+        let _ = std::fs::remove_dir_all(&home);
+        std::fs::create_dir_all(&home).expect("create home");
+        assert_eq!(latest_log(&home, "fix-apply"), None);
+
+        let moved = vec![TrashMoveRecord {
+            from: home.join(".npm"),
+            to: home.join(".Trash/.npm"),
+        }];
+        let outcome = ApplyOutcome {
+            moved: moved.clone(),
+            skipped_missing: vec![],
+            errors: vec![],
+        };
+        let at = OffsetDateTime::now_utc();
+        let mut paths = Vec::new();
+        for offset in [0, 1] {
+            let at = at + time::Duration::seconds(offset);
+            let path = write_fix_apply_log(
+                &home,
+                &home_redactor(&home),
+                at,
+                at,
+                RiskLevel::R1,
+                &[],
+                &outcome,
+            )
+            .expect("write log");
+            paths.push(path);
+        }
+        assert_eq!(latest_log(&home, "fix-apply").as_ref(), paths.last());
+
+        let read = read_fix_apply_log(&paths[0], &home).expect("read log");
+        assert_eq!(read.len(), 1);
+        assert_eq!(read[0].from, moved[0].from);
+        assert_eq!(read[0].to, moved[0].to);
+
+        let restore = RestoreOutcome {
+            restored: vec![TrashMoveRecord {
+                from: home.join(".Trash/.npm"),
+                to: home.join(".npm"),
+            }],
+            skipped_missing: vec![],
+            conflicts: vec![home.join(".gradle")],
+            errors: vec![],
+        };
+        let undo_path = write_undo_log(&home, &home_redactor(&home), at, at, &paths[0], &restore)
+            .expect("write undo log");
+        assert_eq!(latest_log(&home, "undo").as_ref(), Some(&undo_path));
+        let v: serde_json::Value =
+            serde_json::from_slice(&std::fs::read(&undo_path).expect("read")).expect("json");
+        assert_eq!(v["command"], "undo");
+        assert_eq!(v["status"], "partial_error");
+        assert_eq!(v["outcome"]["restored"][0]["to"], "~/.npm");
+        assert_eq!(v["outcome"]["conflicts"][0], "~/.gradle");
+        assert!(read_fix_apply_log(&undo_path, &home).is_err());
+
         let _ = std::fs::remove_dir_all(&home);
     }
 
@@ -576,9 +848,16 @@ mod tests {
             stdout: "ok".to_string(),
             stderr: "".to_string(),
         };
-        let log_path =
-            write_fix_run_cmd_log(&home, started_at, finished_at, &action, Some(&out), None)
-                .expect("write log");
+        let log_path = write_fix_run_cmd_log(
+            &home,
+            &home_redactor(&home),
+            started_at,
+            finished_at,
+            &action,
+            Some(&out),
+            None,
+        )
+        .expect("write log");
 
         let bytes = std::fs::read(&log_path).expect("read log");
         let v: serde_json::Value = serde_json::from_slice(&bytes).expect("parse json");
@@ -639,9 +918,16 @@ mod tests {
             stdout: "Removing: ...\n".to_string(),
             stderr: "Warning: Skipping foo: most recent version 1.2.3 not installed\n".to_string(),
         };
-        let log_path =
-            write_fix_run_cmd_log(&home, started_at, finished_at, &action, Some(&out), None)
-                .expect("write log");
+        let log_path = write_fix_run_cmd_log(
+            &home,
+            &home_redactor(&home),
+            started_at,
+            finished_at,
+            &action,
+            Some(&out),
+            None,
+        )
+        .expect("write log");
 
         let bytes = std::fs::read(&log_path).expect("read log");
         let v: serde_json::Value = serde_json::from_slice(&bytes).expect("parse json");
@@ -692,6 +978,7 @@ mod tests {
         };
         let log_path = write_snapshots_thin_log(
             &home,
+            &home_redactor(&home),
             started_at,
             finished_at,
             123,
@@ -769,6 +1056,7 @@ mod tests {
 
         let log_path = write_snapshots_delete_log(
             &home,
+            &home_redactor(&home),
             started_at,
             finished_at,
             "name-or-uuid",
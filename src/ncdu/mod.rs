@@ -0,0 +1,374 @@
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result, anyhow, bail};
+use serde_json::{Value, json};
+
+use crate::scan::{TreeNode, TreeNodeKind};
+
+const NCDU_MAJOR_VERSION: u64 = 1;
+const NCDU_MINOR_VERSION: u64 = 2;
+
+#[derive(Debug, Clone)]
+pub struct NcduExport {
+    pub tree: TreeNode,
+    pub roots: Vec<PathBuf>,
+    pub timestamp: Option<i64>,
+    pub truncated: bool,
+}
+
+// ncdu の JSON エクスポート形式（https://dev.yorhel.nl/ncdu/jsonfmt）で書き出す。
+// ディレクトリは `[info, child...]`、ファイルは info オブジェクトのみ。
+// `macdiet` キーは ncdu からは無視され、import 時に元のスキャン root を復元するために使う。
+pub fn write_export(path: &Path, export: &NcduExport) -> Result<()> {
+    let file = std::fs::File::create(path)
+        .with_context(|| format!("ncdu エクスポートの作成に失敗しました: {}", path.display()))?;
+    let mut w = std::io::BufWriter::new(file);
+    let meta = json!({
+        "progname": "macdiet",
+        "progver": env!("CARGO_PKG_VERSION"),
+        "timestamp": export.timestamp.unwrap_or(0),
+        "macdiet": {
+            "roots": export
+                .roots
+                .iter()
+                .map(|r| r.display().to_string())
+                .collect::<Vec<_>>(),
+            "truncated": export.truncated,
+        },
+    });
+    write!(w, "[{NCDU_MAJOR_VERSION},{NCDU_MINOR_VERSION},")?;
+    serde_json::to_writer(&mut w, &meta)?;
+    w.write_all(b",\n")?;
+    write_node(&mut w, &export.tree)?;
+    w.write_all(b"]\n")?;
+    w.flush().with_context(|| {
+        format!(
+            "ncdu エクスポートの書き込みに失敗しました: {}",
+            path.display()
+        )
+    })?;
+    Ok(())
+}
+
+fn write_node(w: &mut impl Write, node: &TreeNode) -> Result<()> {
+    let mut info = serde_json::Map::new();
+    info.insert("name".to_string(), Value::from(node.name.clone()));
+    match node.kind {
+        TreeNodeKind::Excluded => {
+            info.insert("excluded".to_string(), Value::from("pattern"));
+        }
+        TreeNodeKind::OtherFs => {
+            info.insert("excluded".to_string(), Value::from("othfs"));
+        }
+        kind => {
+            info.insert("asize".to_string(), Value::from(node.bytes));
+            info.insert("dsize".to_string(), Value::from(node.disk_bytes));
+            if kind == TreeNodeKind::Other {
+                info.insert("notreg".to_string(), Value::from(true));
+            }
+        }
+    }
+    if node.read_error {
+        info.insert("read_error".to_string(), Value::from(true));
+    }
+
+    if node.kind != TreeNodeKind::Dir {
+        serde_json::to_writer(&mut *w, &info)?;
+        return Ok(());
+    }
+    w.write_all(b"[")?;
+    serde_json::to_writer(&mut *w, &info)?;
+    for child in &node.children {
+        w.write_all(b",\n")?;
+        write_node(w, child)?;
+    }
+    w.write_all(b"]")?;
+    Ok(())
+}
+
+pub fn read_export(path: &Path) -> Result<NcduExport> {
+    let bytes = std::fs::read(path)
+        .with_context(|| format!("ncdu エクスポートを開けません: {}", path.display()))?;
+    parse_export(&bytes).with_context(|| format!("ncdu エクスポート: {}", path.display()))
+}
+
+// ディレクトリの入れ子は JSON の配列の入れ子になり、serde_json の Value では 128 段を超えると
+// 読めない。配列だけを自前のスタックで辿り、info などの配列以外の値は 1 つずつ serde_json で読む。
+fn parse_export(bytes: &[u8]) -> Result<NcduExport> {
+    let mut r = JsonReader { bytes, pos: 0 };
+    if !r.eat(b'[') {
+        bail!("ncdu エクスポートの形式が不正です（先頭が配列ではありません）");
+    }
+    let mut header = Vec::new();
+    while header.len() < 3 {
+        header.push(r.value()?);
+        if r.peek() == Some(b']') {
+            bail!("ncdu エクスポートの形式が不正です（要素数が不足しています）");
+        }
+        r.expect_separator()?;
+    }
+    let major = header[0].as_u64();
+    if major != Some(NCDU_MAJOR_VERSION) {
+        bail!(
+            "未対応の ncdu エクスポート形式です（majorver={}）",
+            header[0]
+        );
+    }
+    let meta = header[2].as_object();
+    let timestamp = meta
+        .and_then(|m| m.get("timestamp"))
+        .and_then(Value::as_i64);
+    let roots = meta
+        .and_then(|m| m.get("macdiet"))
+        .and_then(|m| m.get("roots"))
+        .and_then(Value::as_array)
+        .map(|roots| {
+            roots
+                .iter()
+                .filter_map(Value::as_str)
+                .map(PathBuf::from)
+                .collect::<Vec<_>>()
+        })
+        .unwrap_or_default();
+    let truncated = meta
+        .and_then(|m| m.get("macdiet"))
+        .and_then(|m| m.get("truncated"))
+        .and_then(Value::as_bool)
+        .unwrap_or(false);
+
+    let tree = parse_tree(&mut r)?;
+    if !r.eat(b']') || r.peek().is_some() {
+        return Err(r.syntax_error());
+    }
+    if tree.kind != TreeNodeKind::Dir {
+        bail!("ncdu エクスポートの root がディレクトリではありません");
+    }
+    let roots = if roots.is_empty() {
+        vec![PathBuf::from(&tree.name)]
+    } else {
+        roots
+    };
+    Ok(NcduExport {
+        tree,
+        roots,
+        timestamp,
+        truncated,
+    })
+}
+
+struct JsonReader<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl JsonReader<'_> {
+    fn peek(&mut self) -> Option<u8> {
+        while let Some(b) = self.bytes.get(self.pos)
+            && b.is_ascii_whitespace()
+        {
+            self.pos += 1;
+        }
+        self.bytes.get(self.pos).copied()
+    }
+
+    fn eat(&mut self, b: u8) -> bool {
+        if self.peek() == Some(b) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn syntax_error(&self) -> anyhow::Error {
+        anyhow!(
+            "ncdu エクスポートの JSON が不正です（{} バイト目）",
+            self.pos
+        )
+    }
+
+    // 配列の要素の後ろの `,` を読む。
+    fn expect_separator(&mut self) -> Result<()> {
+        if self.eat(b',') {
+            Ok(())
+        } else {
+            Err(self.syntax_error())
+        }
+    }
+
+    // 配列以外の値を 1 つ読む（info オブジェクトやヘッダの数値など、入れ子の浅いもの）。
+    fn value(&mut self) -> Result<Value> {
+        if matches!(self.peek(), None | Some(b'[')) {
+            return Err(self.syntax_error());
+        }
+        let mut stream =
+            serde_json::Deserializer::from_slice(&self.bytes[self.pos..]).into_iter::<Value>();
+        let value = match stream.next() {
+            Some(Ok(value)) => value,
+            Some(Err(err)) => {
+                return Err(anyhow!(
+                    "ncdu エクスポートの JSON が不正です（{} バイト目）: {err}",
+                    self.pos
+                ));
+            }
+            None => return Err(self.syntax_error()),
+        };
+        self.pos += stream.byte_offset();
+        Ok(value)
+    }
+}
+
+// `[info, child...]`（ディレクトリ）と `info`（それ以外）の入れ子を、開いているディレクトリのスタックで組み立てる。
+fn parse_tree(r: &mut JsonReader<'_>) -> Result<TreeNode> {
+    let mut open: Vec<TreeNode> = Vec::new();
+    loop {
+        let mut done = if r.eat(b'[') {
+            if r.peek() == Some(b']') {
+                bail!("ncdu エクスポートのディレクトリ要素が空です");
+            }
+            open.push(parse_info(&r.value()?, true)?);
+            None
+        } else {
+            let leaf = parse_info(&r.value()?, false)?;
+            match open.last_mut() {
+                Some(parent) => {
+                    parent.children.push(leaf);
+                    None
+                }
+                None => Some(leaf),
+            }
+        };
+        // 閉じたディレクトリを親に付け、次の要素（`,`）か root の終わりまで進める。
+        while done.is_none() {
+            if r.eat(b',') {
+                if open.is_empty() {
+                    return Err(r.syntax_error());
+                }
+                break;
+            }
+            if !r.eat(b']') {
+                return Err(r.syntax_error());
+            }
+            let Some(dir) = open.pop() else {
+                return Err(r.syntax_error());
+            };
+            match open.last_mut() {
+                Some(parent) => parent.children.push(dir),
+                None => done = Some(dir),
+            }
+        }
+        if let Some(root) = done {
+            return Ok(root);
+        }
+    }
+}
+
+fn parse_info(info: &Value, is_dir: bool) -> Result<TreeNode> {
+    let Some(info) = info.as_object() else {
+        return Err(anyhow!("ncdu エクスポートの要素が不正です: {info}"));
+    };
+    let name = info
+        .get("name")
+        .and_then(Value::as_str)
+        .ok_or_else(|| anyhow!("ncdu エクスポートの要素に name がありません"))?
+        .to_string();
+    let bytes = info.get("asize").and_then(Value::as_u64);
+    let disk_bytes = info.get("dsize").and_then(Value::as_u64).unwrap_or(0);
+
+    let kind = if is_dir {
+        TreeNodeKind::Dir
+    } else if let Some(reason) = info.get("excluded") {
+        if reason.as_str() == Some("othfs") {
+            TreeNodeKind::OtherFs
+        } else {
+            TreeNodeKind::Excluded
+        }
+    } else if info.get("notreg").and_then(Value::as_bool) == Some(true) {
+        TreeNodeKind::Other
+    } else {
+        TreeNodeKind::File
+    };
+
+    Ok(TreeNode {
+        name,
+        kind,
+        bytes: bytes.unwrap_or(disk_bytes),
+        disk_bytes,
+        read_error: info.get("read_error").and_then(Value::as_bool) == Some(true),
+        children: Vec::new(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_export_reads_ncdu_format() {
+        let export = parse_export(
+            br#"[1,2,{"progname":"ncdu","progver":"1.19","timestamp":1700000000},
+            [{"name":"/data","asize":4096,"dsize":4096},
+              {"name":"a.bin","asize":100,"dsize":4096},
+              {"name":"link","asize":10,"notreg":true},
+              {"name":"skip","excluded":"pattern"},
+              [{"name":"sub","asize":4096,"read_error":true},
+                {"name":"b.bin","dsize":8192}]]]"#,
+        )
+        .expect("parse");
+        assert_eq!(export.roots, vec![PathBuf::from("/data")]);
+        assert_eq!(export.timestamp, Some(1_700_000_000));
+        let tree = &export.tree;
+        assert_eq!(tree.kind, TreeNodeKind::Dir);
+        let kinds: Vec<TreeNodeKind> = tree.children.iter().map(|c| c.kind).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                TreeNodeKind::File,
+                TreeNodeKind::Other,
+                TreeNodeKind::Excluded,
+                TreeNodeKind::Dir,
+            ]
+        );
+        let sub = tree.find(Path::new("sub")).expect("sub");
+        assert!(sub.read_error);
+        assert_eq!(sub.children[0].bytes, 8192);
+
+        assert!(parse_export(br#"[2,0,{},[{"name":"/"}]]"#).is_err());
+        for broken in [
+            &br#"[1,2,{},[{"name":"/"}]"#[..],
+            br#"[1,2,{},[{"name":"/"},]]"#,
+            br#"[1,2,{},[{"name":"/"}]] x"#,
+            br#"[1,2,{}]"#,
+            br#"[1,2,{},[]]"#,
+        ] {
+            assert!(
+                parse_export(broken).is_err(),
+                "{}",
+                String::from_utf8_lossy(broken)
+            );
+        }
+    }
+
+    #[test]
+    fn parse_export_reads_directories_nested_deeper_than_serde_json_limit() {
+        let depth = 2000;
+        let mut json = String::from(r#"[1,2,{},"#);
+        for i in 0..depth {
+            json.push_str(&format!(r#"[{{"name":"d{i}","asize":1}},"#));
+        }
+        json.push_str(r#"{"name":"leaf.bin","asize":7}"#);
+        json.push_str(&"]".repeat(depth + 1));
+
+        let export = parse_export(json.as_bytes()).expect("parse");
+        let mut node = &export.tree;
+        let mut levels = 0;
+        while let Some(child) = node.children.first() {
+            node = child;
+            levels += 1;
+        }
+        assert_eq!(levels, depth);
+        assert_eq!(node.name, "leaf.bin");
+        assert_eq!(node.bytes, 7);
+    }
+}
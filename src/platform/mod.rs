@@ -1,6 +1,7 @@
 use std::io::Read;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::process::{Command, Stdio};
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::time::Duration;
 
 use anyhow::{Context, Result, anyhow};
@@ -185,6 +186,151 @@ fn home_dir_for_uid(_uid: u32) -> Option<PathBuf> {
     None
 }
 
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MountInfo {
+    pub mount_point: PathBuf,
+    pub device: String,
+    pub fs_type: String,
+    pub dev: u64,
+}
+
+pub fn mount_info(path: &Path) -> Option<MountInfo> {
+    use std::os::unix::fs::MetadataExt;
+
+    let path = std::fs::canonicalize(path).ok()?;
+    let dev = std::fs::metadata(&path).ok()?.dev();
+    let (mount_point, device, fs_type) = mount_entry(&path)?;
+    Some(MountInfo {
+        mount_point,
+        device,
+        fs_type,
+        dev,
+    })
+}
+
+#[cfg(target_os = "macos")]
+fn mount_entry(path: &Path) -> Option<(PathBuf, String, String)> {
+    use std::ffi::{CStr, CString};
+    use std::os::unix::ffi::OsStrExt;
+
+    let c_path = CString::new(path.as_os_str().as_bytes()).ok()?;
+    unsafe {
+        let mut st: libc::statfs = std::mem::zeroed();
+        if libc::statfs(c_path.as_ptr(), &mut st) != 0 {
+            return None;
+        }
+        let on = CStr::from_ptr(st.f_mntonname.as_ptr()).to_string_lossy();
+        let from = CStr::from_ptr(st.f_mntfromname.as_ptr()).to_string_lossy();
+        let fs = CStr::from_ptr(st.f_fstypename.as_ptr()).to_string_lossy();
+        Some((
+            PathBuf::from(on.as_ref()),
+            from.into_owned(),
+            fs.into_owned(),
+        ))
+    }
+}
+
+#[cfg(not(target_os = "macos"))]
+fn mount_entry(path: &Path) -> Option<(PathBuf, String, String)> {
+    let mounts = std::fs::read_to_string("/proc/self/mounts").ok()?;
+    parse_mount_table(&mounts)
+        .into_iter()
+        .filter(|(mount_point, _, _)| path.starts_with(mount_point))
+        .max_by_key(|(mount_point, _, _)| mount_point.components().count())
+}
+
+// /proc/self/mounts 形式（`device mount_point fs_type options ...`、空白は `\040` でエスケープ）。
+#[cfg_attr(target_os = "macos", allow(dead_code))]
+fn parse_mount_table(table: &str) -> Vec<(PathBuf, String, String)> {
+    fn unescape(field: &str) -> String {
+        let bytes = field.as_bytes();
+        let mut out = Vec::with_capacity(bytes.len());
+        let mut i = 0;
+        while i < bytes.len() {
+            if bytes[i] == b'\\'
+                && i + 3 < bytes.len()
+                && let Ok(v) = u8::from_str_radix(&field[i + 1..i + 4], 8)
+            {
+                out.push(v);
+                i += 4;
+                continue;
+            }
+            out.push(bytes[i]);
+            i += 1;
+        }
+        String::from_utf8_lossy(&out).into_owned()
+    }
+
+    table
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split_whitespace();
+            let device = unescape(fields.next()?);
+            let mount_point = unescape(fields.next()?);
+            let fs_type = fields.next()?.to_string();
+            Some((PathBuf::from(mount_point), device, fs_type))
+        })
+        .collect()
+}
+
+// `/Volumes` 直下のマウント（起動ボリュームへのシンボリックリンクは除く）。
+pub fn external_volumes() -> Vec<PathBuf> {
+    let Ok(rd) = std::fs::read_dir("/Volumes") else {
+        return Vec::new();
+    };
+    let mut volumes: Vec<PathBuf> = rd
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_ok_and(|t| t.is_dir()))
+        .map(|e| e.path())
+        .collect();
+    volumes.sort();
+    volumes
+}
+
+static INTERRUPTED: AtomicBool = AtomicBool::new(false);
+
+extern "C" fn on_interrupt(_signal: libc::c_int) {
+    INTERRUPTED.store(true, Ordering::SeqCst);
+}
+
+// SIGINT（Ctrl-C）で即終了せず、フラグを立てるだけにする。長時間の監視を最後のレポートまで
+// 出し切るために使う。
+pub fn interrupt_flag() -> &'static AtomicBool {
+    unsafe {
+        libc::signal(
+            libc::SIGINT,
+            on_interrupt as extern "C" fn(libc::c_int) as libc::sighandler_t,
+        );
+    }
+    &INTERRUPTED
+}
+
+pub fn hostname() -> Option<String> {
+    let mut buf = [0u8; 256];
+    let rc = unsafe { libc::gethostname(buf.as_mut_ptr().cast(), buf.len()) };
+    if rc != 0 {
+        return None;
+    }
+    let len = buf.iter().position(|b| *b == 0).unwrap_or(buf.len());
+    let name = String::from_utf8_lossy(&buf[..len]).trim().to_string();
+    (!name.is_empty()).then_some(name)
+}
+
+// 親プロセスから受け継いだ書き込み用 fd（`--events-fd`）を開く。閉じている fd は None。
+// 元の fd は所有せず複製して使うので、返した File を閉じても受け継いだ fd は開いたまま残る。
+pub fn inherited_fd_writer(fd: i32) -> Option<std::fs::File> {
+    use std::os::fd::FromRawFd;
+
+    if fd < 0 {
+        return None;
+    }
+    let dup = unsafe { libc::fcntl(fd, libc::F_DUPFD_CLOEXEC, 0) };
+    if dup == -1 {
+        return None;
+    }
+    Some(unsafe { std::fs::File::from_raw_fd(dup) })
+}
+
 pub fn os_info(timeout: Duration) -> OsInfo {
     #[cfg(target_os = "macos")]
     {
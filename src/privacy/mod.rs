@@ -0,0 +1,533 @@
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::core::{ActionKind, EvidenceKind, Report};
+
+// none: パスをそのまま、home: home 配下を `~` に、strict: さらに既知でないパス要素・
+// シミュレータのデバイス名・Docker イメージ名をソルト付きハッシュ（`#` + 16 進 8 桁）に置き換える。
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Redaction {
+    None,
+    #[default]
+    Home,
+    Strict,
+}
+
+impl std::str::FromStr for Redaction {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "none" => Ok(Redaction::None),
+            "home" => Ok(Redaction::Home),
+            "strict" => Ok(Redaction::Strict),
+            other => Err(format!(
+                "redaction が不正です: {other}（none|home|strict を指定してください）"
+            )),
+        }
+    }
+}
+
+// macOS と開発ツールが決まって作るディレクトリ名。これ以外のパス要素は strict でハッシュ化する。
+const STANDARD_COMPONENTS: &[&str] = &[
+    "~",
+    "Users",
+    "Volumes",
+    "System",
+    "Library",
+    "Applications",
+    "private",
+    "var",
+    "tmp",
+    "opt",
+    "usr",
+    "local",
+    "dev",
+    "homebrew",
+    "Cellar",
+    "Caskroom",
+    "Developer",
+    "Xcode",
+    "DerivedData",
+    "Archives",
+    "Products",
+    "UserData",
+    "Previews",
+    "iOS DeviceSupport",
+    "watchOS DeviceSupport",
+    "tvOS DeviceSupport",
+    "iOS Device Logs",
+    "CoreSimulator",
+    "Devices",
+    "XCTestDevices",
+    "Shared",
+    "Documentation",
+    "DocSets",
+    "com.apple.dt.Xcode",
+    "Caches",
+    "Logs",
+    "Containers",
+    "Group Containers",
+    "Application Support",
+    "Preferences",
+    "iTunes",
+    "iPhone Software Updates",
+    "iPad Software Updates",
+    "Documents",
+    "Downloads",
+    "Desktop",
+    "Movies",
+    "Music",
+    "Pictures",
+    "Public",
+    ".Trash",
+    ".config",
+    "macdiet",
+    ".npm",
+    "_cacache",
+    ".cache",
+    ".gradle",
+    "caches",
+    ".cargo",
+    "registry",
+    "git",
+    "db",
+    "checkouts",
+    "src",
+    "cache",
+    "index",
+    ".pnpm-store",
+    "pnpm",
+    "store",
+    "Yarn",
+    ".nvm",
+    "versions",
+    "node",
+    ".pyenv",
+    ".rbenv",
+    ".asdf",
+    "installs",
+    ".local",
+    "share",
+    "mise",
+    "Homebrew",
+    "com.docker.docker",
+    "Data",
+    "vms",
+    "node_modules",
+    "target",
+    "build",
+    ".git",
+];
+
+const SALT_FILE: &str = ".config/macdiet/redaction-salt";
+
+#[derive(Debug, Clone)]
+pub struct Redactor {
+    level: Redaction,
+    home_dir: PathBuf,
+    salt: [u8; 32],
+}
+
+impl Redactor {
+    pub fn new(level: Redaction, home_dir: &Path, salt: [u8; 32]) -> Self {
+        Self {
+            level,
+            home_dir: home_dir.to_path_buf(),
+            salt,
+        }
+    }
+
+    // strict のときだけ ~/.config/macdiet/redaction-salt を読む（無ければ作る）。
+    // 同じマシンでは同じ名前が毎回同じハッシュになるので、レポート同士を比較できる。
+    pub fn load(level: Redaction, home_dir: &Path) -> Result<Self> {
+        let salt = if level == Redaction::Strict {
+            load_or_create_salt(&home_dir.join(SALT_FILE))?
+        } else {
+            [0; 32]
+        };
+        Ok(Self::new(level, home_dir, salt))
+    }
+
+    pub fn level(&self) -> Redaction {
+        self.level
+    }
+
+    // ローカルの絶対パスを出力用の文字列にする（ログ向け）。
+    pub fn local_path(&self, path: &Path) -> String {
+        if self.level == Redaction::None {
+            return path.display().to_string();
+        }
+        let masked = match path.strip_prefix(&self.home_dir) {
+            Ok(rest) if rest.as_os_str().is_empty() => "~".to_string(),
+            Ok(rest) => format!("~/{}", rest.display()),
+            Err(_) => path.display().to_string(),
+        };
+        self.path(&masked)
+    }
+
+    // 出力用のパス文字列（`~/...` または絶対パス）の既知でない要素をハッシュ化する。
+    pub fn path(&self, path: &str) -> String {
+        if self.level != Redaction::Strict {
+            return path.to_string();
+        }
+        path.split('/')
+            .map(|c| {
+                if c.is_empty() || is_standard_component(c) {
+                    c.to_string()
+                } else {
+                    self.hash(c)
+                }
+            })
+            .collect::<Vec<_>>()
+            .join("/")
+    }
+
+    // 自由文中のパス・`名前 (UUID)` 形式のデバイス名・Docker イメージ参照をハッシュ化する。
+    pub fn text(&self, text: &str) -> String {
+        if self.level != Redaction::Strict {
+            return text.to_string();
+        }
+        text.split_inclusive('\n')
+            .map(|line| {
+                let line = self.redact_device_name(line);
+                let mut out = String::with_capacity(line.len());
+                let mut rest = line.as_str();
+                while !rest.is_empty() {
+                    let end = rest.find(char::is_whitespace).unwrap_or(rest.len());
+                    out.push_str(&self.redact_token(&rest[..end]));
+                    let ws_end = rest[end..]
+                        .find(|c: char| !c.is_whitespace())
+                        .map_or(rest.len(), |i| end + i);
+                    out.push_str(&rest[end..ws_end]);
+                    rest = &rest[ws_end..];
+                }
+                out
+            })
+            .collect()
+    }
+
+    // host は `report --host` などで明示したときだけ入るので、strict 以外ではそのまま出す。
+    pub fn host(&self, host: &str) -> String {
+        if self.level == Redaction::Strict {
+            self.hash(host)
+        } else {
+            host.to_string()
+        }
+    }
+
+    // 共有用に Report を書き換える。fix の実行には元の Report を使うこと。
+    pub fn report(&self, report: &mut Report) {
+        report.host = report.host.as_deref().map(|h| self.host(h));
+        if self.level != Redaction::Strict {
+            return;
+        }
+        // 空白を含むパスは自由文の単語分割では拾えないので、構造化されたパスを先に置き換える。
+        let mut known: Vec<String> = Vec::new();
+        for f in &report.findings {
+            for ev in &f.evidence {
+                if ev.kind == EvidenceKind::Path {
+                    known.push(ev.value.clone());
+                }
+            }
+        }
+        for a in &report.actions {
+            match &a.kind {
+                ActionKind::TrashMove { paths } | ActionKind::Delete { paths } => {
+                    known.extend(paths.iter().cloned());
+                }
+                ActionKind::OpenInFinder { path } => known.push(path.clone()),
+                _ => {}
+            }
+        }
+        known.retain(|p| p.len() > 1 && (p.starts_with("~/") || p.starts_with('/')));
+        known.sort();
+        known.dedup();
+        known.sort_by_key(|p| std::cmp::Reverse(p.len()));
+        let replacements: Vec<(String, String)> = known
+            .into_iter()
+            .map(|p| {
+                let redacted = self.path(&p);
+                (p, redacted)
+            })
+            .collect();
+        let text = |s: &str| {
+            let mut s = s.to_string();
+            for (from, to) in &replacements {
+                if s.contains(from.as_str()) {
+                    s = s.replace(from.as_str(), to);
+                }
+            }
+            self.text(&s)
+        };
+
+        for note in &mut report.summary.notes {
+            *note = text(note);
+        }
+        for f in &mut report.findings {
+            f.id = text(&f.id);
+            f.title = text(&f.title);
+            for ev in &mut f.evidence {
+                ev.value = match ev.kind {
+                    EvidenceKind::Path => self.path(&ev.value),
+                    _ => text(&ev.value),
+                };
+            }
+            for r in &mut f.recommended_actions {
+                r.id = text(&r.id);
+            }
+        }
+        for a in &mut report.actions {
+            a.id = text(&a.id);
+            a.title = text(&a.title);
+            for id in &mut a.related_findings {
+                *id = text(id);
+            }
+            for note in &mut a.notes {
+                *note = text(note);
+            }
+            match &mut a.kind {
+                ActionKind::TrashMove { paths } | ActionKind::Delete { paths } => {
+                    for p in paths {
+                        *p = self.path(p);
+                    }
+                }
+                ActionKind::RunCmd { args, .. } => {
+                    for arg in args {
+                        *arg = text(arg);
+                    }
+                }
+                ActionKind::OpenInFinder { path } => *path = self.path(path),
+                ActionKind::ShowInstructions { markdown } => *markdown = text(markdown),
+            }
+        }
+        for v in &mut report.volumes {
+            v.path = self.path(&v.path);
+            v.mount_point = self.path(&v.mount_point);
+        }
+    }
+
+    fn hash(&self, value: &str) -> String {
+        let hex = blake3::keyed_hash(&self.salt, value.as_bytes()).to_hex();
+        format!("#{}", &hex[..8])
+    }
+
+    fn redact_token(&self, token: &str) -> String {
+        const OPEN: &[char] = &['(', '（', '`', '"', '\'', '[', '「'];
+        const CLOSE: &[char] = &[
+            ')', '）', '`', '"', '\'', ']', '」', ',', '、', '。', ':', ';',
+        ];
+        if token.contains("://") {
+            return token.to_string();
+        }
+        let core_start = token.len() - token.trim_start_matches(OPEN).len();
+        let core_end = token.trim_end_matches(CLOSE).len().max(core_start);
+        let (head, core, tail) = (
+            &token[..core_start],
+            &token[core_start..core_end],
+            &token[core_end..],
+        );
+
+        let path_start = core.char_indices().find_map(|(i, c)| {
+            let starts_path = (c == '/' && core.len() > i + 1) || core[i..].starts_with("~/");
+            let at_boundary = i == 0 || core[..i].ends_with(['=', ':']);
+            (starts_path && at_boundary).then_some(i)
+        });
+        if let Some(i) = path_start {
+            return format!("{head}{}{}{tail}", &core[..i], self.path(&core[i..]));
+        }
+        if is_image_reference(core) {
+            return format!("{head}{}{tail}", self.hash(core));
+        }
+        token.to_string()
+    }
+
+    // `xcrun simctl list` の `    iPhone 15 (UUID) (Shutdown)` のような行のデバイス名。
+    fn redact_device_name(&self, line: &str) -> String {
+        let Some(open) = line.match_indices(" (").map(|(i, _)| i).find(|&i| {
+            let after = &line[i + 2..];
+            after.get(..36).is_some_and(is_uuid) && after[36..].starts_with(')')
+        }) else {
+            return line.to_string();
+        };
+        let indent = line.len() - line.trim_start().len();
+        let name = &line[indent..open];
+        if name.is_empty() || name.starts_with('#') {
+            return line.to_string();
+        }
+        format!("{}{}{}", &line[..indent], self.hash(name), &line[open..])
+    }
+}
+
+// strict で伏せた要素を含むパスか（ログから元の場所を復元できない）。
+pub fn is_redacted_path(path: &str) -> bool {
+    path.split('/').any(is_hashed_component)
+}
+
+fn is_hashed_component(c: &str) -> bool {
+    c.len() == 9 && c.starts_with('#') && c[1..].chars().all(|ch| ch.is_ascii_hexdigit())
+}
+
+fn is_standard_component(c: &str) -> bool {
+    if STANDARD_COMPONENTS.contains(&c) {
+        return true;
+    }
+    // 既にハッシュ化した要素・バージョン番号・ディスク識別子は残す。
+    let is_hash = is_hashed_component(c);
+    let version = c.strip_prefix('v').unwrap_or(c);
+    let is_version = !version.is_empty()
+        && version.starts_with(|ch: char| ch.is_ascii_digit())
+        && version.chars().all(|ch| ch.is_ascii_digit() || ch == '.');
+    let is_disk = c.strip_prefix("disk").is_some_and(|rest| {
+        !rest.is_empty() && rest.chars().all(|ch| ch.is_ascii_digit() || ch == 's')
+    });
+    is_hash || is_version || is_disk
+}
+
+fn is_uuid(s: &str) -> bool {
+    s.len() == 36
+        && s.char_indices().all(|(i, c)| match i {
+            8 | 13 | 18 | 23 => c == '-',
+            _ => c.is_ascii_hexdigit(),
+        })
+}
+
+// `acme/web:1.2` や `redis:7` のようなタグ（または digest）付きのイメージ参照。
+fn is_image_reference(s: &str) -> bool {
+    let (name, reference) = match s.split_once('@') {
+        Some((name, digest)) => (name, digest.strip_prefix("sha256:").unwrap_or("")),
+        None => match s.rsplit_once(':') {
+            Some((name, tag)) => (name, tag),
+            None => return false,
+        },
+    };
+    let name_ok = !name.is_empty()
+        && name.starts_with(|c: char| c.is_ascii_lowercase() || c.is_ascii_digit())
+        && name.chars().any(|c| c.is_ascii_lowercase())
+        && name.chars().all(|c| {
+            c.is_ascii_lowercase() || c.is_ascii_digit() || matches!(c, '.' | '_' | '-' | '/')
+        });
+    let reference_ok = !reference.is_empty()
+        && reference.len() <= 128
+        && reference
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '_' | '-'));
+    name_ok && reference_ok
+}
+
+fn load_or_create_salt(path: &Path) -> Result<[u8; 32]> {
+    if let Ok(s) = std::fs::read_to_string(path)
+        && let Some(salt) = parse_hex_salt(s.trim())
+    {
+        return Ok(salt);
+    }
+    let mut salt = [0u8; 32];
+    {
+        use std::io::Read;
+        std::fs::File::open("/dev/urandom")
+            .and_then(|mut f| f.read_exact(&mut salt))
+            .context("ソルトの生成に失敗しました（/dev/urandom）")?;
+    }
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .with_context(|| format!("ディレクトリの作成に失敗しました: {}", parent.display()))?;
+    }
+    let hex: String = salt.iter().map(|b| format!("{b:02x}")).collect();
+    std::fs::write(path, format!("{hex}\n"))
+        .with_context(|| format!("ソルトの書き込みに失敗しました: {}", path.display()))?;
+    Ok(salt)
+}
+
+fn parse_hex_salt(s: &str) -> Option<[u8; 32]> {
+    if s.len() != 64 {
+        return None;
+    }
+    let mut salt = [0u8; 32];
+    for (i, byte) in salt.iter_mut().enumerate() {
+        *byte = u8::from_str_radix(s.get(i * 2..i * 2 + 2)?, 16).ok()?;
+    }
+    Some(salt)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn strict() -> Redactor {
+        Redactor::new(Redaction::Strict, Path::new("/Users/alice"), [7; 32])
+    }
+
+    #[test]
+    fn strict_hashes_unknown_path_components_stably() {
+        let r = strict();
+        let redacted = r.path("~/Projects/acme-app/node_modules");
+        let parts: Vec<&str> = redacted.split('/').collect();
+        assert_eq!(parts[0], "~");
+        assert!(
+            parts[1].starts_with('#') && parts[1].len() == 9,
+            "{redacted}"
+        );
+        assert_eq!(parts[3], "node_modules");
+        assert_eq!(r.path("~/Projects/acme-app/node_modules"), redacted);
+        assert_eq!(
+            r.path("~/Library/Developer/Xcode/DerivedData"),
+            "~/Library/Developer/Xcode/DerivedData"
+        );
+        assert_eq!(r.path(&redacted), redacted);
+        assert_eq!(
+            r.local_path(Path::new("/Users/alice/.nvm/versions/node/v18.17.0")),
+            "~/.nvm/versions/node/v18.17.0"
+        );
+        assert!(!r.local_path(Path::new("/Users/bob/x")).contains("bob"));
+
+        let other = Redactor::new(Redaction::Strict, Path::new("/Users/alice"), [8; 32]);
+        assert_ne!(
+            other.path("~/Projects/acme-app"),
+            r.path("~/Projects/acme-app")
+        );
+
+        let home = Redactor::new(Redaction::Home, Path::new("/Users/alice"), [7; 32]);
+        assert_eq!(home.path("~/Projects/acme-app"), "~/Projects/acme-app");
+        assert_eq!(
+            home.local_path(Path::new("/Users/alice/Projects")),
+            "~/Projects"
+        );
+        let none = Redactor::new(Redaction::None, Path::new("/Users/alice"), [7; 32]);
+        assert_eq!(
+            none.local_path(Path::new("/Users/alice/Projects")),
+            "/Users/alice/Projects"
+        );
+    }
+
+    #[test]
+    fn strict_text_redacts_paths_devices_and_images() {
+        let r = strict();
+        let out = r.text(
+            "上位ディレクトリ: ~/Clients/acme root=~/Clients max_depth=3 (https://example.com/acme)\n    Alice's iPhone (0A1B2C3D-0000-4000-8000-00000000ABCD) (Shutdown)\nacme/web:1.2 redis:7 count: 3",
+        );
+        assert!(!out.contains("acme/") && !out.contains("Clients"), "{out}");
+        assert!(out.contains("max_depth=3"), "{out}");
+        assert!(out.contains("https://example.com/acme"), "{out}");
+        assert!(!out.contains("Alice"), "{out}");
+        assert!(
+            out.contains("(0A1B2C3D-0000-4000-8000-00000000ABCD) (Shutdown)"),
+            "{out}"
+        );
+        assert!(!out.contains("redis:7"), "{out}");
+        assert!(out.contains("count: 3"), "{out}");
+        assert_eq!(r.text("plain"), "plain");
+    }
+
+    #[test]
+    fn salt_round_trips_through_hex() {
+        let salt: [u8; 32] = std::array::from_fn(|i| i as u8);
+        let hex: String = salt.iter().map(|b| format!("{b:02x}")).collect();
+        assert_eq!(parse_hex_salt(&hex), Some(salt));
+        assert_eq!(parse_hex_salt("zz"), None);
+        assert_eq!("STRICT".parse::<Redaction>(), Ok(Redaction::Strict));
+        assert!("full".parse::<Redaction>().is_err());
+    }
+}
@@ -11,6 +11,11 @@ pub struct RuleContext {
     pub timeout: Duration,
     pub deadline: Option<Instant>,
     pub privacy_mask_home: bool,
+    pub source_roots: Vec<PathBuf>,
+    pub size_cache: Option<std::sync::Arc<crate::cache::SizeCache>>,
+    pub sizer: crate::scan::Sizer,
+    pub cargo_stale_crate_days: u64,
+    pub events: Option<crate::events::EventSink>,
 }
 
 impl RuleContext {
@@ -31,30 +36,84 @@ pub struct RuleOutput {
 
 pub fn doctor_rules(ctx: &RuleContext) -> Vec<RuleOutput> {
     let mut out = Vec::new();
-    out.extend(xcode_derived_data(ctx));
-    out.extend(coresimulator_devices(ctx));
-    out.extend(xcode_archives(ctx));
-    out.extend(xcode_device_support(ctx));
-    out.extend(xcode_docsets(ctx));
-    out.extend(xcode_device_logs(ctx));
-    out.extend(docker_desktop_storage(ctx));
-    out.extend(homebrew_cache(ctx));
-    out.extend(cargo_registry_cache(ctx));
-    out.extend(cargo_git_cache(ctx));
-    out.extend(gradle_caches(ctx));
-    out.extend(npm_cache(ctx));
-    out.extend(yarn_cache(ctx));
-    out.extend(pnpm_store_cache(ctx));
+    run_rule(ctx, &mut out, "xcode-derived-data", || {
+        xcode_derived_data(ctx)
+    });
+    run_rule(ctx, &mut out, "coresimulator-devices", || {
+        coresimulator_devices(ctx)
+    });
+    run_rule(ctx, &mut out, "xcode-archives", || xcode_archives(ctx));
+    run_rule(ctx, &mut out, "xcode-device-support", || {
+        xcode_device_support(ctx)
+    });
+    run_rule(ctx, &mut out, "xcode-docsets", || xcode_docsets(ctx));
+    run_rule(ctx, &mut out, "xcode-device-logs", || {
+        xcode_device_logs(ctx)
+    });
+    run_rule(ctx, &mut out, "xctest-devices", || xctest_devices(ctx));
+    run_rule(ctx, &mut out, "xcode-previews", || xcode_previews(ctx));
+    run_rule(ctx, &mut out, "xcode-caches", || xcode_caches(ctx));
+    run_rule(ctx, &mut out, "docker-desktop-storage", || {
+        docker_desktop_storage(ctx)
+    });
+    run_rule(ctx, &mut out, "homebrew-cache", || homebrew_cache(ctx));
+    run_rule(ctx, &mut out, "homebrew-cleanup-candidates", || {
+        homebrew_cleanup_candidates(ctx)
+    });
+    run_rule(ctx, &mut out, "cargo-registry-cache", || {
+        cargo_registry_cache(ctx)
+    });
+    run_rule(ctx, &mut out, "cargo-git-cache", || cargo_git_cache(ctx));
+    run_rule(ctx, &mut out, "gradle-caches", || gradle_caches(ctx));
+    run_rule(ctx, &mut out, "npm-cache", || npm_cache(ctx));
+    run_rule(ctx, &mut out, "yarn-cache", || yarn_cache(ctx));
+    run_rule(ctx, &mut out, "pnpm-store-cache", || pnpm_store_cache(ctx));
+    run_rule(ctx, &mut out, "language-versions", || {
+        language_versions(ctx)
+    });
+    run_rule(ctx, &mut out, "installer-leftovers", || {
+        installer_leftovers(ctx)
+    });
     out
 }
 
 pub fn snapshots_rules(ctx: &RuleContext) -> Vec<RuleOutput> {
     let mut out = Vec::new();
-    out.push(tm_local_snapshots_status(ctx));
-    out.push(apfs_snapshots_status(ctx));
+    run_rule(ctx, &mut out, "tm-local-snapshots-status", || {
+        [tm_local_snapshots_status(ctx)]
+    });
+    run_rule(ctx, &mut out, "apfs-snapshots-status", || {
+        [apfs_snapshots_status(ctx)]
+    });
     out
 }
 
+// ルールを 1 つ実行し、ctx.events があれば開始/終了イベントを送る。
+fn run_rule<I: IntoIterator<Item = RuleOutput>>(
+    ctx: &RuleContext,
+    out: &mut Vec<RuleOutput>,
+    name: &str,
+    rule: impl FnOnce() -> I,
+) {
+    let Some(events) = &ctx.events else {
+        out.extend(rule());
+        return;
+    };
+    events.emit(crate::events::Event::RuleStarted {
+        rule: name.to_string(),
+    });
+    let started = Instant::now();
+    let before = out.len();
+    out.extend(rule());
+    let added = &out[before..];
+    events.emit(crate::events::Event::RuleFinished {
+        rule: name.to_string(),
+        findings: added.len(),
+        bytes: added.iter().map(|o| o.finding.estimated_bytes).sum(),
+        duration_ms: started.elapsed().as_millis() as u64,
+    });
+}
+
 fn xcode_derived_data(ctx: &RuleContext) -> Option<RuleOutput> {
     let path = ctx.home_dir.join("Library/Developer/Xcode/DerivedData");
     let mut out = dir_finding(
@@ -326,6 +385,138 @@ fn xcode_device_logs(ctx: &RuleContext) -> Option<RuleOutput> {
     Some(out)
 }
 
+fn xctest_devices(ctx: &RuleContext) -> Option<RuleOutput> {
+    let path = ctx.home_dir.join("Library/Developer/XCTestDevices");
+    let mut out = dir_finding(
+        ctx,
+        "xctest-devices",
+        "XCTEST_DEVICES_LARGE",
+        "XCTestDevices（並列UIテスト用のクローンシミュレータ）",
+        &path,
+        RiskLevel::R2,
+        Some(show_instructions_action(
+            "xctest-devices-review",
+            "XCTestDevices を確認",
+            RiskLevel::R2,
+            vec!["xctest-devices".to_string()],
+            r#"XCTestDevices は並列 UI テスト（parallel testing）で作成されるクローンシミュレータです（R2）。
+
+注意:
+- 次回のテスト実行時に再生成されます
+- テスト実行中（xcodebuild test / Xcode の Test）は削除しないでください"#,
+        )),
+    )?;
+
+    let action = ActionPlan {
+        id: "xctest-devices-trash".to_string(),
+        title: "XCTestDevices をゴミ箱へ移動（R2）".to_string(),
+        risk_level: RiskLevel::R2,
+        estimated_reclaimed_bytes: out.finding.estimated_bytes,
+        related_findings: vec![out.finding.id.clone()],
+        kind: ActionKind::TrashMove {
+            paths: vec![maybe_mask_home(&path, &ctx.home_dir, true)],
+        },
+        notes: vec![
+            "実行前に Xcode / xcodebuild のテスト実行を終了してください。".to_string(),
+            "影響: クローンシミュレータは次回のテスト実行時に再生成されます（初回のテスト開始が遅くなる可能性があります）。"
+                .to_string(),
+        ],
+    };
+    out.finding.recommended_actions.push(ActionRef {
+        id: action.id.clone(),
+    });
+    out.actions.push(action);
+    Some(out)
+}
+
+fn xcode_previews(ctx: &RuleContext) -> Option<RuleOutput> {
+    let path = ctx
+        .home_dir
+        .join("Library/Developer/Xcode/UserData/Previews");
+    let mut out = dir_finding(
+        ctx,
+        "xcode-previews",
+        "XCODE_PREVIEWS_LARGE",
+        "Xcode Previews（SwiftUI プレビュー用データ）",
+        &path,
+        RiskLevel::R1,
+        Some(show_instructions_action(
+            "xcode-previews-review",
+            "Xcode Previews を確認",
+            RiskLevel::R1,
+            vec!["xcode-previews".to_string()],
+            r#"SwiftUI プレビューのビルド成果物/プレビュー用シミュレータが溜まります（R1）。
+
+注意:
+- 次回のプレビュー表示時に再生成されます
+- Xcode でプレビューを表示中の場合は、先に Xcode を終了してください"#,
+        )),
+    )?;
+
+    let action = ActionPlan {
+        id: "xcode-previews-trash".to_string(),
+        title: "Xcode Previews をゴミ箱へ移動（R1）".to_string(),
+        risk_level: RiskLevel::R1,
+        estimated_reclaimed_bytes: out.finding.estimated_bytes,
+        related_findings: vec![out.finding.id.clone()],
+        kind: ActionKind::TrashMove {
+            paths: vec![maybe_mask_home(&path, &ctx.home_dir, true)],
+        },
+        notes: vec![
+            "実行前に Xcode を終了してください。".to_string(),
+            "影響: プレビュー用データは次回のプレビュー表示時に再生成されます（初回表示が遅くなる可能性があります）。"
+                .to_string(),
+        ],
+    };
+    out.finding.recommended_actions.push(ActionRef {
+        id: action.id.clone(),
+    });
+    out.actions.push(action);
+    Some(out)
+}
+
+fn xcode_caches(ctx: &RuleContext) -> Option<RuleOutput> {
+    let path = ctx.home_dir.join("Library/Caches/com.apple.dt.Xcode");
+    let mut out = dir_finding(
+        ctx,
+        "xcode-caches",
+        "XCODE_CACHES_LARGE",
+        "Xcode caches（キャッシュ）",
+        &path,
+        RiskLevel::R1,
+        Some(show_instructions_action(
+            "xcode-caches-review",
+            "Xcode caches を確認",
+            RiskLevel::R1,
+            vec!["xcode-caches".to_string()],
+            r#"Xcode のキャッシュは再生成可能です（R1）。
+
+注意:
+- 次回の Xcode 起動/ビルド/テスト時に再生成されます"#,
+        )),
+    )?;
+
+    let action = ActionPlan {
+        id: "xcode-caches-trash".to_string(),
+        title: "Xcode caches をゴミ箱へ移動（R1）".to_string(),
+        risk_level: RiskLevel::R1,
+        estimated_reclaimed_bytes: out.finding.estimated_bytes,
+        related_findings: vec![out.finding.id.clone()],
+        kind: ActionKind::TrashMove {
+            paths: vec![maybe_mask_home(&path, &ctx.home_dir, true)],
+        },
+        notes: vec![
+            "実行前に Xcode を終了してください。".to_string(),
+            "影響: キャッシュは次回の Xcode 起動/ビルド/テスト時に再生成されます。".to_string(),
+        ],
+    };
+    out.finding.recommended_actions.push(ActionRef {
+        id: action.id.clone(),
+    });
+    out.actions.push(action);
+    Some(out)
+}
+
 fn docker_desktop_storage(ctx: &RuleContext) -> Option<RuleOutput> {
     let path = ctx
         .home_dir
@@ -524,45 +715,600 @@ fn homebrew_cache(ctx: &RuleContext) -> Option<RuleOutput> {
     Some(out)
 }
 
-fn cargo_registry_cache(ctx: &RuleContext) -> Option<RuleOutput> {
-    let path = ctx.home_dir.join(".cargo/registry");
-    let mut out = dir_finding(
-        ctx,
-        "cargo-registry",
-        "RUST_CARGO_CACHE_LARGE",
-        "Cargo registry cache（Rust）",
-        &path,
-        RiskLevel::R1,
-        Some(show_instructions_action(
-            "cargo-registry-review",
-            "Cargo registry cache を確認",
-            RiskLevel::R1,
-            vec!["cargo-registry".to_string()],
-            r#"Cargo の registry は再ダウンロード可能ですが、CI/オフライン作業に影響する場合があります（R1）。
+fn homebrew_cleanup_candidates(ctx: &RuleContext) -> Vec<RuleOutput> {
+    let mut out = Vec::new();
+
+    let cmd = "brew cleanup -n";
+    let cmd_timeout = ctx.command_timeout();
+    if cmd_timeout == Duration::from_secs(0) {
+        return out;
+    }
+    if let Ok(output) = platform::run_command_invoking_user("brew", &["cleanup", "-n"], cmd_timeout)
+        && output.exit_code == 0
+    {
+        for keg in parse_brew_cleanup_dry_run(&output.stdout) {
+            let finding_id = format!("homebrew-old-kegs:{}", keg.formula);
+            let action = ActionPlan {
+                id: format!("homebrew-old-kegs-cleanup:{}", keg.formula),
+                title: format!(
+                    "{} の古いバージョンを削除（`brew cleanup {}`）",
+                    keg.formula, keg.formula
+                ),
+                risk_level: RiskLevel::R1,
+                estimated_reclaimed_bytes: keg.bytes,
+                related_findings: vec![finding_id.clone()],
+                kind: ActionKind::RunCmd {
+                    cmd: "brew".to_string(),
+                    args: vec!["cleanup".to_string(), keg.formula.clone()],
+                },
+                notes: vec![
+                    "影響: 最新以外のインストール済みバージョン（keg）を削除します。古いバージョンへ `brew switch`/`brew link` で戻せなくなります。"
+                        .to_string(),
+                    "ヒント: Homebrew の操作中（install/upgrade 等）は避けてください。".to_string(),
+                ],
+            };
+            let finding = Finding {
+                id: finding_id,
+                finding_type: "HOMEBREW_OLD_KEGS".to_string(),
+                title: format!(
+                    "Homebrew の古いバージョン: {}（{}）",
+                    keg.formula,
+                    keg.versions.join(", ")
+                ),
+                estimated_bytes: keg.bytes,
+                confidence: 0.8,
+                risk_level: RiskLevel::R1,
+                evidence: vec![
+                    Evidence::command(cmd),
+                    Evidence::stat(format!("would_remove:\n{}", keg.paths.join("\n"))),
+                ],
+                recommended_actions: vec![ActionRef {
+                    id: action.id.clone(),
+                }],
+            };
+            out.push(RuleOutput {
+                finding,
+                actions: vec![action],
+            });
+        }
+    }
+
+    let cmd = "brew autoremove --dry-run";
+    let cmd_timeout = ctx.command_timeout();
+    if cmd_timeout == Duration::from_secs(0) {
+        return out;
+    }
+    let Ok(output) =
+        platform::run_command_invoking_user("brew", &["autoremove", "--dry-run"], cmd_timeout)
+    else {
+        return out;
+    };
+    if output.exit_code != 0 {
+        return out;
+    }
+    let formulae = parse_brew_autoremove_dry_run(&output.stdout);
+    if formulae.is_empty() {
+        return out;
+    }
+
+    let cellars = homebrew_cellar_dirs();
+    let mut autoremove_outputs = Vec::new();
+    for formula in &formulae {
+        let mut bytes = 0u64;
+        let mut confidence = 0.5;
+        let mut evidence = vec![Evidence::command(cmd)];
+        if let Some(dir) = cellars.iter().map(|c| c.join(formula)).find(|p| p.exists())
+            && let Ok(estimate) = scan::estimate_dir_size(
+                &dir,
+                ctx.timeout,
+                ctx.deadline,
+                ctx.size_cache.as_deref(),
+                ctx.sizer,
+            )
+        {
+            bytes = estimate.bytes;
+            confidence = estimate.confidence();
+            evidence.push(Evidence::path(dir.display().to_string(), false));
+            evidence.push(Evidence::stat(format!(
+                "files={} errors={} method={:?}",
+                estimate.file_count, estimate.error_count, estimate.method
+            )));
+        }
+        autoremove_outputs.push(RuleOutput {
+            finding: Finding {
+                id: format!("homebrew-autoremove:{formula}"),
+                finding_type: "HOMEBREW_AUTOREMOVE_CANDIDATE".to_string(),
+                title: format!("Homebrew の不要な依存: {formula}"),
+                estimated_bytes: bytes,
+                confidence,
+                risk_level: RiskLevel::R2,
+                evidence,
+                recommended_actions: vec![ActionRef {
+                    id: "homebrew-autoremove".to_string(),
+                }],
+            },
+            actions: vec![],
+        });
+    }
+
+    let action = ActionPlan {
+        id: "homebrew-autoremove".to_string(),
+        title: "不要な依存 formula を削除（`brew autoremove`）（R2）".to_string(),
+        risk_level: RiskLevel::R2,
+        estimated_reclaimed_bytes: autoremove_outputs
+            .iter()
+            .map(|o| o.finding.estimated_bytes)
+            .sum(),
+        related_findings: autoremove_outputs
+            .iter()
+            .map(|o| o.finding.id.clone())
+            .collect(),
+        kind: ActionKind::RunCmd {
+            cmd: "brew".to_string(),
+            args: vec!["autoremove".to_string()],
+        },
+        notes: vec![
+            format!("対象: {}", formulae.join(", ")),
+            "影響: 依存として入ったが現在はどの formula からも使われていない formula をアンインストールします。直接使っているツールが含まれていないか確認してください。"
+                .to_string(),
+            "ヒント: 残したい formula は `brew install <name>` で明示インストール扱いにしてください。"
+                .to_string(),
+        ],
+    };
+    if let Some(first) = autoremove_outputs.first_mut() {
+        first.actions.push(action);
+    }
+    out.extend(autoremove_outputs);
+    out
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct BrewOldKegs {
+    formula: String,
+    versions: Vec<String>,
+    paths: Vec<String>,
+    bytes: u64,
+}
+
+fn parse_brew_cleanup_dry_run(stdout: &str) -> Vec<BrewOldKegs> {
+    let mut by_formula: Vec<BrewOldKegs> = Vec::new();
+    for line in stdout.lines() {
+        let Some(rest) = line.trim().strip_prefix("Would remove: ") else {
+            continue;
+        };
+        let (path, size) = match rest.rfind(" (") {
+            Some(idx) if rest.ends_with(')') => {
+                (&rest[..idx], &rest[idx + 2..rest.len().saturating_sub(1)])
+            }
+            _ => (rest, ""),
+        };
+        let Some(cellar_rel) = path.split_once("/Cellar/").map(|(_, r)| r) else {
+            continue;
+        };
+        let mut parts = cellar_rel.split('/');
+        let (Some(formula), Some(version)) = (parts.next(), parts.next()) else {
+            continue;
+        };
+        if formula.is_empty() || version.is_empty() || parts.next().is_some() {
+            continue;
+        }
+        let bytes = size
+            .rsplit(", ")
+            .next()
+            .and_then(parse_brew_size)
+            .unwrap_or(0);
+
+        let entry = match by_formula.iter_mut().find(|k| k.formula == formula) {
+            Some(entry) => entry,
+            None => {
+                by_formula.push(BrewOldKegs {
+                    formula: formula.to_string(),
+                    versions: vec![],
+                    paths: vec![],
+                    bytes: 0,
+                });
+                by_formula.last_mut().expect("pushed")
+            }
+        };
+        entry.versions.push(version.to_string());
+        entry.paths.push(path.to_string());
+        entry.bytes = entry.bytes.saturating_add(bytes);
+    }
+    by_formula.sort_by(|a, b| {
+        b.bytes
+            .cmp(&a.bytes)
+            .then_with(|| a.formula.cmp(&b.formula))
+    });
+    by_formula
+}
+
+fn parse_brew_autoremove_dry_run(stdout: &str) -> Vec<String> {
+    let mut out = Vec::new();
+    let mut in_list = false;
+    for line in stdout.lines() {
+        let t = line.trim();
+        if t.starts_with("==>") {
+            in_list = t.contains("Would autoremove");
+            continue;
+        }
+        if !in_list || t.is_empty() {
+            continue;
+        }
+        out.extend(t.split_whitespace().map(|s| s.to_string()));
+    }
+    out.sort();
+    out.dedup();
+    out
+}
+
+fn parse_brew_size(s: &str) -> Option<u64> {
+    let s = s.trim();
+    let idx = s.find(|c: char| c.is_ascii_alphabetic())?;
+    let (num, unit) = s.split_at(idx);
+    let num: f64 = num.trim().replace(',', "").parse().ok()?;
+    let mult: f64 = match unit.trim() {
+        "B" => 1.0,
+        "KB" => 1024.0,
+        "MB" => 1024.0 * 1024.0,
+        "GB" => 1024.0 * 1024.0 * 1024.0,
+        "TB" => 1024.0 * 1024.0 * 1024.0 * 1024.0,
+        _ => return None,
+    };
+    Some((num * mult) as u64)
+}
+
+fn homebrew_cellar_dirs() -> Vec<PathBuf> {
+    ["/opt/homebrew/Cellar", "/usr/local/Cellar"]
+        .into_iter()
+        .map(PathBuf::from)
+        .filter(|p| p.exists())
+        .collect()
+}
+
+const CARGO_CRATE_DETAIL_ROWS: usize = 10;
+
+fn cargo_registry_cache(ctx: &RuleContext) -> Vec<RuleOutput> {
+    let registry = ctx.home_dir.join(".cargo/registry");
+    let mut out = Vec::new();
+    if !registry.exists() {
+        return out;
+    }
+
+    let crates = collect_cargo_crate_versions(&registry, ctx.deadline);
+    let stale_days = ctx.cargo_stale_crate_days;
+
+    if let Some(mut src) = dir_finding(
+        ctx,
+        "cargo-registry-src",
+        "RUST_CARGO_REGISTRY_SRC_LARGE",
+        "Cargo registry src（展開済みソース、Rust）",
+        &registry.join("src"),
+        RiskLevel::R1,
+        Some(show_instructions_action(
+            "cargo-registry-src-review",
+            "Cargo registry src を確認",
+            RiskLevel::R1,
+            vec!["cargo-registry-src".to_string()],
+            r#"`~/.cargo/registry/src` は `registry/cache` の `.crate` から展開されたソースです（R1）。
+
+削除しても、次回ビルド時に `registry/cache` から再展開されます（オフラインでも可）。"#,
+        )),
+    ) {
+        let action = ActionPlan {
+            id: "cargo-registry-src-trash".to_string(),
+            title: "Cargo registry の展開済みソースのみゴミ箱へ移動（R1）".to_string(),
+            risk_level: RiskLevel::R1,
+            estimated_reclaimed_bytes: src.finding.estimated_bytes,
+            related_findings: vec![src.finding.id.clone()],
+            kind: ActionKind::TrashMove {
+                paths: vec![maybe_mask_home(
+                    &registry.join("src"),
+                    &ctx.home_dir,
+                    true,
+                )],
+            },
+            notes: vec![
+                "影響: 次回ビルド時に `registry/cache` から再展開されます（再ダウンロードは不要で、オフラインビルドは維持されます）。"
+                    .to_string(),
+            ],
+        };
+        src.finding.recommended_actions.push(ActionRef {
+            id: action.id.clone(),
+        });
+        src.actions.push(action);
+        out.push(src);
+    }
+
+    if let Some(mut cache) = dir_finding(
+        ctx,
+        "cargo-registry-cache",
+        "RUST_CARGO_REGISTRY_CACHE_LARGE",
+        "Cargo registry cache（.crate アーカイブ、Rust）",
+        &registry.join("cache"),
+        RiskLevel::R1,
+        Some(show_instructions_action(
+            "cargo-registry-cache-review",
+            "Cargo registry cache を確認",
+            RiskLevel::R1,
+            vec!["cargo-registry-cache".to_string()],
+            r#"`~/.cargo/registry/cache` はダウンロード済みの `.crate` アーカイブです（R1）。
+
+オフラインビルドでは必要になるため、まとめて削除するより、長期間使われていない crate だけを整理するのが安全です。"#,
+        )),
+    ) {
+        if !crates.is_empty() {
+            cache
+                .finding
+                .evidence
+                .push(Evidence::stat(summarize_cargo_crate_versions(
+                    &crates, stale_days,
+                )));
+        }
+
+        let stale: Vec<&CargoCrateVersion> =
+            crates.iter().filter(|c| c.age_days >= stale_days).collect();
+        if !stale.is_empty() {
+            let mut paths = Vec::new();
+            for c in &stale {
+                if let Some(p) = &c.crate_file {
+                    paths.push(maybe_mask_home(p, &ctx.home_dir, true));
+                }
+                if let Some(p) = &c.src_dir {
+                    paths.push(maybe_mask_home(p, &ctx.home_dir, true));
+                }
+            }
+            let action = ActionPlan {
+                id: "cargo-registry-stale-crates-trash".to_string(),
+                title: format!(
+                    "{stale_days}日以上使われていない crate をゴミ箱へ移動（{}件）（R1）",
+                    stale.len()
+                ),
+                risk_level: RiskLevel::R1,
+                estimated_reclaimed_bytes: stale.iter().map(|c| c.total_bytes()).sum(),
+                related_findings: vec![cache.finding.id.clone()],
+                kind: ActionKind::TrashMove { paths },
+                notes: vec![
+                    format!(
+                        "対象: 最終アクセスから{stale_days}日以上経過した crate の `.crate` と展開済みソース。"
+                    ),
+                    "影響: 対象の crate を再び使う場合は再ダウンロードが必要です（それ以外のオフラインビルドは維持されます）。"
+                        .to_string(),
+                ],
+            };
+            cache.finding.recommended_actions.push(ActionRef {
+                id: action.id.clone(),
+            });
+            cache.actions.push(action);
+        }
+        out.push(cache);
+    }
+
+    if let Some(index) = dir_finding(
+        ctx,
+        "cargo-registry-index",
+        "RUST_CARGO_REGISTRY_INDEX_LARGE",
+        "Cargo registry index（Rust）",
+        &registry.join("index"),
+        RiskLevel::R2,
+        Some(show_instructions_action(
+            "cargo-registry-index-review",
+            "Cargo registry index を確認",
+            RiskLevel::R2,
+            vec!["cargo-registry-index".to_string()],
+            r#"`~/.cargo/registry/index` は依存解決に使う registry のインデックスです（R2）。
+
+注意:
+- オフラインビルド（`--offline`）に必要です
+- 削除すると次回ビルド時に再取得が必要になります"#,
+        )),
+    ) {
+        out.push(index);
+    }
+
+    // src / cache / index のどれにも当たらない（レイアウトが想定外の）registry は全体を報告する。
+    // index を含みうるため、ゴミ箱への移動は提案しない。
+    if out.is_empty()
+        && let Some(whole) = dir_finding(
+            ctx,
+            "cargo-registry",
+            "RUST_CARGO_CACHE_LARGE",
+            "Cargo registry（Rust）",
+            &registry,
+            RiskLevel::R1,
+            Some(show_instructions_action(
+                "cargo-registry-review",
+                "Cargo registry を確認",
+                RiskLevel::R1,
+                vec!["cargo-registry".to_string()],
+                r#"Cargo の registry は再ダウンロード可能ですが、CI/オフライン作業に影響する場合があります（R1）。
+
+まずはサイズ確認と内訳確認を推奨します。`registry/index` はオフラインビルドに必要なため、registry 全体は削除しないでください。"#,
+            )),
+        )
+    {
+        out.push(whole);
+    }
+
+    out
+}
+
+#[derive(Debug, Clone)]
+struct CargoCrateVersion {
+    name: String,
+    crate_file: Option<PathBuf>,
+    crate_bytes: u64,
+    src_dir: Option<PathBuf>,
+    src_bytes: u64,
+    age_days: u64,
+}
+
+impl CargoCrateVersion {
+    fn total_bytes(&self) -> u64 {
+        self.crate_bytes.saturating_add(self.src_bytes)
+    }
+}
+
+fn collect_cargo_crate_versions(
+    registry: &Path,
+    deadline: Option<Instant>,
+) -> Vec<CargoCrateVersion> {
+    use std::collections::BTreeMap;
+
+    let now = std::time::SystemTime::now();
+    let mut by_key: BTreeMap<(String, String), CargoCrateVersion> = BTreeMap::new();
+    let mut last_access: BTreeMap<(String, String), std::time::SystemTime> = BTreeMap::new();
+
+    let mut touch = |key: &(String, String), meta: &std::fs::Metadata| {
+        let t = [meta.accessed().ok(), meta.modified().ok()]
+            .into_iter()
+            .flatten()
+            .max();
+        if let Some(t) = t {
+            let e = last_access.entry(key.clone()).or_insert(t);
+            if t > *e {
+                *e = t;
+            }
+        }
+    };
+
+    for index_dir in read_dir_paths(&registry.join("cache")) {
+        let Some(index_name) = file_name_string(&index_dir) else {
+            continue;
+        };
+        for file in read_dir_paths(&index_dir) {
+            if deadline.is_some_and(|d| Instant::now() >= d) {
+                break;
+            }
+            let Some(file_name) = file_name_string(&file) else {
+                continue;
+            };
+            let Some(name) = file_name.strip_suffix(".crate") else {
+                continue;
+            };
+            let Ok(meta) = std::fs::symlink_metadata(&file) else {
+                continue;
+            };
+            let key = (index_name.clone(), name.to_string());
+            touch(&key, &meta);
+            let entry = by_key.entry(key).or_insert_with(|| CargoCrateVersion {
+                name: name.to_string(),
+                crate_file: None,
+                crate_bytes: 0,
+                src_dir: None,
+                src_bytes: 0,
+                age_days: 0,
+            });
+            entry.crate_file = Some(file);
+            entry.crate_bytes = meta.len();
+        }
+    }
+
+    for index_dir in read_dir_paths(&registry.join("src")) {
+        let Some(index_name) = file_name_string(&index_dir) else {
+            continue;
+        };
+        for dir in read_dir_paths(&index_dir) {
+            if deadline.is_some_and(|d| Instant::now() >= d) {
+                break;
+            }
+            let Some(name) = file_name_string(&dir) else {
+                continue;
+            };
+            let Ok(meta) = std::fs::symlink_metadata(&dir) else {
+                continue;
+            };
+            if !meta.is_dir() {
+                continue;
+            }
+            let key = (index_name.clone(), name.clone());
+            touch(&key, &meta);
+            let entry = by_key.entry(key).or_insert_with(|| CargoCrateVersion {
+                name: name.clone(),
+                crate_file: None,
+                crate_bytes: 0,
+                src_dir: None,
+                src_bytes: 0,
+                age_days: 0,
+            });
+            entry.src_bytes = sum_file_bytes(&dir, deadline);
+            entry.src_dir = Some(dir);
+        }
+    }
+
+    let mut out: Vec<CargoCrateVersion> = by_key
+        .into_iter()
+        .map(|(key, mut c)| {
+            c.age_days = last_access
+                .get(&key)
+                .and_then(|t| now.duration_since(*t).ok())
+                .map(|d| d.as_secs() / 86_400)
+                .unwrap_or(0);
+            c
+        })
+        .collect();
+    out.sort_by(|a, b| {
+        b.total_bytes()
+            .cmp(&a.total_bytes())
+            .then_with(|| a.name.cmp(&b.name))
+    });
+    out
+}
+
+fn summarize_cargo_crate_versions(crates: &[CargoCrateVersion], stale_days: u64) -> String {
+    let stale = crates.iter().filter(|c| c.age_days >= stale_days).count();
+    let mut lines = vec![format!(
+        "crate versions: {} 件（{stale_days}日以上未使用: {stale} 件）",
+        crates.len()
+    )];
+    for c in crates.iter().take(CARGO_CRATE_DETAIL_ROWS) {
+        lines.push(format!(
+            "{} {} last_access={}d",
+            c.name,
+            crate::ui::format_bytes(c.total_bytes()),
+            c.age_days
+        ));
+    }
+    if crates.len() > CARGO_CRATE_DETAIL_ROWS {
+        lines.push(format!(
+            "...（残り {} 件）",
+            crates.len() - CARGO_CRATE_DETAIL_ROWS
+        ));
+    }
+    lines.join("\n")
+}
+
+fn read_dir_paths(dir: &Path) -> Vec<PathBuf> {
+    let Ok(rd) = std::fs::read_dir(dir) else {
+        return vec![];
+    };
+    let mut out: Vec<PathBuf> = rd.filter_map(|e| e.ok()).map(|e| e.path()).collect();
+    out.sort();
+    out
+}
 
-まずはサイズ確認と内訳確認を推奨します。"#,
-        )),
-    )?;
+fn file_name_string(path: &Path) -> Option<String> {
+    path.file_name()
+        .and_then(|s| s.to_str())
+        .map(|s| s.to_string())
+}
 
-    let action = ActionPlan {
-        id: "cargo-registry-trash".to_string(),
-        title: "Cargo registry cache をゴミ箱へ移動（R1）".to_string(),
-        risk_level: RiskLevel::R1,
-        estimated_reclaimed_bytes: out.finding.estimated_bytes,
-        related_findings: vec![out.finding.id.clone()],
-        kind: ActionKind::TrashMove {
-            paths: vec![maybe_mask_home(&path, &ctx.home_dir, true)],
-        },
-        notes: vec![
-            "影響: registry データを再ダウンロードします。オフライン作業や CI キャッシュに影響する可能性があります。"
-                .to_string(),
-        ],
-    };
-    out.finding.recommended_actions.push(ActionRef {
-        id: action.id.clone(),
-    });
-    out.actions.push(action);
-    Some(out)
+fn sum_file_bytes(dir: &Path, deadline: Option<Instant>) -> u64 {
+    let mut total: u64 = 0;
+    for entry in walkdir::WalkDir::new(dir).follow_links(false) {
+        if deadline.is_some_and(|d| Instant::now() >= d) {
+            break;
+        }
+        let Ok(entry) = entry else {
+            continue;
+        };
+        if !entry.file_type().is_file() {
+            continue;
+        }
+        if let Ok(meta) = entry.metadata() {
+            total = total.saturating_add(meta.len());
+        }
+    }
+    total
 }
 
 fn cargo_git_cache(ctx: &RuleContext) -> Option<RuleOutput> {
@@ -796,6 +1542,619 @@ fn pnpm_store_cache(ctx: &RuleContext) -> Option<RuleOutput> {
     None
 }
 
+const INSTALLER_SEARCH_MAX_DEPTH: usize = 2;
+
+fn installer_leftovers(ctx: &RuleContext) -> Vec<RuleOutput> {
+    let home = &ctx.home_dir;
+    let search_dirs = [
+        (home.clone(), 1),
+        (home.join("Downloads"), INSTALLER_SEARCH_MAX_DEPTH),
+        (home.join("Desktop"), INSTALLER_SEARCH_MAX_DEPTH),
+        (home.join("Library/iTunes/iPhone Software Updates"), 1),
+        (home.join("Library/iTunes/iPad Software Updates"), 1),
+        (home.join("Applications"), 1),
+        (PathBuf::from("/Applications"), 1),
+    ];
+
+    let mut found: Vec<(PathBuf, &'static str)> = Vec::new();
+    for (dir, max_depth) in search_dirs {
+        let mut walker = walkdir::WalkDir::new(&dir)
+            .follow_links(false)
+            .min_depth(1)
+            .max_depth(max_depth)
+            .into_iter();
+        while let Some(entry) = walker.next() {
+            if ctx.deadline.is_some_and(|d| Instant::now() >= d) {
+                break;
+            }
+            let Ok(entry) = entry else {
+                continue;
+            };
+            let name = entry.file_name().to_str().unwrap_or("");
+            // 隠しファイルやバンドル（.app/.pkg 等）の中には入らない。
+            if entry.file_type().is_dir()
+                && entry.depth() < max_depth
+                && (name.starts_with('.') || name.ends_with(".app") || name.ends_with(".pkg"))
+            {
+                walker.skip_current_dir();
+            }
+            if name.starts_with('.') {
+                continue;
+            }
+            if entry.file_type().is_symlink() {
+                continue;
+            }
+            let Some(kind) = entry
+                .file_name()
+                .to_str()
+                .and_then(crate::actions::installer_leftover_kind)
+            else {
+                continue;
+            };
+            if entry.file_type().is_dir() && !matches!(kind, "Install macOS" | "pkg") {
+                continue;
+            }
+            if !found.iter().any(|(p, _)| p == entry.path()) {
+                found.push((entry.path().to_path_buf(), kind));
+            }
+        }
+    }
+
+    let mut out = Vec::new();
+    for (path, kind) in found {
+        let Ok(meta) = std::fs::symlink_metadata(&path) else {
+            continue;
+        };
+        let mut confidence = 0.95;
+        let mut bytes = meta.len();
+        let mut evidence = vec![Evidence::path(
+            maybe_mask_home(&path, &ctx.home_dir, ctx.privacy_mask_home),
+            ctx.privacy_mask_home,
+        )];
+        if meta.is_dir() {
+            let Ok(estimate) = scan::estimate_dir_size(
+                &path,
+                ctx.timeout,
+                ctx.deadline,
+                ctx.size_cache.as_deref(),
+                ctx.sizer,
+            ) else {
+                continue;
+            };
+            bytes = estimate.bytes;
+            confidence = estimate.confidence();
+            evidence.push(Evidence::stat(format!(
+                "files={} errors={} method={:?}",
+                estimate.file_count, estimate.error_count, estimate.method
+            )));
+        }
+        if bytes == 0 {
+            continue;
+        }
+        let age_days = meta
+            .created()
+            .or_else(|_| meta.modified())
+            .ok()
+            .and_then(|t| t.elapsed().ok())
+            .map(|d| d.as_secs() / 86_400);
+        let age = match age_days {
+            Some(days) => {
+                evidence.push(Evidence::stat(format!("age_days={days}")));
+                format!("{days} 日前")
+            }
+            None => "経過日数不明".to_string(),
+        };
+
+        let display_path = maybe_mask_home(&path, &ctx.home_dir, true);
+        let finding_id = format!("installer-leftover:{display_path}");
+        let mut actions = vec![ActionPlan {
+            id: format!("installer-leftover-open:{display_path}"),
+            title: "Finder で場所を確認".to_string(),
+            risk_level: RiskLevel::R0,
+            estimated_reclaimed_bytes: 0,
+            related_findings: vec![finding_id.clone()],
+            kind: ActionKind::OpenInFinder {
+                path: display_path.clone(),
+            },
+            notes: vec![],
+        }];
+        if path.starts_with(&ctx.home_dir) {
+            actions.push(ActionPlan {
+                id: format!("installer-leftover-trash:{display_path}"),
+                title: format!("{kind} をゴミ箱へ移動（R1）"),
+                risk_level: RiskLevel::R1,
+                estimated_reclaimed_bytes: bytes,
+                related_findings: vec![finding_id.clone()],
+                kind: ActionKind::TrashMove {
+                    paths: vec![display_path.clone()],
+                },
+                notes: vec![
+                    "影響: 同じインストーラが必要になった場合は再ダウンロードが必要です。"
+                        .to_string(),
+                ],
+            });
+        }
+
+        out.push(RuleOutput {
+            finding: Finding {
+                id: finding_id,
+                finding_type: "INSTALLER_LEFTOVER".to_string(),
+                title: format!("インストーラの残り（{kind}、{age}）: {display_path}"),
+                estimated_bytes: bytes,
+                confidence,
+                risk_level: RiskLevel::R1,
+                evidence,
+                recommended_actions: actions
+                    .iter()
+                    .map(|a| ActionRef { id: a.id.clone() })
+                    .collect(),
+            },
+            actions,
+        });
+    }
+    out.sort_by_key(|o| std::cmp::Reverse(o.finding.estimated_bytes));
+    out
+}
+
+const VERSION_FILE_SCAN_MAX_DEPTH: usize = 4;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum VersionManager {
+    Nvm,
+    Pyenv,
+    Rbenv,
+    Asdf,
+    Mise,
+}
+
+impl VersionManager {
+    fn as_str(self) -> &'static str {
+        match self {
+            VersionManager::Nvm => "nvm",
+            VersionManager::Pyenv => "pyenv",
+            VersionManager::Rbenv => "rbenv",
+            VersionManager::Asdf => "asdf",
+            VersionManager::Mise => "mise",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct InstalledLanguageVersion {
+    manager: VersionManager,
+    tool: String,
+    version: String,
+    path: PathBuf,
+}
+
+#[derive(Debug, Clone, Default)]
+struct VersionReferences {
+    specs: Vec<(String, String, PathBuf)>,
+    unresolved_tools: Vec<String>,
+    truncated: bool,
+}
+
+fn language_versions(ctx: &RuleContext) -> Vec<RuleOutput> {
+    let installed = collect_installed_language_versions(&ctx.home_dir);
+    if installed.is_empty() {
+        return vec![];
+    }
+    let refs = collect_version_references(&ctx.home_dir, &ctx.source_roots, ctx.deadline);
+
+    let mut out = Vec::new();
+    for v in installed {
+        if ctx.deadline.is_some_and(|d| Instant::now() >= d) {
+            break;
+        }
+        let canonical_tool = canonical_tool_name(&v.tool);
+        let referenced_by: Vec<String> = refs
+            .specs
+            .iter()
+            .filter(|(tool, spec, _)| {
+                *tool == canonical_tool && version_spec_matches(spec, &v.version)
+            })
+            .map(|(_, _, path)| maybe_mask_home(path, &ctx.home_dir, ctx.privacy_mask_home))
+            .collect();
+        let undetermined_reason = if !referenced_by.is_empty() {
+            None
+        } else if ctx.source_roots.is_empty() {
+            Some("scan.source_roots が未設定のため、プロジェクトからの参照を確認していません")
+        } else if refs.truncated {
+            Some("時間制限によりバージョンファイルの走査が途中で打ち切られました")
+        } else if refs.unresolved_tools.contains(&canonical_tool) {
+            Some("エイリアス指定（lts/* や latest 等）があり、参照先を特定できません")
+        } else {
+            None
+        };
+
+        let finding_id = format!(
+            "lang-version:{}:{}:{}",
+            v.manager.as_str(),
+            v.tool,
+            v.version
+        );
+        let masked_path = maybe_mask_home(&v.path, &ctx.home_dir, ctx.privacy_mask_home);
+        let mut evidence = vec![Evidence::path(masked_path, ctx.privacy_mask_home)];
+        let mut bytes = 0u64;
+        let mut confidence = 0.3;
+        if let Ok(estimate) = scan::estimate_dir_size(
+            &v.path,
+            ctx.timeout,
+            ctx.deadline,
+            ctx.size_cache.as_deref(),
+            ctx.sizer,
+        ) {
+            bytes = estimate.bytes;
+            confidence = estimate.confidence();
+            evidence.push(Evidence::stat(format!(
+                "files={} errors={} method={:?}",
+                estimate.file_count, estimate.error_count, estimate.method
+            )));
+        }
+        let status = if !referenced_by.is_empty() {
+            evidence.push(Evidence::stat(format!(
+                "referenced_by:\n{}",
+                referenced_by.join("\n")
+            )));
+            "参照あり"
+        } else if let Some(reason) = undetermined_reason {
+            evidence.push(Evidence::stat(format!("referenced_by: 不明（{reason}）")));
+            "参照未確認"
+        } else {
+            evidence.push(Evidence::stat(format!(
+                "referenced_by: なし（source_roots: {} 件を走査）",
+                ctx.source_roots.len()
+            )));
+            "参照なし"
+        };
+
+        let mut actions = Vec::new();
+        if referenced_by.is_empty() && undetermined_reason.is_none() {
+            actions.push(language_version_uninstall_action(
+                ctx,
+                &v,
+                &finding_id,
+                bytes,
+            ));
+        }
+
+        out.push(RuleOutput {
+            finding: Finding {
+                id: finding_id,
+                finding_type: "LANG_VERSION_INSTALLED".to_string(),
+                title: format!(
+                    "{} {}（{}）: {status}",
+                    v.tool,
+                    v.version,
+                    v.manager.as_str()
+                ),
+                estimated_bytes: bytes,
+                confidence,
+                risk_level: RiskLevel::R2,
+                evidence,
+                recommended_actions: actions
+                    .iter()
+                    .map(|a| ActionRef { id: a.id.clone() })
+                    .collect(),
+            },
+            actions,
+        });
+    }
+    out
+}
+
+fn language_version_uninstall_action(
+    ctx: &RuleContext,
+    v: &InstalledLanguageVersion,
+    finding_id: &str,
+    bytes: u64,
+) -> ActionPlan {
+    let notes = vec![
+        "影響: このバージョンを使うプロジェクトやシェル設定があると、再インストールが必要になります。"
+            .to_string(),
+        "注: 参照判定は scan.source_roots 配下のバージョンファイルのみが対象です。CI やスクリプトで直接指定している場合は検出できません。"
+            .to_string(),
+    ];
+    let (cmd, args) = match v.manager {
+        VersionManager::Nvm => {
+            // nvm はシェル関数のため直接実行できない。バージョンディレクトリをゴミ箱へ移動する。
+            return ActionPlan {
+                id: format!("lang-version-trash:nvm:{}:{}", v.tool, v.version),
+                title: format!("Node.js {} をゴミ箱へ移動（nvm）（R2）", v.version),
+                risk_level: RiskLevel::R2,
+                estimated_reclaimed_bytes: bytes,
+                related_findings: vec![finding_id.to_string()],
+                kind: ActionKind::TrashMove {
+                    paths: vec![maybe_mask_home(&v.path, &ctx.home_dir, true)],
+                },
+                notes,
+            };
+        }
+        VersionManager::Pyenv => (
+            "pyenv",
+            vec!["uninstall".to_string(), "-f".to_string(), v.version.clone()],
+        ),
+        VersionManager::Rbenv => (
+            "rbenv",
+            vec!["uninstall".to_string(), "-f".to_string(), v.version.clone()],
+        ),
+        VersionManager::Asdf => (
+            "asdf",
+            vec!["uninstall".to_string(), v.tool.clone(), v.version.clone()],
+        ),
+        VersionManager::Mise => (
+            "mise",
+            vec!["uninstall".to_string(), format!("{}@{}", v.tool, v.version)],
+        ),
+    };
+    ActionPlan {
+        id: format!(
+            "lang-version-uninstall:{}:{}:{}",
+            v.manager.as_str(),
+            v.tool,
+            v.version
+        ),
+        title: format!(
+            "{} {} をアンインストール（`{} {}`）（R2）",
+            v.tool,
+            v.version,
+            cmd,
+            args.join(" ")
+        ),
+        risk_level: RiskLevel::R2,
+        estimated_reclaimed_bytes: bytes,
+        related_findings: vec![finding_id.to_string()],
+        kind: ActionKind::RunCmd {
+            cmd: cmd.to_string(),
+            args,
+        },
+        notes,
+    }
+}
+
+fn collect_installed_language_versions(home_dir: &Path) -> Vec<InstalledLanguageVersion> {
+    let mut out = Vec::new();
+    let mut push_versions = |manager: VersionManager, tool: &str, dir: &Path| {
+        for path in read_dir_paths(dir) {
+            // mise/asdf はエイリアス（`latest` や `18` 等）をシンボリックリンクで置くため除外する。
+            let Ok(meta) = std::fs::symlink_metadata(&path) else {
+                continue;
+            };
+            if !meta.is_dir() {
+                continue;
+            }
+            let Some(version) = file_name_string(&path) else {
+                continue;
+            };
+            if version.starts_with('.') {
+                continue;
+            }
+            out.push(InstalledLanguageVersion {
+                manager,
+                tool: tool.to_string(),
+                version,
+                path,
+            });
+        }
+    };
+
+    push_versions(
+        VersionManager::Nvm,
+        "node",
+        &home_dir.join(".nvm/versions/node"),
+    );
+    push_versions(
+        VersionManager::Pyenv,
+        "python",
+        &home_dir.join(".pyenv/versions"),
+    );
+    push_versions(
+        VersionManager::Rbenv,
+        "ruby",
+        &home_dir.join(".rbenv/versions"),
+    );
+    for (manager, installs) in [
+        (VersionManager::Asdf, home_dir.join(".asdf/installs")),
+        (
+            VersionManager::Mise,
+            home_dir.join(".local/share/mise/installs"),
+        ),
+    ] {
+        for tool_dir in read_dir_paths(&installs) {
+            if !tool_dir.is_dir() {
+                continue;
+            }
+            let Some(tool) = file_name_string(&tool_dir) else {
+                continue;
+            };
+            push_versions(manager, &tool, &tool_dir);
+        }
+    }
+    out
+}
+
+fn collect_version_references(
+    home_dir: &Path,
+    source_roots: &[PathBuf],
+    deadline: Option<Instant>,
+) -> VersionReferences {
+    let mut refs = VersionReferences::default();
+
+    for (tool, rel) in [
+        ("node", ".nvm/alias/default"),
+        ("python", ".pyenv/version"),
+        ("ruby", ".rbenv/version"),
+    ] {
+        let path = home_dir.join(rel);
+        if let Ok(content) = std::fs::read_to_string(&path) {
+            add_version_specs(&mut refs, tool, &content, &path);
+        }
+    }
+    for rel in [".tool-versions", ".config/mise/config.toml"] {
+        let path = home_dir.join(rel);
+        if let Ok(content) = std::fs::read_to_string(&path) {
+            add_version_file_references(&mut refs, &path, &content);
+        }
+    }
+
+    for root in source_roots {
+        let walker = walkdir::WalkDir::new(root)
+            .follow_links(false)
+            .max_depth(VERSION_FILE_SCAN_MAX_DEPTH)
+            .into_iter()
+            .filter_entry(|e| {
+                !(e.file_type().is_dir()
+                    && e.depth() > 0
+                    && e.file_name().to_str().is_some_and(|name| {
+                        matches!(
+                            name,
+                            "node_modules" | ".git" | "target" | "vendor" | ".venv"
+                        )
+                    }))
+            });
+        for entry in walker {
+            if deadline.is_some_and(|d| Instant::now() >= d) {
+                refs.truncated = true;
+                return refs;
+            }
+            let Ok(entry) = entry else {
+                continue;
+            };
+            if !entry.file_type().is_file() {
+                continue;
+            }
+            let is_version_file = entry.file_name().to_str().is_some_and(|name| {
+                matches!(
+                    name,
+                    ".nvmrc"
+                        | ".node-version"
+                        | ".python-version"
+                        | ".ruby-version"
+                        | ".tool-versions"
+                        | "mise.toml"
+                        | ".mise.toml"
+                )
+            });
+            if !is_version_file {
+                continue;
+            }
+            if let Ok(content) = std::fs::read_to_string(entry.path()) {
+                add_version_file_references(&mut refs, entry.path(), &content);
+            }
+        }
+    }
+    refs
+}
+
+fn add_version_file_references(refs: &mut VersionReferences, path: &Path, content: &str) {
+    let name = path.file_name().and_then(|s| s.to_str()).unwrap_or("");
+    match name {
+        ".nvmrc" | ".node-version" => add_version_specs(refs, "node", content, path),
+        ".python-version" => add_version_specs(refs, "python", content, path),
+        ".ruby-version" => add_version_specs(refs, "ruby", content, path),
+        ".tool-versions" => {
+            for (tool, spec) in parse_tool_versions(content) {
+                add_version_specs(refs, &tool, &spec, path);
+            }
+        }
+        _ => {
+            for (tool, spec) in parse_mise_toml_tools(content) {
+                add_version_specs(refs, &tool, &spec, path);
+            }
+        }
+    }
+}
+
+fn add_version_specs(refs: &mut VersionReferences, tool: &str, content: &str, path: &Path) {
+    let tool = canonical_tool_name(tool);
+    for spec in content.split_whitespace() {
+        if spec.starts_with('#') {
+            break;
+        }
+        if spec == "system" {
+            continue;
+        }
+        let normalized = normalize_version(spec);
+        if normalized.is_empty() || !normalized.starts_with(|c: char| c.is_ascii_digit()) {
+            // `lts/*`・`latest`・`node` 等のエイリアスはローカルで解決できない。
+            if !refs.unresolved_tools.contains(&tool) {
+                refs.unresolved_tools.push(tool.clone());
+            }
+            continue;
+        }
+        refs.specs
+            .push((tool.clone(), spec.to_string(), path.to_path_buf()));
+    }
+}
+
+fn parse_tool_versions(content: &str) -> Vec<(String, String)> {
+    let mut out = Vec::new();
+    for line in content.lines() {
+        let line = line.split('#').next().unwrap_or("").trim();
+        let mut parts = line.split_whitespace();
+        let Some(tool) = parts.next() else {
+            continue;
+        };
+        for spec in parts {
+            out.push((tool.to_string(), spec.to_string()));
+        }
+    }
+    out
+}
+
+fn parse_mise_toml_tools(content: &str) -> Vec<(String, String)> {
+    let Ok(value) = content.parse::<toml::Table>() else {
+        return vec![];
+    };
+    let Some(tools) = value.get("tools").and_then(|v| v.as_table()) else {
+        return vec![];
+    };
+    let mut out = Vec::new();
+    for (tool, spec) in tools {
+        let specs: Vec<&toml::Value> = match spec {
+            toml::Value::Array(items) => items.iter().collect(),
+            other => vec![other],
+        };
+        for spec in specs {
+            let version = match spec {
+                toml::Value::String(s) => Some(s.as_str()),
+                toml::Value::Table(t) => t.get("version").and_then(|v| v.as_str()),
+                _ => None,
+            };
+            if let Some(version) = version {
+                out.push((tool.to_string(), version.to_string()));
+            }
+        }
+    }
+    out
+}
+
+fn canonical_tool_name(tool: &str) -> String {
+    match tool.to_ascii_lowercase().as_str() {
+        "nodejs" => "node".to_string(),
+        other => other.to_string(),
+    }
+}
+
+fn normalize_version(version: &str) -> &str {
+    let version = version.trim();
+    let version = version.strip_prefix("ruby-").unwrap_or(version);
+    let version = version.strip_prefix("node-").unwrap_or(version);
+    version.strip_prefix('v').unwrap_or(version)
+}
+
+fn version_spec_matches(spec: &str, installed: &str) -> bool {
+    let spec = normalize_version(spec);
+    let installed = normalize_version(installed);
+    if spec.is_empty() {
+        return false;
+    }
+    installed == spec
+        || installed
+            .strip_prefix(spec)
+            .is_some_and(|rest| rest.starts_with('.'))
+}
+
 fn tm_local_snapshots_status(ctx: &RuleContext) -> RuleOutput {
     #[cfg(not(target_os = "macos"))]
     {
@@ -1270,6 +2629,329 @@ mod tests {
         assert!(!simctl_list_has_unavailable("== Devices ==\n"));
         assert!(!simctl_list_has_unavailable("-- iOS 17.0 --\n"));
     }
+
+    #[test]
+    fn parse_brew_cleanup_dry_run_groups_old_kegs_by_formula() {
+        let stdout = "Would remove: /opt/homebrew/Cellar/node/21.1.0 (2,345 files, 60MB)\nWould remove: /opt/homebrew/Cellar/node/21.2.0 (2,350 files, 1.5GB)\nWould remove: /opt/homebrew/Cellar/jq/1.6 (18 files, 512KB)\nWould remove: /Users/test/Library/Caches/Homebrew/downloads/abc--node-21.1.0.bottle.tar.gz (20.1MB)\n==> This operation would free approximately 1.6GB of disk space.\n";
+        let kegs = parse_brew_cleanup_dry_run(stdout);
+        assert_eq!(kegs.len(), 2);
+        assert_eq!(kegs[0].formula, "node");
+        assert_eq!(kegs[0].versions, vec!["21.1.0", "21.2.0"]);
+        assert_eq!(
+            kegs[0].bytes,
+            60 * 1024 * 1024 + (1.5 * 1024.0 * 1024.0 * 1024.0) as u64
+        );
+        assert_eq!(kegs[1].formula, "jq");
+        assert_eq!(kegs[1].bytes, 512 * 1024);
+    }
+
+    #[test]
+    fn parse_brew_autoremove_dry_run_lists_formulae() {
+        let stdout = "==> Would autoremove 2 unneeded formulae:\nlibyaml\nm4\n";
+        assert_eq!(parse_brew_autoremove_dry_run(stdout), vec!["libyaml", "m4"]);
+        assert!(parse_brew_autoremove_dry_run("").is_empty());
+    }
+
+    fn make_temp_dir(label: &str) -> PathBuf {
+        use std::sync::atomic::{AtomicU64, Ordering};
+        static SEQ: AtomicU64 = AtomicU64::new(0);
+
+        let seq = SEQ.fetch_add(1, Ordering::Relaxed);
+        let dir = std::env::temp_dir().join(format!(
+            "macdiet-rules-{label}-{}-{seq}",
+            std::process::id()
+        ));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).expect("create dir");
+        dir
+    }
+
+    fn write_file(path: &Path, bytes: &[u8]) {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).expect("mkdirs");
+        }
+        std::fs::write(path, bytes).expect("write");
+    }
+
+    fn set_times_days_ago(path: &Path, days: u64) {
+        use std::os::unix::ffi::OsStrExt;
+
+        let secs = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .expect("now")
+            .as_secs()
+            - days * 86_400;
+        let tv = libc::timeval {
+            tv_sec: secs as libc::time_t,
+            tv_usec: 0,
+        };
+        let c_path = std::ffi::CString::new(path.as_os_str().as_bytes()).expect("cstring");
+        let rc = unsafe { libc::utimes(c_path.as_ptr(), [tv, tv].as_ptr()) };
+        assert_eq!(rc, 0, "utimes failed: {}", path.display());
+    }
+
+    #[test]
+    fn collect_cargo_crate_versions_merges_cache_and_src() {
+        let registry = make_temp_dir("cargo-registry");
+        let idx = "index.crates.io-test";
+        write_file(
+            &registry.join(format!("cache/{idx}/serde-1.0.0.crate")),
+            b"crate",
+        );
+        write_file(
+            &registry.join(format!("src/{idx}/serde-1.0.0/src/lib.rs")),
+            b"fn main() {}",
+        );
+        write_file(
+            &registry.join(format!("cache/{idx}/libc-0.2.0.crate")),
+            b"c",
+        );
+
+        let crates = collect_cargo_crate_versions(&registry, None);
+        assert_eq!(crates.len(), 2);
+        assert_eq!(crates[0].name, "serde-1.0.0");
+        assert_eq!(crates[0].crate_bytes, 5);
+        assert_eq!(crates[0].src_bytes, 12);
+        assert!(crates[0].src_dir.is_some());
+        assert_eq!(crates[1].name, "libc-0.2.0");
+        assert!(crates[1].src_dir.is_none());
+
+        let summary = summarize_cargo_crate_versions(&crates, 90);
+        assert!(
+            summary.contains("crate versions: 2 件"),
+            "summary={summary}"
+        );
+        assert!(summary.contains("serde-1.0.0"), "summary={summary}");
+
+        let _ = std::fs::remove_dir_all(&registry);
+    }
+
+    #[test]
+    fn cargo_registry_cache_reports_parts_separately_and_offers_stale_crate_trash() {
+        let home = make_temp_dir("cargo-home");
+        let registry = home.join(".cargo/registry");
+        let idx = "index.crates.io-test";
+        write_file(&registry.join(format!("index/{idx}/config.json")), b"{}");
+        write_file(
+            &registry.join(format!("cache/{idx}/old-1.0.0.crate")),
+            b"old",
+        );
+        write_file(
+            &registry.join(format!("src/{idx}/old-1.0.0/lib.rs")),
+            b"old",
+        );
+        write_file(
+            &registry.join(format!("cache/{idx}/new-1.0.0.crate")),
+            b"new",
+        );
+        set_times_days_ago(&registry.join(format!("cache/{idx}/old-1.0.0.crate")), 200);
+        set_times_days_ago(&registry.join(format!("src/{idx}/old-1.0.0")), 200);
+
+        let ctx = RuleContext {
+            home_dir: home.clone(),
+            timeout: Duration::from_secs(5),
+            deadline: None,
+            privacy_mask_home: true,
+            source_roots: vec![],
+            size_cache: None,
+            sizer: scan::Sizer::Native,
+            cargo_stale_crate_days: 90,
+            events: None,
+        };
+        let outputs = cargo_registry_cache(&ctx);
+        let ids: Vec<&str> = outputs.iter().map(|o| o.finding.id.as_str()).collect();
+        assert!(ids.contains(&"cargo-registry-src"), "ids={ids:?}");
+        assert!(ids.contains(&"cargo-registry-cache"), "ids={ids:?}");
+        assert!(ids.contains(&"cargo-registry-index"), "ids={ids:?}");
+
+        let cache = outputs
+            .iter()
+            .find(|o| o.finding.id == "cargo-registry-cache")
+            .expect("cache finding");
+        let stale = cache
+            .actions
+            .iter()
+            .find(|a| a.id == "cargo-registry-stale-crates-trash")
+            .expect("stale action");
+        let ActionKind::TrashMove { paths } = &stale.kind else {
+            panic!("expected TrashMove, got: {:?}", stale.kind);
+        };
+        assert_eq!(
+            paths,
+            &vec![
+                format!("~/.cargo/registry/cache/{idx}/old-1.0.0.crate"),
+                format!("~/.cargo/registry/src/{idx}/old-1.0.0"),
+            ]
+        );
+        crate::actions::validate_actions(std::slice::from_ref(stale), &home)
+            .expect("stale paths are allowlisted");
+
+        let index = outputs
+            .iter()
+            .find(|o| o.finding.id == "cargo-registry-index")
+            .expect("index finding");
+        assert_eq!(index.finding.risk_level, RiskLevel::R2);
+        assert!(
+            index
+                .actions
+                .iter()
+                .all(|a| !matches!(a.kind, ActionKind::TrashMove { .. }))
+        );
+
+        let _ = std::fs::remove_dir_all(&home);
+    }
+
+    #[test]
+    fn version_spec_matches_exact_and_prefix_versions() {
+        assert!(version_spec_matches("v18.17.0", "v18.17.0"));
+        assert!(version_spec_matches("18", "v18.17.0"));
+        assert!(version_spec_matches("3.11", "3.11.4"));
+        assert!(version_spec_matches("ruby-3.2.2", "3.2.2"));
+        assert!(!version_spec_matches("3.1", "3.11.4"));
+        assert!(!version_spec_matches("18", "v20.1.0"));
+    }
+
+    #[test]
+    fn parse_tool_versions_and_mise_toml_tools() {
+        let tools = parse_tool_versions("nodejs 18.17.0\n# comment\npython 3.11.4 3.10.0 # x\n");
+        assert_eq!(
+            tools,
+            vec![
+                ("nodejs".to_string(), "18.17.0".to_string()),
+                ("python".to_string(), "3.11.4".to_string()),
+                ("python".to_string(), "3.10.0".to_string()),
+            ]
+        );
+
+        let mut tools = parse_mise_toml_tools(
+            "[tools]\nnode = \"20\"\npython = [\"3.12\", \"3.11\"]\nruby = { version = \"3.3.0\" }\n",
+        );
+        tools.sort();
+        assert_eq!(
+            tools,
+            vec![
+                ("node".to_string(), "20".to_string()),
+                ("python".to_string(), "3.11".to_string()),
+                ("python".to_string(), "3.12".to_string()),
+                ("ruby".to_string(), "3.3.0".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn language_versions_marks_unreferenced_versions_for_uninstall() {
+        let home = make_temp_dir("lang-home");
+        write_file(&home.join(".nvm/versions/node/v18.17.0/bin/node"), b"node");
+        write_file(&home.join(".nvm/versions/node/v20.1.0/bin/node"), b"node");
+        write_file(&home.join(".pyenv/versions/3.11.4/bin/python"), b"python");
+        write_file(&home.join(".asdf/installs/nodejs/16.0.0/bin/node"), b"node");
+        write_file(&home.join(".pyenv/version"), b"3.11.4\n");
+        write_file(&home.join("src/app/.nvmrc"), b"v18\n");
+
+        let ctx = RuleContext {
+            home_dir: home.clone(),
+            timeout: Duration::from_secs(5),
+            deadline: None,
+            privacy_mask_home: true,
+            source_roots: vec![home.join("src")],
+            size_cache: None,
+            sizer: scan::Sizer::Native,
+            cargo_stale_crate_days: 90,
+            events: None,
+        };
+        let outputs = language_versions(&ctx);
+        let find = |id: &str| {
+            outputs
+                .iter()
+                .find(|o| o.finding.id == id)
+                .unwrap_or_else(|| panic!("missing finding: {id}"))
+        };
+
+        let referenced = find("lang-version:nvm:node:v18.17.0");
+        assert!(referenced.actions.is_empty());
+        assert!(referenced.finding.title.contains("参照あり"));
+        assert!(find("lang-version:pyenv:python:3.11.4").actions.is_empty());
+
+        let nvm = find("lang-version:nvm:node:v20.1.0");
+        assert!(nvm.finding.title.contains("参照なし"));
+        let ActionKind::TrashMove { paths } = &nvm.actions[0].kind else {
+            panic!("expected TrashMove, got: {:?}", nvm.actions[0].kind);
+        };
+        assert_eq!(paths, &vec!["~/.nvm/versions/node/v20.1.0".to_string()]);
+        crate::actions::validate_actions(std::slice::from_ref(&nvm.actions[0]), &home)
+            .expect("nvm version dir is allowlisted");
+
+        let asdf = find("lang-version:asdf:nodejs:16.0.0");
+        assert_eq!(
+            asdf.actions[0].id,
+            "lang-version-uninstall:asdf:nodejs:16.0.0"
+        );
+        assert!(crate::actions::allowlisted_run_cmd(&asdf.actions[0]).is_some());
+
+        let no_roots = RuleContext {
+            source_roots: vec![],
+            ..ctx
+        };
+        let outputs = language_versions(&no_roots);
+        assert!(outputs.iter().all(|o| o.actions.is_empty()));
+
+        let _ = std::fs::remove_dir_all(&home);
+    }
+
+    #[test]
+    fn installer_leftovers_reports_installers_with_open_and_trash_actions() {
+        let home = make_temp_dir("installer-home");
+        write_file(&home.join("Downloads/Xcode_16.xip"), &[0u8; 4096]);
+        write_file(&home.join("Downloads/old/Tool.dmg"), &[0u8; 1024]);
+        write_file(&home.join("Desktop/Setup.pkg"), &[0u8; 512]);
+        write_file(
+            &home.join("Library/iTunes/iPhone Software Updates/iPhone.ipsw"),
+            &[0u8; 256],
+        );
+        write_file(&home.join("Downloads/Some.app/Contents/Inner.dmg"), b"x");
+        write_file(&home.join("Downloads/notes.txt"), b"x");
+
+        let ctx = RuleContext {
+            home_dir: home.clone(),
+            timeout: Duration::from_secs(5),
+            deadline: None,
+            privacy_mask_home: true,
+            source_roots: vec![],
+            size_cache: None,
+            sizer: scan::Sizer::Native,
+            cargo_stale_crate_days: 90,
+            events: None,
+        };
+        let outputs = installer_leftovers(&ctx);
+        let ids: Vec<&str> = outputs.iter().map(|o| o.finding.id.as_str()).collect();
+        assert_eq!(
+            ids,
+            vec![
+                "installer-leftover:~/Downloads/Xcode_16.xip",
+                "installer-leftover:~/Downloads/old/Tool.dmg",
+                "installer-leftover:~/Desktop/Setup.pkg",
+                "installer-leftover:~/Library/iTunes/iPhone Software Updates/iPhone.ipsw",
+            ]
+        );
+
+        let xip = &outputs[0];
+        assert_eq!(xip.finding.estimated_bytes, 4096);
+        assert!(xip.finding.title.contains("日前"), "{}", xip.finding.title);
+        assert!(matches!(
+            xip.actions[0].kind,
+            ActionKind::OpenInFinder { .. }
+        ));
+        let trash: Vec<ActionPlan> = outputs
+            .iter()
+            .flat_map(|o| o.actions.iter())
+            .filter(|a| matches!(a.kind, ActionKind::TrashMove { .. }))
+            .cloned()
+            .collect();
+        assert_eq!(trash.len(), 4);
+        crate::actions::validate_actions(&trash, &home).expect("installer files are allowlisted");
+
+        let _ = std::fs::remove_dir_all(&home);
+    }
 }
 
 fn dir_finding(
@@ -1285,19 +2967,29 @@ fn dir_finding(
         return None;
     }
 
-    let estimate = scan::estimate_dir_size(path, ctx.timeout, ctx.deadline).ok()?;
+    let estimate = scan::estimate_dir_size(
+        path,
+        ctx.timeout,
+        ctx.deadline,
+        ctx.size_cache.as_deref(),
+        ctx.sizer,
+    )
+    .ok()?;
     if estimate.bytes == 0 {
         return None;
     }
 
     let masked_path = maybe_mask_home(path, &ctx.home_dir, ctx.privacy_mask_home);
-    let evidence = vec![
+    let mut evidence = vec![
         Evidence::path(masked_path, ctx.privacy_mask_home),
         Evidence::stat(format!(
             "files={} errors={} method={:?}",
             estimate.file_count, estimate.error_count, estimate.method
         )),
     ];
+    if let Some(note) = estimate.cache_note() {
+        evidence.push(Evidence::stat(note));
+    }
 
     let mut actions = Vec::new();
     let mut action_refs = Vec::new();
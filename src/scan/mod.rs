@@ -1,13 +1,19 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::path::{Path, PathBuf};
+use std::sync::atomic::AtomicBool;
 use std::time::{Duration, Instant};
 
 use anyhow::{Context, Result};
 use globset::{Glob, GlobSet, GlobSetBuilder};
+use serde::{Deserialize, Serialize};
 use walkdir::WalkDir;
 
+use crate::cache::{DirKey, DirListing, LinkedFile, SizeCache};
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum SizeEstimateMethod {
+    Native,
+    NativeTruncated,
     Du,
     WalkDir,
     WalkDirTruncated,
@@ -20,13 +26,40 @@ pub struct SizeEstimate {
     pub file_count: u64,
     pub error_count: u64,
     pub method: SizeEstimateMethod,
+    pub cache_hits: u64,
+    pub cached_at: Option<u64>,
+    // st_blocks 由来の割り当て済みバイト数。du 以外で取得していない場合は None。
+    pub allocated_bytes: Option<u64>,
+}
+
+// ディレクトリサイズ推定の主手段。Du は `du -sk` を先に試す従来の挙動（失敗時は Native）。
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Sizer {
+    #[default]
+    Native,
+    Du,
+}
+
+impl std::str::FromStr for Sizer {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "native" => Ok(Sizer::Native),
+            "du" => Ok(Sizer::Du),
+            other => Err(format!(
+                "sizer が不正です: {other}（native|du を指定してください）"
+            )),
+        }
+    }
 }
 
 impl SizeEstimate {
     pub fn confidence(self) -> f64 {
         match self.method {
             SizeEstimateMethod::Du => 0.8,
-            SizeEstimateMethod::WalkDir => {
+            SizeEstimateMethod::Native | SizeEstimateMethod::WalkDir => {
                 if self.file_count == 0 {
                     0.5
                 } else if self.error_count == 0 {
@@ -35,16 +68,36 @@ impl SizeEstimate {
                     0.5
                 }
             }
-            SizeEstimateMethod::WalkDirTruncated => 0.3,
+            SizeEstimateMethod::NativeTruncated | SizeEstimateMethod::WalkDirTruncated => 0.3,
             SizeEstimateMethod::BudgetExhausted => 0.3,
         }
     }
+
+    pub fn cache_note(self) -> Option<String> {
+        let cached_at = self.cached_at?;
+        Some(format!(
+            "cache_hits={} cache_age={}",
+            self.cache_hits,
+            format_cache_age(crate::cache::unix_now().saturating_sub(cached_at))
+        ))
+    }
+}
+
+pub fn format_cache_age(secs: u64) -> String {
+    match secs {
+        0..60 => format!("{secs}s"),
+        60..3600 => format!("{}m", secs / 60),
+        3600..86400 => format!("{}h", secs / 3600),
+        _ => format!("{}d", secs / 86400),
+    }
 }
 
 pub fn estimate_dir_size(
     path: &Path,
     max_duration: Duration,
     deadline: Option<Instant>,
+    cache: Option<&SizeCache>,
+    sizer: Sizer,
 ) -> Result<SizeEstimate> {
     let mut end = Instant::now() + max_duration;
     if let Some(d) = deadline {
@@ -58,14 +111,20 @@ pub fn estimate_dir_size(
             file_count: 0,
             error_count: 1,
             method: SizeEstimateMethod::BudgetExhausted,
+            cache_hits: 0,
+            cached_at: None,
+            allocated_bytes: None,
         });
     }
 
-    if let Some(estimate) = estimate_dir_size_du(path, end) {
+    // キャッシュは Native の走査の中でディレクトリ単位に使う。du はツリー全体を 1 回で測るため対象外。
+    if sizer == Sizer::Du
+        && let Some(estimate) = estimate_dir_size_du(path, end)
+    {
         return Ok(estimate);
     }
 
-    Ok(estimate_dir_size_walkdir(path, end))
+    Ok(estimate_dir_size_native(path, end, cache))
 }
 
 fn estimate_dir_size_du(path: &Path, end: Instant) -> Option<SizeEstimate> {
@@ -92,42 +151,171 @@ fn estimate_dir_size_du(path: &Path, end: Instant) -> Option<SizeEstimate> {
         file_count: 0,
         error_count: 0,
         method: SizeEstimateMethod::Du,
+        cache_hits: 0,
+        cached_at: None,
+        allocated_bytes: Some(kb.saturating_mul(1024)),
     })
 }
 
-fn estimate_dir_size_walkdir(path: &Path, end: Instant) -> SizeEstimate {
-    let mut bytes: u64 = 0;
-    let mut files: u64 = 0;
+// openat/fstatat で走査する。子は親ディレクトリの fd からの相対名で開くため、パスの再解決が
+// 要らない（パスはキャッシュのキーにだけ使う）。親 fd は未走査の子が残る間だけ共有するので、
+// 同時に開く fd はおおむね深さ程度に収まる。deadline に達した場合もそこまでの集計を返す。
+// ハードリンク（st_nlink > 1）は (dev, ino) で 1 回だけ数える。キャッシュにヒットしたディレクトリは
+// readdir/fstatat を省き、記録済みの直下の集計とサブディレクトリ名を使う。
+fn estimate_dir_size_native(path: &Path, end: Instant, cache: Option<&SizeCache>) -> SizeEstimate {
+    use std::ffi::{CStr, CString, OsStr};
+    use std::os::fd::{AsRawFd, FromRawFd, OwnedFd};
+    use std::os::unix::ffi::OsStrExt;
+    use std::rc::Rc;
+
+    let mut tally = NativeTally::default();
     let mut errors: u64 = 0;
     let mut truncated = false;
+    let mut cache_hits: u64 = 0;
+    let mut cached_at: Option<u64> = None;
+    let filter = cache.map_or(0, |_| {
+        crate::cache::filter_key(&["estimate_dir_size_native".to_string()])
+    });
 
-    for entry in WalkDir::new(path).follow_links(false).into_iter() {
+    let mut stack: Vec<(Option<Rc<OwnedFd>>, CString, Option<PathBuf>)> = Vec::new();
+    match CString::new(path.as_os_str().as_bytes()) {
+        Ok(root) => stack.push((None, root, cache.map(|_| path.to_path_buf()))),
+        Err(_) => errors = 1,
+    }
+    while let Some((parent, name, dir_path)) = stack.pop() {
         if Instant::now() >= end {
             truncated = true;
             break;
         }
-        match entry {
-            Ok(entry) => {
-                let ft = entry.file_type();
-                if !ft.is_file() {
-                    continue;
-                }
-                let meta = entry
-                    .metadata()
-                    .with_context(|| format!("メタデータ取得: {}", entry.path().display()));
-                match meta {
-                    Ok(meta) => {
-                        bytes = bytes.saturating_add(meta.len());
-                        files = files.saturating_add(1);
-                    }
-                    Err(_) => {
-                        errors = errors.saturating_add(1);
-                    }
+        let at = parent.as_ref().map_or(libc::AT_FDCWD, |fd| fd.as_raw_fd());
+        let raw = unsafe {
+            libc::openat(
+                at,
+                name.as_ptr(),
+                libc::O_RDONLY | libc::O_DIRECTORY | libc::O_NOFOLLOW | libc::O_CLOEXEC,
+            )
+        };
+        drop(parent);
+        if raw < 0 {
+            errors = errors.saturating_add(1);
+            continue;
+        }
+        let fd = Rc::new(unsafe { OwnedFd::from_raw_fd(raw) });
+        let child_path = |child: &[u8]| dir_path.as_ref().map(|p| p.join(OsStr::from_bytes(child)));
+
+        let key = match (cache, &dir_path) {
+            (Some(_), Some(_)) => {
+                let mut st: libc::stat = unsafe { std::mem::zeroed() };
+                let rc = unsafe { libc::fstat(fd.as_raw_fd(), &mut st) };
+                (rc == 0).then_some(DirKey {
+                    ino: st.st_ino as u64,
+                    mtime_secs: st.st_mtime as i64,
+                    mtime_nsecs: st.st_mtime_nsec as i64,
+                })
+            }
+            _ => None,
+        };
+        let cached = match (cache, &dir_path, key) {
+            (Some(cache), Some(dir_path), Some(key)) => cache.lookup(dir_path, key, filter),
+            _ => None,
+        };
+        if let Some(record) = cached {
+            for sub in &record.listing.subdirs {
+                if let Ok(sub_name) = CString::new(sub.as_bytes()) {
+                    stack.push((Some(Rc::clone(&fd)), sub_name, child_path(sub.as_bytes())));
                 }
             }
-            Err(_) => {
-                errors = errors.saturating_add(1);
+            tally.add(&record.listing);
+            cache_hits = cache_hits.saturating_add(1);
+            cached_at = Some(cached_at.map_or(record.cached_at, |t| t.min(record.cached_at)));
+            continue;
+        }
+
+        // fdopendir は渡した fd を所有するため、子の fstatat/openat 用とは別に dup して渡す。
+        let dup = unsafe { libc::dup(fd.as_raw_fd()) };
+        let dir = if dup < 0 {
+            std::ptr::null_mut()
+        } else {
+            unsafe { libc::fdopendir(dup) }
+        };
+        if dir.is_null() {
+            if dup >= 0 {
+                unsafe { libc::close(dup) };
             }
+            errors = errors.saturating_add(1);
+            continue;
+        }
+        let mut listing = DirListing::default();
+        let mut dir_errors: u64 = 0;
+        // UTF-8 でない名前はキャッシュに正しく記録できないため、そのディレクトリは保存しない。
+        let mut cacheable = true;
+        loop {
+            if Instant::now() >= end {
+                truncated = true;
+                break;
+            }
+            let ent = unsafe { libc::readdir(dir) };
+            if ent.is_null() {
+                break;
+            }
+            let child = unsafe { CStr::from_ptr((*ent).d_name.as_ptr()) };
+            if matches!(child.to_bytes(), b"." | b"..") {
+                continue;
+            }
+            let mut st: libc::stat = unsafe { std::mem::zeroed() };
+            let rc = unsafe {
+                libc::fstatat(
+                    fd.as_raw_fd(),
+                    child.as_ptr(),
+                    &mut st,
+                    libc::AT_SYMLINK_NOFOLLOW,
+                )
+            };
+            if rc != 0 {
+                dir_errors = dir_errors.saturating_add(1);
+                continue;
+            }
+            let kind = st.st_mode & libc::S_IFMT;
+            let allocated = u64::try_from(st.st_blocks).unwrap_or(0).saturating_mul(512);
+            if kind == libc::S_IFREG && st.st_nlink > 1 {
+                listing.linked.push(LinkedFile {
+                    dev: st.st_dev as u64,
+                    ino: st.st_ino as u64,
+                    bytes: u64::try_from(st.st_size).unwrap_or(0),
+                    allocated_bytes: allocated,
+                });
+                continue;
+            }
+            listing.allocated_bytes = listing.allocated_bytes.saturating_add(allocated);
+            if kind == libc::S_IFDIR {
+                match child.to_str() {
+                    Ok(sub) => listing.subdirs.push(sub.to_string()),
+                    Err(_) => cacheable = false,
+                }
+                stack.push((
+                    Some(Rc::clone(&fd)),
+                    child.to_owned(),
+                    child_path(child.to_bytes()),
+                ));
+            } else if kind == libc::S_IFREG {
+                listing.file_bytes = listing
+                    .file_bytes
+                    .saturating_add(u64::try_from(st.st_size).unwrap_or(0));
+                listing.file_count = listing.file_count.saturating_add(1);
+            }
+        }
+        unsafe { libc::closedir(dir) };
+        tally.add(&listing);
+        errors = errors.saturating_add(dir_errors);
+        if let (Some(cache), Some(dir_path), Some(key)) = (cache, &dir_path, key)
+            && cacheable
+            && dir_errors == 0
+            && !truncated
+        {
+            cache.store(dir_path, key, filter, listing);
+        }
+        if truncated {
+            break;
         }
     }
 
@@ -136,14 +324,40 @@ fn estimate_dir_size_walkdir(path: &Path, end: Instant) -> SizeEstimate {
     }
 
     SizeEstimate {
-        bytes,
-        file_count: files,
+        bytes: tally.bytes,
+        file_count: tally.files,
         error_count: errors,
         method: if truncated {
-            SizeEstimateMethod::WalkDirTruncated
+            SizeEstimateMethod::NativeTruncated
         } else {
-            SizeEstimateMethod::WalkDir
+            SizeEstimateMethod::Native
         },
+        cache_hits,
+        cached_at,
+        allocated_bytes: Some(tally.allocated),
+    }
+}
+
+#[derive(Default)]
+struct NativeTally {
+    bytes: u64,
+    allocated: u64,
+    files: u64,
+    linked: std::collections::HashSet<(u64, u64)>,
+}
+
+impl NativeTally {
+    fn add(&mut self, listing: &DirListing) {
+        self.bytes = self.bytes.saturating_add(listing.file_bytes);
+        self.allocated = self.allocated.saturating_add(listing.allocated_bytes);
+        self.files = self.files.saturating_add(listing.file_count);
+        for file in &listing.linked {
+            if self.linked.insert((file.dev, file.ino)) {
+                self.bytes = self.bytes.saturating_add(file.bytes);
+                self.allocated = self.allocated.saturating_add(file.allocated_bytes);
+                self.files = self.files.saturating_add(1);
+            }
+        }
     }
 }
 
@@ -159,7 +373,86 @@ pub struct TopDirsResult {
     pub total_bytes: u64,
     pub file_count: u64,
     pub error_count: u64,
+    pub method: SizeEstimateMethod,
+    pub cache_hits: u64,
+    pub cached_at: Option<u64>,
     pub entries: Vec<TopDirEntry>,
+    // 走査した全ディレクトリの直下ファイル合計（0 のものは省く）。entries は top_n で
+    // 切り詰められるため、folded 出力などツリー全体が必要な用途はこちらを使う。
+    pub dir_bytes: HashMap<PathBuf, u64>,
+    pub skipped_mounts: Vec<PathBuf>,
+}
+
+impl TopDirsResult {
+    pub fn buckets(&self, max_depth: usize) -> Vec<TopDirEntry> {
+        let max_depth = max_depth.max(1);
+        let mut buckets: BTreeMap<PathBuf, u64> = BTreeMap::new();
+        for (dir, bytes) in &self.dir_bytes {
+            if let Some(bucket) = bucket_for_dir(dir, &self.root, max_depth) {
+                let acc = buckets.entry(bucket).or_insert(0);
+                *acc = acc.saturating_add(*bytes);
+            }
+        }
+        buckets
+            .into_iter()
+            .map(|(path, bytes)| TopDirEntry { path, bytes })
+            .collect()
+    }
+}
+
+const TOP_DIRS_MAX_WORKERS: usize = 8;
+const TOP_DIRS_PROGRESS_EVERY: u64 = 2048;
+
+#[derive(Default)]
+struct WalkQueue {
+    dirs: Vec<PathBuf>,
+    pending: usize,
+    stopped: bool,
+}
+
+#[derive(Default)]
+struct WalkTally {
+    buckets: HashMap<PathBuf, u64>,
+    dirs: HashMap<PathBuf, u64>,
+    skipped_mounts: Vec<PathBuf>,
+    total_bytes: u64,
+    file_count: u64,
+    error_count: u64,
+    cache_hits: u64,
+    cached_at: Option<u64>,
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct WalkLimits {
+    pub deadline: Option<Instant>,
+    // root と異なるデバイス（別ボリューム/ネットワーク共有のマウント）へ降りない。
+    pub one_file_system: bool,
+    // Ctrl-C などで立つフラグ。立った時点で deadline 到達と同じく打ち切る。
+    pub cancel: Option<&'static AtomicBool>,
+}
+
+impl WalkLimits {
+    pub fn until(deadline: Instant) -> Self {
+        Self {
+            deadline: Some(deadline),
+            ..Self::default()
+        }
+    }
+
+    fn expired(&self) -> bool {
+        self.deadline.is_some_and(|d| Instant::now() >= d)
+            || self
+                .cancel
+                .is_some_and(|c| c.load(std::sync::atomic::Ordering::Relaxed))
+    }
+
+    fn root_dev(&self, root: &Path) -> Option<u64> {
+        use std::os::unix::fs::MetadataExt;
+        if !self.one_file_system {
+            return None;
+        }
+        std::fs::symlink_metadata(root).ok().map(|m| m.dev())
+    }
 }
 
 pub fn top_directories(
@@ -167,71 +460,1018 @@ pub fn top_directories(
     max_depth: usize,
     top_n: usize,
     excludes: &[String],
+    limits: WalkLimits,
+    progress: Option<&(dyn Fn(u64, u64) + Sync)>,
+    cache: Option<&SizeCache>,
 ) -> Result<TopDirsResult> {
-    let mut buckets: HashMap<PathBuf, u64> = HashMap::new();
-    let mut total_bytes: u64 = 0;
-    let mut file_count: u64 = 0;
-    let mut error_count: u64 = 0;
+    use std::os::unix::fs::MetadataExt;
+    use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+    use std::sync::{Condvar, Mutex};
 
     let max_depth = max_depth.max(1);
     let exclude_set = build_exclude_set(excludes)?;
+    let mut filter_src = vec!["top_directories".to_string()];
+    if limits.one_file_system {
+        filter_src.push("one_file_system".to_string());
+    }
+    filter_src.extend(excludes.iter().cloned());
+    let filter = crate::cache::filter_key(&filter_src);
+
+    let queue = Mutex::new(WalkQueue::default());
+    let wakeup = Condvar::new();
+    let truncated = AtomicBool::new(false);
+    let files_seen = AtomicU64::new(0);
+    let bytes_seen = AtomicU64::new(0);
+    let mut tally = WalkTally::default();
+
+    if !exclude_set.is_match(root) {
+        match std::fs::symlink_metadata(root) {
+            Ok(meta) if meta.is_dir() => {
+                let mut q = queue.lock().expect("walk queue");
+                q.dirs.push(root.to_path_buf());
+                q.pending = 1;
+            }
+            Ok(meta) => {
+                tally.total_bytes = meta.len();
+                tally.file_count = u64::from(meta.is_file());
+            }
+            Err(_) => tally.error_count = 1,
+        }
+    }
+
+    let expired = || limits.expired();
+    let root_dev = limits.root_dev(root);
+    let workers = std::thread::available_parallelism()
+        .map(|n| n.get())
+        .unwrap_or(1)
+        .clamp(1, TOP_DIRS_MAX_WORKERS);
+
+    // パニックしたワーカーは pending を減らせず、他のワーカーが待ち続けるため、巻き戻り時にキューを止める。
+    struct StopOnPanic<'a>(&'a Mutex<WalkQueue>, &'a Condvar);
+    impl Drop for StopOnPanic<'_> {
+        fn drop(&mut self) {
+            if std::thread::panicking() {
+                let mut q = self.0.lock().unwrap_or_else(|e| e.into_inner());
+                q.stopped = true;
+                self.1.notify_all();
+            }
+        }
+    }
+
+    let worker = || {
+        let _guard = StopOnPanic(&queue, &wakeup);
+        let mut local = WalkTally::default();
+        loop {
+            let dir = {
+                let mut q = queue.lock().expect("walk queue");
+                loop {
+                    if q.stopped {
+                        return local;
+                    }
+                    if expired() {
+                        truncated.store(true, Ordering::Relaxed);
+                        q.stopped = true;
+                        wakeup.notify_all();
+                        return local;
+                    }
+                    if let Some(dir) = q.dirs.pop() {
+                        break dir;
+                    }
+                    if q.pending == 0 {
+                        wakeup.notify_all();
+                        return local;
+                    }
+                    q = wakeup
+                        .wait_timeout(q, Duration::from_millis(50))
+                        .expect("walk queue")
+                        .0;
+                }
+            };
+
+            let key = cache.and_then(|_| {
+                std::fs::symlink_metadata(&dir)
+                    .ok()
+                    .map(|m| DirKey::from_metadata(&m))
+            });
+            let cached = match (cache, key) {
+                (Some(cache), Some(key)) => cache.lookup(&dir, key, filter),
+                _ => None,
+            };
+
+            let mut subdirs = Vec::new();
+            let mut dir_bytes: u64 = 0;
+            let mut dir_files: u64 = 0;
+            if let Some(record) = cached {
+                dir_bytes = record.listing.file_bytes;
+                dir_files = record.listing.file_count;
+                subdirs.extend(record.listing.subdirs.iter().map(|name| dir.join(name)));
+                local.cache_hits = local.cache_hits.saturating_add(1);
+                local.cached_at = Some(
+                    local
+                        .cached_at
+                        .map_or(record.cached_at, |t| t.min(record.cached_at)),
+                );
+            } else {
+                let mut dir_errors: u64 = 0;
+                let mut complete = false;
+                match std::fs::read_dir(&dir) {
+                    Ok(rd) => {
+                        complete = true;
+                        for entry in rd {
+                            if expired() {
+                                truncated.store(true, Ordering::Relaxed);
+                                complete = false;
+                                break;
+                            }
+                            let Ok(entry) = entry else {
+                                dir_errors = dir_errors.saturating_add(1);
+                                continue;
+                            };
+                            let path = entry.path();
+                            if exclude_set.is_match(&path) {
+                                continue;
+                            }
+                            let Ok(ft) = entry.file_type() else {
+                                dir_errors = dir_errors.saturating_add(1);
+                                continue;
+                            };
+                            if ft.is_dir() {
+                                if let Some(root_dev) = root_dev
+                                    && entry.metadata().is_ok_and(|m| m.dev() != root_dev)
+                                {
+                                    local.skipped_mounts.push(path);
+                                    continue;
+                                }
+                                subdirs.push(path);
+                                continue;
+                            }
+                            if !ft.is_file() {
+                                continue;
+                            }
+                            let Ok(meta) = entry.metadata() else {
+                                dir_errors = dir_errors.saturating_add(1);
+                                continue;
+                            };
+                            dir_bytes = dir_bytes.saturating_add(meta.len());
+                            dir_files = dir_files.saturating_add(1);
+                        }
+                    }
+                    Err(_) => {
+                        dir_errors = dir_errors.saturating_add(1);
+                    }
+                }
+                local.error_count = local.error_count.saturating_add(dir_errors);
+                if let (Some(cache), Some(key)) = (cache, key)
+                    && complete
+                    && dir_errors == 0
+                {
+                    let names = subdirs
+                        .iter()
+                        .filter_map(|p| p.file_name())
+                        .map(|n| n.to_string_lossy().into_owned())
+                        .collect();
+                    let listing = DirListing {
+                        file_bytes: dir_bytes,
+                        file_count: dir_files,
+                        subdirs: names,
+                        ..DirListing::default()
+                    };
+                    cache.store(&dir, key, filter, listing);
+                }
+            }
+
+            local.total_bytes = local.total_bytes.saturating_add(dir_bytes);
+            local.file_count = local.file_count.saturating_add(dir_files);
+            if dir_bytes > 0
+                && let Some(bucket) = bucket_for_dir(&dir, root, max_depth)
+            {
+                let acc = local.buckets.entry(bucket).or_insert(0);
+                *acc = acc.saturating_add(dir_bytes);
+            }
+            if dir_bytes > 0 {
+                local.dirs.insert(dir.clone(), dir_bytes);
+            }
+            let bytes_after = bytes_seen.fetch_add(dir_bytes, Ordering::Relaxed) + dir_bytes;
+            if dir_files > 0 {
+                let before = files_seen.fetch_add(dir_files, Ordering::Relaxed);
+                let after = before + dir_files;
+                if let Some(progress) = progress
+                    && before / TOP_DIRS_PROGRESS_EVERY != after / TOP_DIRS_PROGRESS_EVERY
+                {
+                    progress(after, bytes_after);
+                }
+            }
+
+            let mut q = queue.lock().expect("walk queue");
+            q.pending = q.pending - 1 + subdirs.len();
+            q.dirs.extend(subdirs);
+            wakeup.notify_all();
+        }
+    };
+
+    let joined: Vec<std::thread::Result<WalkTally>> = std::thread::scope(|scope| {
+        let handles: Vec<_> = (0..workers).map(|_| scope.spawn(worker)).collect();
+        handles.into_iter().map(|h| h.join()).collect()
+    });
+    let mut partials = Vec::with_capacity(joined.len());
+    for part in joined {
+        match part {
+            Ok(part) => partials.push(part),
+            Err(payload) => std::panic::resume_unwind(payload),
+        }
+    }
+    for part in partials {
+        tally.total_bytes = tally.total_bytes.saturating_add(part.total_bytes);
+        tally.file_count = tally.file_count.saturating_add(part.file_count);
+        tally.error_count = tally.error_count.saturating_add(part.error_count);
+        tally.cache_hits = tally.cache_hits.saturating_add(part.cache_hits);
+        if let Some(t) = part.cached_at {
+            tally.cached_at = Some(tally.cached_at.map_or(t, |c| c.min(t)));
+        }
+        for (bucket, bytes) in part.buckets {
+            let acc = tally.buckets.entry(bucket).or_insert(0);
+            *acc = acc.saturating_add(bytes);
+        }
+        tally.dirs.extend(part.dirs);
+        tally.skipped_mounts.extend(part.skipped_mounts);
+    }
+    if let Some(progress) = progress {
+        progress(
+            files_seen.load(Ordering::Relaxed),
+            bytes_seen.load(Ordering::Relaxed),
+        );
+    }
 
-    let walker = WalkDir::new(root)
-        .follow_links(false)
+    let truncated = truncated.load(Ordering::Relaxed);
+    if truncated {
+        tally.error_count = tally.error_count.max(1);
+    }
+
+    let mut entries: Vec<TopDirEntry> = tally
+        .buckets
         .into_iter()
-        .filter_entry(|e| !exclude_set.is_match(e.path()));
+        .map(|(path, bytes)| TopDirEntry { path, bytes })
+        .collect();
+    entries.sort_by(|a, b| b.bytes.cmp(&a.bytes).then_with(|| a.path.cmp(&b.path)));
+    entries.truncate(top_n.max(1));
+
+    Ok(TopDirsResult {
+        root: root.to_path_buf(),
+        total_bytes: tally.total_bytes,
+        file_count: tally.file_count,
+        error_count: tally.error_count,
+        method: if truncated {
+            SizeEstimateMethod::WalkDirTruncated
+        } else {
+            SizeEstimateMethod::WalkDir
+        },
+        cache_hits: tally.cache_hits,
+        cached_at: tally.cached_at,
+        entries,
+        dir_bytes: tally.dirs,
+        skipped_mounts: {
+            let mut mounts = tally.skipped_mounts;
+            mounts.sort();
+            mounts
+        },
+    })
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GrowthEntry {
+    pub path: PathBuf,
+    pub start_bytes: u64,
+    pub end_bytes: u64,
+}
+
+impl GrowthEntry {
+    pub fn growth_bytes(&self) -> u64 {
+        self.end_bytes.saturating_sub(self.start_bytes)
+    }
+}
+
+// 2 時点の buckets を比べ、増えたものを増加量の大きい順に返す（減少/消滅は対象外）。
+pub fn rank_growth(start: &[TopDirEntry], end: &[TopDirEntry], top_n: usize) -> Vec<GrowthEntry> {
+    let before: HashMap<&Path, u64> = start.iter().map(|e| (e.path.as_path(), e.bytes)).collect();
+    let mut entries: Vec<GrowthEntry> = end
+        .iter()
+        .map(|e| GrowthEntry {
+            path: e.path.clone(),
+            start_bytes: before.get(e.path.as_path()).copied().unwrap_or(0),
+            end_bytes: e.bytes,
+        })
+        .filter(|e| e.growth_bytes() > 0)
+        .collect();
+    entries.sort_by(|a, b| {
+        b.growth_bytes()
+            .cmp(&a.growth_bytes())
+            .then_with(|| a.path.cmp(&b.path))
+    });
+    entries.truncate(top_n.max(1));
+    entries
+}
+
+pub const DUPLICATE_MIN_BYTES: u64 = 1024 * 1024;
+const DUPLICATE_PARTIAL_HASH_BYTES: usize = 64 * 1024;
+
+#[derive(Debug, Clone)]
+pub struct DuplicateSet {
+    pub hash: String,
+    pub file_bytes: u64,
+    pub paths: Vec<PathBuf>,
+}
+
+impl DuplicateSet {
+    pub fn wasted_bytes(&self) -> u64 {
+        self.file_bytes
+            .saturating_mul(self.paths.len().saturating_sub(1) as u64)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct DuplicatesResult {
+    pub file_count: u64,
+    pub error_count: u64,
+    pub truncated: bool,
+    pub sets: Vec<DuplicateSet>,
+}
 
-    for entry in walker {
-        let entry = match entry {
-            Ok(entry) => entry,
-            Err(_) => {
+pub fn find_duplicates(
+    roots: &[PathBuf],
+    min_bytes: u64,
+    excludes: &[String],
+    limits: WalkLimits,
+) -> Result<DuplicatesResult> {
+    use std::os::unix::fs::MetadataExt;
+
+    let exclude_set = build_exclude_set(excludes)?;
+    let deadline = limits.deadline;
+    let expired = || limits.expired();
+
+    let mut by_size: HashMap<u64, Vec<PathBuf>> = HashMap::new();
+    let mut seen_inodes: std::collections::HashSet<(u64, u64)> = std::collections::HashSet::new();
+    let mut file_count: u64 = 0;
+    let mut error_count: u64 = 0;
+    let mut truncated = false;
+
+    'roots: for root in roots {
+        let walker = WalkDir::new(root)
+            .follow_links(false)
+            .same_file_system(limits.one_file_system)
+            .into_iter()
+            .filter_entry(|e| !exclude_set.is_match(e.path()));
+        for entry in walker {
+            if expired() {
+                truncated = true;
+                break 'roots;
+            }
+            let entry = match entry {
+                Ok(entry) => entry,
+                Err(_) => {
+                    error_count = error_count.saturating_add(1);
+                    continue;
+                }
+            };
+            if !entry.file_type().is_file() {
+                continue;
+            }
+            let Ok(meta) = entry.metadata() else {
                 error_count = error_count.saturating_add(1);
                 continue;
+            };
+            file_count = file_count.saturating_add(1);
+            if meta.len() < min_bytes.max(1) {
+                continue;
+            }
+            // ハードリンクは容量を重複して消費しないため、同一 inode は1件として扱う。
+            if !seen_inodes.insert((meta.dev(), meta.ino())) {
+                continue;
+            }
+            by_size
+                .entry(meta.len())
+                .or_default()
+                .push(entry.path().to_path_buf());
+        }
+    }
+
+    let mut candidates: Vec<(u64, Vec<PathBuf>)> = by_size
+        .into_iter()
+        .filter(|(_, paths)| paths.len() > 1)
+        .collect();
+    candidates.sort_by_key(|(size, _)| std::cmp::Reverse(*size));
+
+    let mut sets = Vec::new();
+    'sizes: for (size, paths) in candidates {
+        let mut by_partial: HashMap<blake3::Hash, Vec<PathBuf>> = HashMap::new();
+        for path in paths {
+            if expired() {
+                truncated = true;
+                break 'sizes;
+            }
+            match hash_file(&path, Some(DUPLICATE_PARTIAL_HASH_BYTES), deadline) {
+                Ok(hash) => by_partial.entry(hash).or_default().push(path),
+                Err(e) if e.kind() == std::io::ErrorKind::TimedOut => {
+                    truncated = true;
+                    break 'sizes;
+                }
+                Err(_) => error_count = error_count.saturating_add(1),
+            }
+        }
+
+        for (_, paths) in by_partial.into_iter().filter(|(_, p)| p.len() > 1) {
+            let mut by_full: HashMap<blake3::Hash, Vec<PathBuf>> = HashMap::new();
+            for path in paths {
+                if expired() {
+                    truncated = true;
+                    break 'sizes;
+                }
+                match hash_file(&path, None, deadline) {
+                    Ok(hash) => by_full.entry(hash).or_default().push(path),
+                    Err(e) if e.kind() == std::io::ErrorKind::TimedOut => {
+                        truncated = true;
+                        break 'sizes;
+                    }
+                    Err(_) => error_count = error_count.saturating_add(1),
+                }
             }
+            for (hash, mut paths) in by_full.into_iter().filter(|(_, p)| p.len() > 1) {
+                paths.sort();
+                sets.push(DuplicateSet {
+                    hash: hash.to_hex().to_string(),
+                    file_bytes: size,
+                    paths,
+                });
+            }
+        }
+    }
+
+    sets.sort_by(|a, b| {
+        b.wasted_bytes()
+            .cmp(&a.wasted_bytes())
+            .then_with(|| a.paths.cmp(&b.paths))
+    });
+
+    Ok(DuplicatesResult {
+        file_count,
+        error_count,
+        truncated,
+        sets,
+    })
+}
+
+fn hash_file(
+    path: &Path,
+    limit: Option<usize>,
+    deadline: Option<Instant>,
+) -> std::io::Result<blake3::Hash> {
+    use std::io::Read;
+
+    let mut file = std::fs::File::open(path)?;
+    let mut hasher = blake3::Hasher::new();
+    let mut buf = vec![0u8; 1024 * 1024];
+    let mut remaining = limit.unwrap_or(usize::MAX);
+    while remaining > 0 {
+        if deadline.is_some_and(|d| Instant::now() >= d) {
+            return Err(std::io::Error::from(std::io::ErrorKind::TimedOut));
+        }
+        let want = buf.len().min(remaining);
+        let n = file.read(&mut buf[..want])?;
+        if n == 0 {
+            break;
+        }
+        hasher.update(&buf[..n]);
+        remaining -= n;
+    }
+    Ok(hasher.finalize())
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TreeNodeKind {
+    #[default]
+    Dir,
+    File,
+    Other,
+    Excluded,
+    // 別ファイルシステムのマウントポイント（--one-file-system で降りなかったもの）。
+    OtherFs,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TreeNode {
+    pub name: String,
+    pub kind: TreeNodeKind,
+    pub bytes: u64,
+    pub disk_bytes: u64,
+    pub read_error: bool,
+    pub children: Vec<TreeNode>,
+}
+
+impl TreeNode {
+    pub fn find(&self, rel: &Path) -> Option<&TreeNode> {
+        let mut node = self;
+        for component in rel.components() {
+            let name = component.as_os_str().to_string_lossy();
+            node = node
+                .children
+                .iter()
+                .find(|c| c.kind == TreeNodeKind::Dir && c.name == name)?;
+        }
+        Some(node)
+    }
+
+    pub fn graft(&mut self, rel: &Path, subtree: TreeNode) {
+        let mut components: Vec<String> = rel
+            .components()
+            .map(|c| c.as_os_str().to_string_lossy().into_owned())
+            .collect();
+        let Some(last) = components.pop() else {
+            *self = TreeNode {
+                name: std::mem::take(&mut self.name),
+                ..subtree
+            };
+            return;
         };
+        let mut node = self;
+        for name in components {
+            let idx = match node
+                .children
+                .iter()
+                .position(|c| c.kind == TreeNodeKind::Dir && c.name == name)
+            {
+                Some(idx) => idx,
+                None => {
+                    node.children.push(TreeNode {
+                        name,
+                        ..TreeNode::default()
+                    });
+                    node.children.len() - 1
+                }
+            };
+            node = &mut node.children[idx];
+        }
+        node.children.retain(|c| c.name != last);
+        node.children.push(TreeNode {
+            name: last,
+            ..subtree
+        });
+    }
+}
 
-        let path = entry.path();
-        if !entry.file_type().is_file() {
-            continue;
+#[derive(Debug, Clone)]
+pub struct TreeResult {
+    pub tree: TreeNode,
+    pub file_count: u64,
+    pub error_count: u64,
+    pub method: SizeEstimateMethod,
+}
+
+pub fn build_tree(root: &Path, excludes: &[String], limits: WalkLimits) -> Result<TreeResult> {
+    let exclude_set = build_exclude_set(excludes)?;
+    let mut walk = TreeWalk {
+        exclude_set: &exclude_set,
+        limits,
+        root_dev: limits.root_dev(root),
+        file_count: 0,
+        error_count: 0,
+        truncated: false,
+    };
+
+    let mut tree = TreeNode {
+        name: root.display().to_string(),
+        ..TreeNode::default()
+    };
+    match std::fs::symlink_metadata(root) {
+        Ok(meta) if meta.is_dir() => {
+            if exclude_set.is_match(root) {
+                tree.kind = TreeNodeKind::Excluded;
+            } else {
+                walk.fill_dir(&mut tree, root);
+            }
         }
+        Ok(meta) => {
+            tree.kind = TreeNodeKind::File;
+            tree.bytes = meta.len();
+            tree.disk_bytes = disk_bytes(&meta);
+            walk.file_count = 1;
+        }
+        Err(_) => {
+            tree.read_error = true;
+            walk.error_count = 1;
+        }
+    }
 
-        let meta = entry
-            .metadata()
-            .with_context(|| format!("メタデータ取得: {}", path.display()));
-        let meta = match meta {
-            Ok(meta) => meta,
-            Err(_) => {
-                error_count = error_count.saturating_add(1);
+    if walk.truncated {
+        walk.error_count = walk.error_count.max(1);
+    }
+
+    Ok(TreeResult {
+        tree,
+        file_count: walk.file_count,
+        error_count: walk.error_count,
+        method: if walk.truncated {
+            SizeEstimateMethod::WalkDirTruncated
+        } else {
+            SizeEstimateMethod::WalkDir
+        },
+    })
+}
+
+struct TreeWalk<'a> {
+    exclude_set: &'a GlobSet,
+    limits: WalkLimits,
+    root_dev: Option<u64>,
+    file_count: u64,
+    error_count: u64,
+    truncated: bool,
+}
+
+impl TreeWalk<'_> {
+    fn fill_dir(&mut self, node: &mut TreeNode, dir: &Path) {
+        use std::os::unix::fs::MetadataExt;
+
+        if let Ok(meta) = std::fs::symlink_metadata(dir) {
+            node.bytes = meta.len();
+            node.disk_bytes = disk_bytes(&meta);
+        }
+        let Ok(rd) = std::fs::read_dir(dir) else {
+            node.read_error = true;
+            self.error_count = self.error_count.saturating_add(1);
+            return;
+        };
+        let mut entries: Vec<std::fs::DirEntry> = Vec::new();
+        for entry in rd {
+            match entry {
+                Ok(entry) => entries.push(entry),
+                Err(_) => {
+                    node.read_error = true;
+                    self.error_count = self.error_count.saturating_add(1);
+                }
+            }
+        }
+        entries.sort_by_key(|e| e.file_name());
+
+        for entry in entries {
+            if self.truncated || self.limits.expired() {
+                self.truncated = true;
+                return;
+            }
+            let path = entry.path();
+            let mut child = TreeNode {
+                name: entry.file_name().to_string_lossy().into_owned(),
+                ..TreeNode::default()
+            };
+            if self.exclude_set.is_match(&path) {
+                child.kind = TreeNodeKind::Excluded;
+                node.children.push(child);
                 continue;
             }
-        };
+            let Ok(meta) = std::fs::symlink_metadata(&path) else {
+                child.kind = TreeNodeKind::Other;
+                child.read_error = true;
+                self.error_count = self.error_count.saturating_add(1);
+                node.children.push(child);
+                continue;
+            };
+            if meta.is_dir() {
+                if self.root_dev.is_some_and(|dev| dev != meta.dev()) {
+                    child.kind = TreeNodeKind::OtherFs;
+                } else {
+                    self.fill_dir(&mut child, &path);
+                }
+            } else {
+                child.kind = if meta.is_file() {
+                    self.file_count = self.file_count.saturating_add(1);
+                    TreeNodeKind::File
+                } else {
+                    TreeNodeKind::Other
+                };
+                child.bytes = meta.len();
+                child.disk_bytes = disk_bytes(&meta);
+            }
+            node.children.push(child);
+        }
+    }
+}
+
+fn disk_bytes(meta: &std::fs::Metadata) -> u64 {
+    use std::os::unix::fs::MetadataExt;
+    meta.blocks().saturating_mul(512)
+}
 
-        let bytes = meta.len();
-        total_bytes = total_bytes.saturating_add(bytes);
-        file_count = file_count.saturating_add(1);
+pub fn top_directories_from_tree(
+    tree: &TreeNode,
+    root: &Path,
+    max_depth: usize,
+    top_n: usize,
+    excludes: &[String],
+) -> Result<TopDirsResult> {
+    let exclude_set = build_exclude_set(excludes)?;
+    let max_depth = max_depth.max(1);
+    let mut tally = WalkTally::default();
 
-        if let Some(bucket) = bucket_dir(path, root, max_depth) {
-            let acc = buckets.entry(bucket).or_insert(0);
-            *acc = acc.saturating_add(bytes);
+    fn visit(
+        node: &TreeNode,
+        path: &Path,
+        root: &Path,
+        max_depth: usize,
+        exclude_set: &GlobSet,
+        tally: &mut WalkTally,
+    ) {
+        if node.read_error {
+            tally.error_count = tally.error_count.saturating_add(1);
+        }
+        let mut dir_bytes: u64 = 0;
+        for child in &node.children {
+            let child_path = path.join(&child.name);
+            if exclude_set.is_match(&child_path) {
+                continue;
+            }
+            match child.kind {
+                TreeNodeKind::Dir => visit(child, &child_path, root, max_depth, exclude_set, tally),
+                TreeNodeKind::File => {
+                    dir_bytes = dir_bytes.saturating_add(child.bytes);
+                    tally.file_count = tally.file_count.saturating_add(1);
+                }
+                TreeNodeKind::OtherFs => tally.skipped_mounts.push(child_path),
+                TreeNodeKind::Other | TreeNodeKind::Excluded => {
+                    if child.read_error {
+                        tally.error_count = tally.error_count.saturating_add(1);
+                    }
+                }
+            }
+        }
+        tally.total_bytes = tally.total_bytes.saturating_add(dir_bytes);
+        if dir_bytes > 0
+            && let Some(bucket) = bucket_for_dir(path, root, max_depth)
+        {
+            let acc = tally.buckets.entry(bucket).or_insert(0);
+            *acc = acc.saturating_add(dir_bytes);
+        }
+        if dir_bytes > 0 {
+            tally.dirs.insert(path.to_path_buf(), dir_bytes);
         }
     }
 
-    let mut entries: Vec<TopDirEntry> = buckets
+    if tree.kind == TreeNodeKind::Dir {
+        visit(tree, root, root, max_depth, &exclude_set, &mut tally);
+    }
+
+    let mut entries: Vec<TopDirEntry> = tally
+        .buckets
         .into_iter()
         .map(|(path, bytes)| TopDirEntry { path, bytes })
         .collect();
-    entries.sort_by_key(|e| std::cmp::Reverse(e.bytes));
+    entries.sort_by(|a, b| b.bytes.cmp(&a.bytes).then_with(|| a.path.cmp(&b.path)));
     entries.truncate(top_n.max(1));
 
     Ok(TopDirsResult {
         root: root.to_path_buf(),
-        total_bytes,
-        file_count,
-        error_count,
+        total_bytes: tally.total_bytes,
+        file_count: tally.file_count,
+        error_count: tally.error_count,
+        method: SizeEstimateMethod::WalkDir,
+        cache_hits: 0,
+        cached_at: None,
         entries,
+        dir_bytes: tally.dirs,
+        skipped_mounts: tally.skipped_mounts,
     })
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum FileCategory {
+    Media,
+    Archive,
+    DiskImage,
+    Database,
+    BuildOutput,
+    Log,
+    Cache,
+    Other,
+}
+
+impl FileCategory {
+    pub const ALL: [FileCategory; 8] = [
+        FileCategory::Media,
+        FileCategory::Archive,
+        FileCategory::DiskImage,
+        FileCategory::Database,
+        FileCategory::BuildOutput,
+        FileCategory::Log,
+        FileCategory::Cache,
+        FileCategory::Other,
+    ];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            FileCategory::Media => "media",
+            FileCategory::Archive => "archive",
+            FileCategory::DiskImage => "disk_image",
+            FileCategory::Database => "database",
+            FileCategory::BuildOutput => "build_output",
+            FileCategory::Log => "log",
+            FileCategory::Cache => "cache",
+            FileCategory::Other => "other",
+        }
+    }
+}
+
+pub const AGE_BUCKET_LABELS: [&str; 4] = ["<7d", "<30d", "<180d", ">=180d"];
+const AGE_BUCKET_LIMIT_DAYS: [u64; 3] = [7, 30, 180];
+// 拡張子で判別できない小さなファイルは先頭を読まない（I/O に見合う容量にならないため）。
+const CLASSIFY_SNIFF_MIN_BYTES: u64 = 4096;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ClassBytes {
+    pub bytes: u64,
+    pub files: u64,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ClassifyResult {
+    pub categories: BTreeMap<FileCategory, ClassBytes>,
+    pub by_mtime: [ClassBytes; 4],
+    pub by_atime: [ClassBytes; 4],
+    pub file_count: u64,
+    pub error_count: u64,
+    pub truncated: bool,
+}
+
+pub fn classify_files(
+    roots: &[PathBuf],
+    excludes: &[String],
+    limits: WalkLimits,
+    now: std::time::SystemTime,
+) -> Result<ClassifyResult> {
+    use std::os::unix::fs::MetadataExt;
+
+    let exclude_set = build_exclude_set(excludes)?;
+    let mut result = ClassifyResult::default();
+    let mut seen_inodes: std::collections::HashSet<(u64, u64)> = std::collections::HashSet::new();
+
+    'roots: for root in roots {
+        let walker = WalkDir::new(root)
+            .follow_links(false)
+            .same_file_system(limits.one_file_system)
+            .into_iter()
+            .filter_entry(|e| !exclude_set.is_match(e.path()));
+        for entry in walker {
+            if limits.expired() {
+                result.truncated = true;
+                break 'roots;
+            }
+            let entry = match entry {
+                Ok(entry) => entry,
+                Err(_) => {
+                    result.error_count = result.error_count.saturating_add(1);
+                    continue;
+                }
+            };
+            if !entry.file_type().is_file() {
+                continue;
+            }
+            let Ok(meta) = entry.metadata() else {
+                result.error_count = result.error_count.saturating_add(1);
+                continue;
+            };
+            if meta.nlink() > 1 && !seen_inodes.insert((meta.dev(), meta.ino())) {
+                continue;
+            }
+            let path = entry.path();
+            let bytes = meta.len();
+            let category = category_from_extension(path)
+                .or_else(|| {
+                    if bytes < CLASSIFY_SNIFF_MIN_BYTES {
+                        return None;
+                    }
+                    read_head(path).and_then(|head| category_from_magic(&head))
+                })
+                .or_else(|| category_from_path(path))
+                .unwrap_or(FileCategory::Other);
+
+            result.file_count = result.file_count.saturating_add(1);
+            add_class_bytes(result.categories.entry(category).or_default(), bytes);
+            let age = |t: Option<std::time::SystemTime>| {
+                t.and_then(|t| now.duration_since(t).ok())
+                    .map_or(0, |d| d.as_secs() / 86_400)
+            };
+            add_class_bytes(
+                &mut result.by_mtime[age_bucket(age(meta.modified().ok()))],
+                bytes,
+            );
+            add_class_bytes(
+                &mut result.by_atime[age_bucket(age(meta.accessed().ok()))],
+                bytes,
+            );
+        }
+    }
+
+    if result.truncated {
+        result.error_count = result.error_count.max(1);
+    }
+    Ok(result)
+}
+
+fn add_class_bytes(acc: &mut ClassBytes, bytes: u64) {
+    acc.bytes = acc.bytes.saturating_add(bytes);
+    acc.files = acc.files.saturating_add(1);
+}
+
+fn age_bucket(age_days: u64) -> usize {
+    AGE_BUCKET_LIMIT_DAYS
+        .iter()
+        .position(|limit| age_days < *limit)
+        .unwrap_or(AGE_BUCKET_LIMIT_DAYS.len())
+}
+
+fn read_head(path: &Path) -> Option<Vec<u8>> {
+    use std::io::Read;
+
+    let mut head = Vec::with_capacity(16);
+    std::fs::File::open(path)
+        .ok()?
+        .take(16)
+        .read_to_end(&mut head)
+        .ok()?;
+    Some(head)
+}
+
+pub fn category_from_extension(path: &Path) -> Option<FileCategory> {
+    let ext = path.extension()?.to_str()?.to_ascii_lowercase();
+    let category = match ext.as_str() {
+        "jpg" | "jpeg" | "png" | "gif" | "heic" | "heif" | "tif" | "tiff" | "raw" | "cr2"
+        | "nef" | "arw" | "dng" | "webp" | "mp4" | "mov" | "m4v" | "avi" | "mkv" | "webm"
+        | "mp3" | "m4a" | "aac" | "wav" | "aiff" | "flac" | "caf" => FileCategory::Media,
+        "zip" | "tar" | "gz" | "tgz" | "bz2" | "xz" | "zst" | "7z" | "rar" | "xip" | "pkg"
+        | "jar" | "whl" | "gem" => FileCategory::Archive,
+        "dmg" | "iso" | "img" | "sparseimage" | "sparsebundle" | "qcow2" | "vmdk" | "vdi"
+        | "vhd" | "vhdx" | "ipsw" | "utm" | "pvm" => FileCategory::DiskImage,
+        "sqlite" | "sqlite3" | "db" | "db-wal" | "db-shm" | "realm" | "ldb" | "mdb" | "frm"
+        | "ibd" => FileCategory::Database,
+        "o" | "a" | "rlib" | "rmeta" | "d" | "class" | "pyc" | "dylib" | "so" | "pcm"
+        | "swiftmodule" | "dia" | "obj" | "pdb" | "wasm" => FileCategory::BuildOutput,
+        "log" | "crash" | "ips" | "diag" | "tracev3" => FileCategory::Log,
+        "cache" => FileCategory::Cache,
+        _ => return None,
+    };
+    Some(category)
+}
+
+pub fn category_from_magic(head: &[u8]) -> Option<FileCategory> {
+    const MAGICS: &[(&[u8], FileCategory)] = &[
+        (b"\x89PNG", FileCategory::Media),
+        (b"\xFF\xD8\xFF", FileCategory::Media),
+        (b"GIF8", FileCategory::Media),
+        (b"ID3", FileCategory::Media),
+        (b"fLaC", FileCategory::Media),
+        (b"PK\x03\x04", FileCategory::Archive),
+        (b"\x1F\x8B", FileCategory::Archive),
+        (b"BZh", FileCategory::Archive),
+        (b"\xFD7zXZ\x00", FileCategory::Archive),
+        (b"7z\xBC\xAF\x27\x1C", FileCategory::Archive),
+        (b"Rar!", FileCategory::Archive),
+        (b"\x28\xB5\x2F\xFD", FileCategory::Archive),
+        (b"xar!", FileCategory::Archive),
+        (b"QFI\xFB", FileCategory::DiskImage),
+        (b"KDMV", FileCategory::DiskImage),
+        (b"conectix", FileCategory::DiskImage),
+        (b"vhdxfile", FileCategory::DiskImage),
+        (b"SQLite format 3\x00", FileCategory::Database),
+        (b"\xCF\xFA\xED\xFE", FileCategory::BuildOutput),
+        (b"\xCE\xFA\xED\xFE", FileCategory::BuildOutput),
+        (b"!<arch>\n", FileCategory::BuildOutput),
+        (b"\x7FELF", FileCategory::BuildOutput),
+    ];
+    if let Some((_, category)) = MAGICS.iter().find(|(magic, _)| head.starts_with(magic)) {
+        return Some(*category);
+    }
+    // ISO BMFF（mp4/mov/heic）は 4 バイト目から `ftyp`。
+    if head.get(4..8) == Some(b"ftyp".as_slice()) {
+        return Some(FileCategory::Media);
+    }
+    None
+}
+
+pub fn category_from_path(path: &Path) -> Option<FileCategory> {
+    let mut category = None;
+    for component in path.components() {
+        let name = component.as_os_str().to_string_lossy();
+        let hint = match name.as_ref() {
+            "Caches" | ".cache" | "cache" | "Cache" | "CachedData" | "_cacache" => {
+                FileCategory::Cache
+            }
+            "DerivedData" | "target" | "build" | ".build" | "dist" | ".gradle" => {
+                FileCategory::BuildOutput
+            }
+            "Logs" | "logs" | "DiagnosticReports" => FileCategory::Log,
+            _ => continue,
+        };
+        // 最も深いヒントを優先する（例: ~/Library/Caches/foo/build は build_output）。
+        category = Some(hint);
+    }
+    category
+}
+
 pub fn validate_excludes(excludes: &[String]) -> Result<()> {
     let _ = build_exclude_set(excludes)?;
     Ok(())
@@ -255,9 +1495,8 @@ fn build_exclude_set(excludes: &[String]) -> Result<GlobSet> {
     Ok(builder.build()?)
 }
 
-fn bucket_dir(file_path: &Path, root: &Path, max_depth: usize) -> Option<PathBuf> {
-    let parent = file_path.parent()?;
-    let rel = parent.strip_prefix(root).ok()?;
+fn bucket_for_dir(dir: &Path, root: &Path, max_depth: usize) -> Option<PathBuf> {
+    let rel = dir.strip_prefix(root).ok()?;
 
     let mut bucket = root.to_path_buf();
     for component in rel.components().take(max_depth) {
@@ -287,8 +1526,14 @@ mod tests {
         std::fs::write(dir.join("file.bin"), b"hello").expect("write");
 
         let deadline = Instant::now() - Duration::from_secs(1);
-        let est =
-            estimate_dir_size(&dir, Duration::from_secs(5), Some(deadline)).expect("estimate");
+        let est = estimate_dir_size(
+            &dir,
+            Duration::from_secs(5),
+            Some(deadline),
+            None,
+            Sizer::Native,
+        )
+        .expect("estimate");
         assert_eq!(est.bytes, 0);
         assert!(est.error_count > 0);
         assert_eq!(est.method, SizeEstimateMethod::BudgetExhausted);
@@ -296,6 +1541,437 @@ mod tests {
         let _ = std::fs::remove_dir_all(&dir);
     }
 
+    #[test]
+    fn estimate_dir_size_native_counts_files_once_without_following_symlinks() {
+        static SEQ: AtomicU64 = AtomicU64::new(0);
+
+        let seq = SEQ.fetch_add(1, Ordering::Relaxed);
+        let dir =
+            std::env::temp_dir().join(format!("macdiet-native-test-{}-{seq}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let outside = dir.with_extension("outside");
+        let _ = std::fs::remove_dir_all(&outside);
+        std::fs::create_dir_all(dir.join("a/b/c")).expect("create dir");
+        std::fs::create_dir_all(&outside).expect("create outside");
+        std::fs::write(dir.join("top.bin"), [0u8; 100]).expect("write");
+        std::fs::write(dir.join("a/b/c/deep.bin"), [0u8; 5000]).expect("write");
+        std::fs::hard_link(dir.join("top.bin"), dir.join("a/top-link.bin")).expect("hard link");
+        std::fs::write(outside.join("big.bin"), [0u8; 9000]).expect("write");
+        std::os::unix::fs::symlink(&outside, dir.join("a/outside")).expect("symlink");
+
+        let est = estimate_dir_size(&dir, Duration::from_secs(5), None, None, Sizer::Native)
+            .expect("estimate");
+        assert_eq!(est.method, SizeEstimateMethod::Native);
+        assert_eq!(est.bytes, 5100);
+        assert_eq!(est.file_count, 2);
+        assert_eq!(est.error_count, 0);
+        assert!(est.allocated_bytes.is_some());
+
+        let missing = estimate_dir_size(
+            &dir.join("missing"),
+            Duration::from_secs(5),
+            None,
+            None,
+            Sizer::Native,
+        )
+        .expect("estimate");
+        assert_eq!(missing.bytes, 0);
+        assert_eq!(missing.error_count, 1);
+
+        let _ = std::fs::remove_dir_all(&dir);
+        let _ = std::fs::remove_dir_all(&outside);
+    }
+
+    #[test]
+    fn top_directories_buckets_in_parallel_and_respects_excludes_and_deadline() {
+        static SEQ: AtomicU64 = AtomicU64::new(0);
+
+        let seq = SEQ.fetch_add(1, Ordering::Relaxed);
+        let dir =
+            std::env::temp_dir().join(format!("macdiet-topdirs-test-{}-{seq}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        for (rel, len) in [
+            ("a/x/1.bin", 100usize),
+            ("a/x/deep/2.bin", 50),
+            ("a/y/3.bin", 10),
+            ("b/4.bin", 30),
+            ("b/node_modules/pkg/5.bin", 1000),
+            ("skip/6.bin", 1000),
+            ("7.bin", 5),
+        ] {
+            let path = dir.join(rel);
+            std::fs::create_dir_all(path.parent().expect("parent")).expect("create dir");
+            std::fs::write(&path, vec![0u8; len]).expect("write");
+        }
+
+        let calls = AtomicU64::new(0);
+        let last_bytes = AtomicU64::new(0);
+        let progress = |_files: u64, bytes: u64| {
+            calls.fetch_add(1, Ordering::Relaxed);
+            last_bytes.store(bytes, Ordering::Relaxed);
+        };
+        let result = top_directories(
+            &dir,
+            2,
+            10,
+            &["**/skip".to_string()],
+            WalkLimits::default(),
+            Some(&progress),
+            None,
+        )
+            .expect("top dirs");
+        assert_eq!(result.method, SizeEstimateMethod::WalkDir);
+        assert_eq!(result.file_count, 5);
+        assert_eq!(result.total_bytes, 195);
+        assert_eq!(last_bytes.load(Ordering::Relaxed), 195);
+        assert_eq!(result.error_count, 0);
+        let entries: Vec<(PathBuf, u64)> = result
+            .entries
+            .iter()
+            .map(|e| (e.path.clone(), e.bytes))
+            .collect();
+        assert_eq!(
+            entries,
+            vec![
+                (dir.join("a/x"), 150),
+                (dir.join("b"), 30),
+                (dir.join("a/y"), 10),
+                (dir.clone(), 5),
+            ]
+        );
+        assert!(calls.load(Ordering::Relaxed) >= 1);
+        let buckets: Vec<(PathBuf, u64)> = result
+            .buckets(1)
+            .into_iter()
+            .map(|e| (e.path, e.bytes))
+            .collect();
+        assert_eq!(
+            buckets,
+            vec![(dir.clone(), 5), (dir.join("a"), 160), (dir.join("b"), 30)]
+        );
+
+        let expired = Instant::now() - Duration::from_secs(1);
+        let result = top_directories(&dir, 2, 10, &[], WalkLimits::until(expired), None, None)
+            .expect("top dirs");
+        assert_eq!(result.method, SizeEstimateMethod::WalkDirTruncated);
+        assert!(result.error_count > 0);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn top_directories_propagates_worker_panic_instead_of_hanging() {
+        static SEQ: AtomicU64 = AtomicU64::new(0);
+
+        let seq = SEQ.fetch_add(1, Ordering::Relaxed);
+        let dir = std::env::temp_dir().join(format!(
+            "macdiet-topdirs-panic-test-{}-{seq}",
+            std::process::id()
+        ));
+        let _ = std::fs::remove_dir_all(&dir);
+        for sub in 0..4 {
+            let sub_dir = dir.join(format!("d{sub}"));
+            std::fs::create_dir_all(&sub_dir).expect("create dir");
+            for i in 0..TOP_DIRS_PROGRESS_EVERY {
+                std::fs::write(sub_dir.join(format!("{i}.bin")), b"x").expect("write");
+            }
+        }
+
+        let progress = |_files: u64, _bytes: u64| panic!("progress sink failed");
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            top_directories(
+                &dir,
+                2,
+                10,
+                &[],
+                WalkLimits::default(),
+                Some(&progress),
+                None,
+            )
+        }));
+        assert!(result.is_err());
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn build_tree_records_full_hierarchy_and_matches_top_directories() {
+        static SEQ: AtomicU64 = AtomicU64::new(0);
+
+        let seq = SEQ.fetch_add(1, Ordering::Relaxed);
+        let dir =
+            std::env::temp_dir().join(format!("macdiet-tree-test-{}-{seq}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        for (rel, len) in [
+            ("a/x/1.bin", 100usize),
+            ("a/x/deep/2.bin", 50),
+            ("a/y/3.bin", 10),
+            ("b/4.bin", 30),
+            ("skip/6.bin", 1000),
+            ("7.bin", 5),
+        ] {
+            let path = dir.join(rel);
+            std::fs::create_dir_all(path.parent().expect("parent")).expect("create dir");
+            std::fs::write(&path, vec![0u8; len]).expect("write");
+        }
+        std::os::unix::fs::symlink(dir.join("7.bin"), dir.join("link")).expect("symlink");
+
+        let excludes = vec!["**/skip".to_string()];
+        let built = build_tree(&dir, &excludes, WalkLimits::default()).expect("tree");
+        assert_eq!(built.method, SizeEstimateMethod::WalkDir);
+        assert_eq!(built.file_count, 5);
+        let names: Vec<(&str, TreeNodeKind)> = built
+            .tree
+            .children
+            .iter()
+            .map(|c| (c.name.as_str(), c.kind))
+            .collect();
+        assert_eq!(
+            names,
+            vec![
+                ("7.bin", TreeNodeKind::File),
+                ("a", TreeNodeKind::Dir),
+                ("b", TreeNodeKind::Dir),
+                ("link", TreeNodeKind::Other),
+                ("skip", TreeNodeKind::Excluded),
+            ]
+        );
+        let deep = built.tree.find(Path::new("a/x/deep")).expect("deep");
+        assert_eq!(deep.children[0].bytes, 50);
+
+        let from_tree = top_directories_from_tree(&built.tree, &dir, 2, 10, &excludes)
+            .expect("top dirs from tree");
+        let walked = top_directories(&dir, 2, 10, &excludes, WalkLimits::default(), None, None)
+            .expect("top dirs");
+        assert_eq!(from_tree.total_bytes, walked.total_bytes);
+        assert_eq!(from_tree.file_count, walked.file_count);
+        let pairs = |r: &TopDirsResult| -> Vec<(PathBuf, u64)> {
+            r.entries
+                .iter()
+                .map(|e| (e.path.clone(), e.bytes))
+                .collect()
+        };
+        assert_eq!(pairs(&from_tree), pairs(&walked));
+        assert_eq!(pairs(&from_tree)[0], (dir.join("a/x"), 150));
+
+        let mut base = TreeNode {
+            name: "/".to_string(),
+            ..TreeNode::default()
+        };
+        base.graft(Path::new("tmp/data"), built.tree.clone());
+        let grafted = base.find(Path::new("tmp/data")).expect("grafted");
+        assert_eq!(grafted.name, "data");
+        assert_eq!(grafted.children, built.tree.children);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn estimate_dir_size_reuses_cached_subtrees_until_directory_changes() {
+        static SEQ: AtomicU64 = AtomicU64::new(0);
+
+        let seq = SEQ.fetch_add(1, Ordering::Relaxed);
+        let home = std::env::temp_dir().join(format!(
+            "macdiet-size-cache-test-{}-{seq}",
+            std::process::id()
+        ));
+        let _ = std::fs::remove_dir_all(&home);
+        let dir = home.join("data");
+        std::fs::create_dir_all(dir.join("a/b")).expect("create dir");
+        std::fs::write(dir.join("a/1.bin"), [0u8; 10]).expect("write");
+        std::fs::write(dir.join("a/b/2.bin"), [0u8; 20]).expect("write");
+        std::fs::hard_link(dir.join("a/1.bin"), dir.join("a/b/1-link.bin")).expect("hard link");
+
+        let cache = SizeCache::load(&home);
+        let first = estimate_dir_size(
+            &dir,
+            Duration::from_secs(5),
+            None,
+            Some(&cache),
+            Sizer::Native,
+        )
+        .expect("estimate");
+        assert_eq!(first.bytes, 30);
+        assert_eq!(first.file_count, 2);
+        assert_eq!(first.cache_hits, 0);
+        assert!(first.cache_note().is_none());
+        cache.save().expect("save cache");
+
+        let cache = SizeCache::load(&home);
+        let second = estimate_dir_size(
+            &dir,
+            Duration::from_secs(5),
+            None,
+            Some(&cache),
+            Sizer::Native,
+        )
+        .expect("estimate");
+        assert_eq!(second.bytes, 30);
+        assert_eq!(second.file_count, 2);
+        assert_eq!(second.method, SizeEstimateMethod::Native);
+        assert_eq!(second.allocated_bytes, first.allocated_bytes);
+        assert_eq!(second.cache_hits, 3);
+        assert!(second.cache_note().is_some());
+
+        std::fs::write(dir.join("a/b/3.bin"), [0u8; 5]).expect("write");
+        let third = estimate_dir_size(
+            &dir,
+            Duration::from_secs(5),
+            None,
+            Some(&cache),
+            Sizer::Native,
+        )
+        .expect("estimate");
+        assert_eq!(third.bytes, 35);
+        assert_eq!(third.cache_hits, 2);
+
+        std::fs::remove_file(dir.join("a/b/1-link.bin")).expect("remove link");
+        let top = top_directories(&dir, 1, 10, &[], WalkLimits::default(), None, Some(&cache))
+            .expect("top dirs");
+        assert_eq!(top.total_bytes, 35);
+        assert_eq!(top.cache_hits, 0);
+        let top = top_directories(&dir, 1, 10, &[], WalkLimits::default(), None, Some(&cache))
+            .expect("top dirs");
+        assert_eq!(top.total_bytes, 35);
+        assert_eq!(top.cache_hits, 3);
+
+        let _ = std::fs::remove_dir_all(&home);
+    }
+
+    #[test]
+    fn find_duplicates_confirms_full_content_and_skips_hardlinks() {
+        static SEQ: AtomicU64 = AtomicU64::new(0);
+
+        let seq = SEQ.fetch_add(1, Ordering::Relaxed);
+        let dir = std::env::temp_dir().join(format!(
+            "macdiet-duplicates-test-{}-{seq}",
+            std::process::id()
+        ));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(dir.join("a")).expect("create dir");
+        std::fs::create_dir_all(dir.join("b")).expect("create dir");
+        std::fs::create_dir_all(dir.join("skip")).expect("create dir");
+
+        let same = vec![7u8; 200 * 1024];
+        let mut tail_differs = same.clone();
+        *tail_differs.last_mut().expect("non-empty") = 8;
+        std::fs::write(dir.join("a/data.bin"), &same).expect("write");
+        std::fs::write(dir.join("b/data.bin"), &same).expect("write");
+        std::fs::write(dir.join("b/other.bin"), &tail_differs).expect("write");
+        std::fs::write(dir.join("skip/data.bin"), &same).expect("write");
+        std::fs::hard_link(dir.join("a/data.bin"), dir.join("a/link.bin")).expect("hard link");
+
+        let result = find_duplicates(
+            std::slice::from_ref(&dir),
+            1024,
+            &["**/skip/**".to_string()],
+            WalkLimits::default(),
+        )
+        .expect("find duplicates");
+        assert!(!result.truncated);
+        assert_eq!(result.sets.len(), 1, "sets={:?}", result.sets);
+        let set = &result.sets[0];
+        assert_eq!(set.file_bytes, same.len() as u64);
+        assert_eq!(set.paths.len(), 2, "paths={:?}", set.paths);
+        assert!(set.paths.contains(&dir.join("b/data.bin")));
+        assert_eq!(set.wasted_bytes(), same.len() as u64);
+
+        let expired = Instant::now() - Duration::from_secs(1);
+        let result = find_duplicates(
+            std::slice::from_ref(&dir),
+            1024,
+            &[],
+            WalkLimits::until(expired),
+        )
+        .expect("find duplicates");
+        assert!(result.truncated);
+        assert!(result.sets.is_empty());
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn classify_files_groups_by_category_and_age() {
+        static SEQ: AtomicU64 = AtomicU64::new(0);
+
+        let seq = SEQ.fetch_add(1, Ordering::Relaxed);
+        let dir = std::env::temp_dir().join(format!(
+            "macdiet-classify-test-{}-{seq}",
+            std::process::id()
+        ));
+        let _ = std::fs::remove_dir_all(&dir);
+        let mut png = b"\x89PNG\r\n\x1a\n".to_vec();
+        png.resize(5000, 0);
+        let mut sqlite = b"SQLite format 3\x00".to_vec();
+        sqlite.resize(8192, 0);
+        for (rel, bytes) in [
+            ("clip.MP4", vec![0u8; 100]),
+            ("photo", png),
+            ("store", sqlite),
+            ("Library/Caches/app/blob", vec![0u8; 300]),
+            ("notes.txt", vec![0u8; 7]),
+            ("skip/big.dmg", vec![0u8; 1000]),
+        ] {
+            let path = dir.join(rel);
+            std::fs::create_dir_all(path.parent().expect("parent")).expect("create dir");
+            std::fs::write(&path, bytes).expect("write");
+        }
+        let now = std::time::SystemTime::now();
+        let old = now - Duration::from_secs(200 * 86_400);
+        std::fs::File::options()
+            .write(true)
+            .open(dir.join("clip.MP4"))
+            .expect("open")
+            .set_times(
+                std::fs::FileTimes::new()
+                    .set_modified(old)
+                    .set_accessed(now - Duration::from_secs(10 * 86_400)),
+            )
+            .expect("set times");
+
+        let result = classify_files(
+            std::slice::from_ref(&dir),
+            &["**/skip".to_string()],
+            WalkLimits::default(),
+            now,
+        )
+        .expect("classify");
+        assert_eq!(result.file_count, 5);
+        let bytes = |c: FileCategory| result.categories.get(&c).map_or(0, |v| v.bytes);
+        assert_eq!(bytes(FileCategory::Media), 5100);
+        assert_eq!(bytes(FileCategory::Database), 8192);
+        assert_eq!(bytes(FileCategory::Cache), 300);
+        assert_eq!(bytes(FileCategory::Other), 7);
+        assert_eq!(bytes(FileCategory::DiskImage), 0);
+        assert_eq!(result.by_mtime[3].bytes, 100);
+        assert_eq!(result.by_mtime[0].bytes, 13_499);
+        assert_eq!(result.by_atime[1].bytes, 100);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn rank_growth_orders_by_growth_and_ignores_shrinking_dirs() {
+        let entry = |path: &str, bytes: u64| TopDirEntry {
+            path: PathBuf::from(path),
+            bytes,
+        };
+        let start = vec![entry("/h/a", 100), entry("/h/b", 500), entry("/h/c", 10)];
+        let end = vec![
+            entry("/h/a", 1100),
+            entry("/h/b", 400),
+            entry("/h/c", 10),
+            entry("/h/new", 300),
+        ];
+        let ranked = rank_growth(&start, &end, 10);
+        let got: Vec<(&str, u64)> = ranked
+            .iter()
+            .map(|e| (e.path.to_str().unwrap_or(""), e.growth_bytes()))
+            .collect();
+        assert_eq!(got, vec![("/h/a", 1000), ("/h/new", 300)]);
+        assert_eq!(rank_growth(&start, &end, 1).len(), 1);
+    }
+
     #[test]
     fn confidence_is_method_aware() {
         let du = SizeEstimate {
@@ -303,6 +1979,9 @@ mod tests {
             file_count: 0,
             error_count: 0,
             method: SizeEstimateMethod::Du,
+            cache_hits: 0,
+            cached_at: None,
+            allocated_bytes: None,
         };
         assert!((du.confidence() - 0.8).abs() < 1e-9);
 
@@ -311,6 +1990,9 @@ mod tests {
             file_count: 1,
             error_count: 0,
             method: SizeEstimateMethod::WalkDir,
+            cache_hits: 0,
+            cached_at: None,
+            allocated_bytes: None,
         };
         assert!((full.confidence() - 0.9).abs() < 1e-9);
 
@@ -319,7 +2001,16 @@ mod tests {
             file_count: 1,
             error_count: 1,
             method: SizeEstimateMethod::WalkDirTruncated,
+            cache_hits: 0,
+            cached_at: None,
+            allocated_bytes: None,
         };
         assert!((truncated.confidence() - 0.3).abs() < 1e-9);
+
+        let native_partial = SizeEstimate {
+            method: SizeEstimateMethod::NativeTruncated,
+            ..truncated
+        };
+        assert!((native_partial.confidence() - 0.3).abs() < 1e-9);
     }
 }
@@ -0,0 +1,353 @@
+use serde_json::{Map, Value};
+
+use crate::core::{REPORT_SCHEMA_VERSION, Report};
+
+// 読み込める Report の schema_version（古い順）。最後が現行版。
+pub const SUPPORTED_REPORT_VERSIONS: &[&str] = &["1.0", "1.1", REPORT_SCHEMA_VERSION];
+
+pub fn report_schema() -> Value {
+    let mut schema = schemars::schema_for!(Report);
+    let meta = schema.schema.metadata();
+    meta.title = Some(format!(
+        "macdiet Report (schema_version {REPORT_SCHEMA_VERSION})"
+    ));
+    serde_json::to_value(schema).unwrap_or(Value::Null)
+}
+
+pub fn log_schema(kind: &str) -> Option<Value> {
+    let mut schema = crate::logs::log_schema(kind)?;
+    schema.schema.metadata().title = Some(format!("macdiet {kind} log"));
+    serde_json::to_value(schema).ok()
+}
+
+#[derive(Debug)]
+pub enum MigrateError {
+    MissingVersion,
+    Unsupported(String),
+    Invalid(serde_json::Error),
+}
+
+impl std::fmt::Display for MigrateError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MigrateError::MissingVersion => write!(f, "schema_version がありません"),
+            MigrateError::Unsupported(version) => write!(
+                f,
+                "未対応の schema_version です: {version}（対応: {}）",
+                SUPPORTED_REPORT_VERSIONS.join(", ")
+            ),
+            MigrateError::Invalid(err) => write!(f, "{err}"),
+        }
+    }
+}
+
+impl std::error::Error for MigrateError {}
+
+// 古い schema_version の Report を現行版へ順に引き上げてから読み込む。
+pub fn migrate_report(mut value: Value) -> Result<Report, MigrateError> {
+    let version = value
+        .get("schema_version")
+        .and_then(Value::as_str)
+        .ok_or(MigrateError::MissingVersion)?
+        .to_string();
+    let Some(start) = SUPPORTED_REPORT_VERSIONS.iter().position(|v| *v == version) else {
+        return Err(MigrateError::Unsupported(version));
+    };
+    for from in &SUPPORTED_REPORT_VERSIONS[start..SUPPORTED_REPORT_VERSIONS.len() - 1] {
+        match *from {
+            // 1.0 -> 1.1（classification / volumes）、1.1 -> 1.2（host）はどちらも省略可能な
+            // 追加フィールドなので版だけ上げる。
+            "1.0" | "1.1" => {}
+            _ => unreachable!("migration for {from} is missing"),
+        }
+    }
+    if let Some(obj) = value.as_object_mut() {
+        obj.insert(
+            "schema_version".to_string(),
+            Value::from(REPORT_SCHEMA_VERSION),
+        );
+    }
+    serde_json::from_value(value).map_err(MigrateError::Invalid)
+}
+
+// schemars が出力する draft-07 のうち、macdiet のスキーマで使うキーワードだけを検証する。
+// 戻り値は `<JSON Pointer>: <理由>` の一覧（空なら適合）。
+pub fn validate(schema: &Value, value: &Value) -> Vec<String> {
+    let mut errors = Vec::new();
+    validate_at(schema, schema, value, "", &mut errors);
+    errors
+}
+
+fn validate_at(root: &Value, schema: &Value, value: &Value, path: &str, errors: &mut Vec<String>) {
+    let schema = match schema {
+        Value::Bool(true) => return,
+        Value::Bool(false) => {
+            errors.push(format!("{}: 値は許可されていません", pointer(path)));
+            return;
+        }
+        Value::Object(schema) => schema,
+        _ => return,
+    };
+
+    if let Some(reference) = schema.get("$ref").and_then(Value::as_str) {
+        match resolve_ref(root, reference) {
+            Some(target) => validate_at(root, target, value, path, errors),
+            None => errors.push(format!(
+                "{}: 参照を解決できません: {reference}",
+                pointer(path)
+            )),
+        }
+        return;
+    }
+
+    if let Some(types) = schema.get("type")
+        && !type_matches(types, value)
+    {
+        errors.push(format!(
+            "{}: 型が一致しません（期待: {}、実際: {}）",
+            pointer(path),
+            type_label(types),
+            value_type(value)
+        ));
+        return;
+    }
+
+    if let Some(allowed) = schema.get("enum").and_then(Value::as_array)
+        && !allowed.contains(value)
+    {
+        errors.push(format!(
+            "{}: 許可されていない値です: {value}",
+            pointer(path)
+        ));
+    }
+    if let Some(expected) = schema.get("const")
+        && expected != value
+    {
+        errors.push(format!(
+            "{}: 値が一致しません（期待: {expected}）",
+            pointer(path)
+        ));
+    }
+    if let (Some(min), Some(n)) = (
+        schema.get("minimum").and_then(Value::as_f64),
+        value.as_f64(),
+    ) && n < min
+    {
+        errors.push(format!("{}: {n} は最小値 {min} 未満です", pointer(path)));
+    }
+
+    if let Some(subs) = schema.get("allOf").and_then(Value::as_array) {
+        for sub in subs {
+            validate_at(root, sub, value, path, errors);
+        }
+    }
+    for (keyword, exactly_one) in [("anyOf", false), ("oneOf", true)] {
+        let Some(subs) = schema.get(keyword).and_then(Value::as_array) else {
+            continue;
+        };
+        let matched = subs
+            .iter()
+            .filter(|sub| {
+                let mut sub_errors = Vec::new();
+                validate_at(root, sub, value, path, &mut sub_errors);
+                sub_errors.is_empty()
+            })
+            .count();
+        if matched == 0 || (exactly_one && matched > 1) {
+            errors.push(format!(
+                "{}: {keyword} の候補に{}一致しません",
+                pointer(path),
+                if matched == 0 { "" } else { "1 つだけ" }
+            ));
+        }
+    }
+
+    if let Value::Object(obj) = value {
+        validate_object(root, schema, obj, path, errors);
+    }
+    if let (Some(items), Value::Array(values)) = (schema.get("items"), value) {
+        for (i, item) in values.iter().enumerate() {
+            validate_at(root, items, item, &format!("{path}/{i}"), errors);
+        }
+    }
+}
+
+fn validate_object(
+    root: &Value,
+    schema: &Map<String, Value>,
+    obj: &Map<String, Value>,
+    path: &str,
+    errors: &mut Vec<String>,
+) {
+    if let Some(required) = schema.get("required").and_then(Value::as_array) {
+        for key in required.iter().filter_map(Value::as_str) {
+            if !obj.contains_key(key) {
+                errors.push(format!(
+                    "{}: 必須フィールド {key} がありません",
+                    pointer(path)
+                ));
+            }
+        }
+    }
+    let properties = schema.get("properties").and_then(Value::as_object);
+    for (key, child) in obj {
+        let child_path = format!("{path}/{}", key.replace('~', "~0").replace('/', "~1"));
+        match properties.and_then(|p| p.get(key)) {
+            Some(sub) => validate_at(root, sub, child, &child_path, errors),
+            None => {
+                if let Some(additional) = schema.get("additionalProperties") {
+                    validate_at(root, additional, child, &child_path, errors);
+                }
+            }
+        }
+    }
+}
+
+fn resolve_ref<'a>(root: &'a Value, reference: &str) -> Option<&'a Value> {
+    root.pointer(reference.strip_prefix('#')?)
+}
+
+fn type_matches(types: &Value, value: &Value) -> bool {
+    match types {
+        Value::String(t) => type_is(t, value),
+        Value::Array(ts) => ts
+            .iter()
+            .filter_map(Value::as_str)
+            .any(|t| type_is(t, value)),
+        _ => true,
+    }
+}
+
+fn type_is(t: &str, value: &Value) -> bool {
+    match t {
+        "null" => value.is_null(),
+        "boolean" => value.is_boolean(),
+        "object" => value.is_object(),
+        "array" => value.is_array(),
+        "string" => value.is_string(),
+        "number" => value.is_number(),
+        "integer" => value.is_i64() || value.is_u64(),
+        _ => true,
+    }
+}
+
+fn type_label(types: &Value) -> String {
+    match types {
+        Value::String(t) => t.clone(),
+        Value::Array(ts) => ts
+            .iter()
+            .filter_map(Value::as_str)
+            .collect::<Vec<_>>()
+            .join(" | "),
+        other => other.to_string(),
+    }
+}
+
+fn value_type(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(n) if n.is_f64() => "number",
+        Value::Number(_) => "integer",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+fn pointer(path: &str) -> &str {
+    if path.is_empty() { "/" } else { path }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn minimal_report(version: &str) -> Value {
+        json!({
+            "schema_version": version,
+            "tool_version": "0.1.0",
+            "os": {"name": "macOS", "version": "14.0"},
+            "generated_at": "2026-01-02T03:04:05Z",
+            "summary": {"estimated_total_bytes": 5, "unobserved_bytes": 0, "notes": []},
+            "findings": [{
+                "id": "npm-cache",
+                "type": "NODE_NPM_CACHE_LARGE",
+                "title": "npm cache",
+                "estimated_bytes": 5,
+                "confidence": 0.8,
+                "risk_level": "R1",
+                "evidence": [{"kind": "path", "value": "~/.npm", "masked": true}],
+                "recommended_actions": [{"id": "npm-cache-trash"}]
+            }],
+            "actions": [{
+                "id": "npm-cache-trash",
+                "title": "npm cache をゴミ箱へ移動",
+                "risk_level": "R1",
+                "estimated_reclaimed_bytes": 5,
+                "related_findings": ["npm-cache"],
+                "kind": {"kind": "TRASH_MOVE", "paths": ["~/.npm"]},
+                "notes": []
+            }]
+        })
+    }
+
+    #[test]
+    fn report_schema_accepts_reports_and_reports_pointer_errors() {
+        let schema = report_schema();
+        assert_eq!(
+            validate(&schema, &minimal_report("1.0")),
+            Vec::<String>::new()
+        );
+
+        let mut broken = minimal_report(REPORT_SCHEMA_VERSION);
+        broken["findings"][0]["risk_level"] = json!("R9");
+        broken["findings"][0]["estimated_bytes"] = json!(-1);
+        broken["actions"][0]["kind"]["kind"] = json!("FORMAT_DISK");
+        broken["summary"]
+            .as_object_mut()
+            .expect("summary")
+            .remove("notes");
+        let errors = validate(&schema, &broken);
+        let pointers: Vec<&str> = errors
+            .iter()
+            .map(|e| e.split(':').next().unwrap_or_default())
+            .collect();
+        assert!(pointers.contains(&"/findings/0/risk_level"), "{errors:?}");
+        assert!(
+            pointers.contains(&"/findings/0/estimated_bytes"),
+            "{errors:?}"
+        );
+        assert!(pointers.contains(&"/actions/0/kind"), "{errors:?}");
+        assert!(pointers.contains(&"/summary"), "{errors:?}");
+
+        for kind in crate::logs::LOG_KINDS {
+            assert!(log_schema(kind).is_some(), "{kind}");
+        }
+        assert!(log_schema("unknown").is_none());
+    }
+
+    #[test]
+    fn migrate_report_upgrades_old_versions_and_rejects_unknown() {
+        let report = migrate_report(minimal_report("1.0")).expect("migrate 1.0");
+        assert_eq!(report.schema_version, REPORT_SCHEMA_VERSION);
+        assert!(report.classification.is_none());
+        assert_eq!(report.findings[0].id, "npm-cache");
+
+        assert!(matches!(
+            migrate_report(minimal_report("2.0")),
+            Err(MigrateError::Unsupported(v)) if v == "2.0"
+        ));
+        assert!(matches!(
+            migrate_report(json!({})),
+            Err(MigrateError::MissingVersion)
+        ));
+        let mut broken = minimal_report("1.0");
+        broken["findings"] = json!("oops");
+        assert!(matches!(
+            migrate_report(broken),
+            Err(MigrateError::Invalid(_))
+        ));
+    }
+}
@@ -82,6 +82,8 @@ enum Screen {
     SnapshotsDeleteResult,
     LogsList,
     LogsDetail,
+    LogsRestoreConfirm,
+    LogsRestoreResult,
     ReportView,
     Utilities,
     CleanupView,
@@ -141,6 +143,8 @@ struct CommandItem {
 struct PendingRun {
     kind: CommandKind,
     rx: mpsc::Receiver<Result<Report>>,
+    events: mpsc::Receiver<crate::events::Event>,
+    progress: crate::events::Progress,
     started_at: Instant,
 }
 
@@ -320,9 +324,23 @@ struct LogDetail {
     parse_error: Option<String>,
 }
 
+struct LogsRestoreConfirm {
+    log_path: PathBuf,
+    moved: Vec<crate::actions::TrashMoveRecord>,
+    input: String,
+    error: Option<String>,
+}
+
+struct LogsRestoreResult {
+    outcome: crate::actions::RestoreOutcome,
+    log_path: Option<PathBuf>,
+    log_error: Option<String>,
+}
+
 struct App {
     color: bool,
     home_dir: PathBuf,
+    redactor: crate::privacy::Redactor,
     dry_run: bool,
     scan_default_scope: String,
     scan_exclude: Vec<String>,
@@ -347,6 +365,8 @@ struct App {
     logs_state: ListState,
     logs_view: Option<LogDetail>,
     logs_scroll: u16,
+    logs_restore_confirm: Option<LogsRestoreConfirm>,
+    logs_restore_result: Option<LogsRestoreResult>,
     utilities_actions: Vec<crate::core::ActionPlan>,
     utilities_state: ListState,
     cleanup_kind: CleanupKind,
@@ -486,14 +506,25 @@ impl App {
         let default_scan_request = ScanRequest {
             scope: default_scope,
             deep: true,
+            duplicates: false,
+            classify: false,
+            one_file_system: None,
+            volumes: vec![],
             max_depth: 3,
             top_dirs: 20,
             exclude: scan_exclude.clone(),
+            export_ncdu: None,
+            import_ncdu: None,
             show_progress: false,
         };
 
         Self {
             color,
+            redactor: crate::privacy::Redactor::new(
+                crate::privacy::Redaction::Home,
+                &home_dir,
+                [0; 32],
+            ),
             home_dir,
             dry_run,
             scan_default_scope,
@@ -519,6 +550,8 @@ impl App {
             logs_state,
             logs_view: None,
             logs_scroll: 0,
+            logs_restore_confirm: None,
+            logs_restore_result: None,
             utilities_actions,
             utilities_state,
             cleanup_kind: CleanupKind::XcodeArchives,
@@ -647,6 +680,7 @@ fn run_app(
         scan_default_scope,
         scan_exclude,
     );
+    app.redactor = engine.redactor().clone();
 
     let tick_rate = Duration::from_millis(200);
     let mut last_tick = Instant::now();
@@ -654,7 +688,10 @@ fn run_app(
     loop {
         terminal.draw(|f| draw(f, &mut app)).context("画面描画")?;
 
-        if let Some(pending) = app.pending.take() {
+        if let Some(mut pending) = app.pending.take() {
+            while let Ok(event) = pending.events.try_recv() {
+                pending.progress.apply(&event);
+            }
             match pending.rx.try_recv() {
                 Ok(res) => match res {
                     Ok(report) => {
@@ -982,6 +1019,8 @@ fn open_error_return_to(app: &mut App, msg: impl Into<String>, return_to: Screen
         | Screen::SnapshotsDeleteResult
         | Screen::LogsList
         | Screen::LogsDetail
+        | Screen::LogsRestoreConfirm
+        | Screen::LogsRestoreResult
         | Screen::ReportView
         | Screen::Utilities
         | Screen::CleanupView
@@ -1331,12 +1370,48 @@ fn handle_key(app: &mut App, engine: &Engine, key: KeyEvent) -> Result<bool> {
                 app.screen = Screen::LogsList;
             }
             KeyCode::Char('r') => reload_log_detail(app),
+            KeyCode::Char('u') => open_logs_restore_confirm(app),
             KeyCode::Up | KeyCode::Char('k') => app.logs_scroll = app.logs_scroll.saturating_sub(1),
             KeyCode::Down | KeyCode::Char('j') => {
                 app.logs_scroll = app.logs_scroll.saturating_add(1)
             }
             _ => {}
         },
+        Screen::LogsRestoreConfirm => match key.code {
+            KeyCode::Char('q') => return Ok(true),
+            KeyCode::Char('?') => open_help(app),
+            KeyCode::Esc => {
+                app.logs_restore_confirm = None;
+                app.screen = Screen::LogsDetail;
+            }
+            KeyCode::Backspace => {
+                if let Some(confirm) = app.logs_restore_confirm.as_mut() {
+                    confirm.input.pop();
+                }
+            }
+            KeyCode::Enter => submit_logs_restore_confirm(app),
+            KeyCode::Char(c) => {
+                if key.modifiers.contains(KeyModifiers::CONTROL)
+                    || key.modifiers.contains(KeyModifiers::ALT)
+                {
+                    return Ok(false);
+                }
+                if let Some(confirm) = app.logs_restore_confirm.as_mut() {
+                    confirm.input.push(c);
+                }
+            }
+            _ => {}
+        },
+        Screen::LogsRestoreResult => match key.code {
+            KeyCode::Char('q') => return Ok(true),
+            KeyCode::Char('?') => open_help(app),
+            KeyCode::Char('b') | KeyCode::Esc => {
+                app.logs_restore_result = None;
+                refresh_logs(app);
+                app.screen = Screen::LogsList;
+            }
+            _ => {}
+        },
         Screen::Running => match key.code {
             KeyCode::Char('q') => return Ok(true),
             KeyCode::Esc => {
@@ -1670,6 +1745,10 @@ fn handle_key(app: &mut App, engine: &Engine, key: KeyEvent) -> Result<bool> {
 
 fn start_run(app: &mut App, engine: Engine, kind: CommandKind) {
     let (tx, rx) = mpsc::channel::<Result<Report>>();
+    let (etx, erx) = mpsc::channel::<crate::events::Event>();
+    let engine = engine.with_events(crate::events::EventSink::new(move |event| {
+        let _ = etx.send(event.clone());
+    }));
     let scan_req = if kind == CommandKind::ScanDeep {
         Some(
             app.last_scan_request
@@ -1703,6 +1782,8 @@ fn start_run(app: &mut App, engine: Engine, kind: CommandKind) {
     app.pending = Some(PendingRun {
         kind,
         rx,
+        events: erx,
+        progress: crate::events::Progress::default(),
         started_at: Instant::now(),
     });
     app.screen = Screen::Running;
@@ -2051,7 +2132,7 @@ fn start_fix_run_cmd_confirm(app: &mut App) -> Result<()> {
     if actions.is_empty() {
         open_error_return_to(
             app,
-            "実行可能な RUN_CMD アクションが選択されていません。\nヒント: 許可リストの RUN_CMD（例: `homebrew-cache-cleanup` / `npm-cache-cleanup` / `yarn-cache-cleanup` / `pnpm-store-prune` / `docker-storage-df` / `docker-builder-prune` / `docker-system-prune` / `coresimulator-simctl-delete-unavailable`）を選択して x を押してください。R2 が表示されない場合は 2 を押してください。"
+            "実行可能な RUN_CMD アクションが選択されていません。\nヒント: 許可リストの RUN_CMD（例: `homebrew-cache-cleanup` / `homebrew-autoremove` / `lang-version-uninstall:*` / `npm-cache-cleanup` / `yarn-cache-cleanup` / `pnpm-store-prune` / `docker-storage-df` / `docker-builder-prune` / `docker-system-prune` / `coresimulator-simctl-delete-unavailable`）を選択して x を押してください。R2 が表示されない場合は 2 を押してください。"
                 .to_string(),
             Screen::FixView,
         );
@@ -2500,7 +2581,7 @@ fn build_cleanup_actions(
 }
 
 fn estimate_candidate_bytes(path: &std::path::Path, per_item: Duration, deadline: Option<Instant>) -> u64 {
-    crate::scan::estimate_dir_size(path, per_item, deadline)
+    crate::scan::estimate_dir_size(path, per_item, deadline, None, crate::scan::Sizer::Native)
         .map(|e| e.bytes)
         .unwrap_or(0)
 }
@@ -2572,6 +2653,7 @@ fn start_fix_apply(
     return_to: Screen,
 ) {
     let home_dir = app.home_dir.clone();
+    let redactor = app.redactor.clone();
     let (tx, rx) = mpsc::channel::<Result<FixApplyResult>>();
     thread::spawn(move || {
         let res = (|| -> Result<FixApplyResult> {
@@ -2580,6 +2662,7 @@ fn start_fix_apply(
             let finished_at = OffsetDateTime::now_utc();
             let log_path = crate::logs::write_fix_apply_log(
                 &home_dir,
+                &redactor,
                 started_at,
                 finished_at,
                 max_risk,
@@ -2618,6 +2701,7 @@ fn start_fix_run_cmd(
     return_to: Screen,
 ) {
     let home_dir = app.home_dir.clone();
+    let redactor = app.redactor.clone();
     let (tx, rx) = mpsc::channel::<Result<FixRunCmdResult>>();
     thread::spawn(move || {
         let res = (|| -> Result<FixRunCmdResult> {
@@ -2634,6 +2718,7 @@ fn start_fix_run_cmd(
 
                 let log_path = crate::logs::write_fix_run_cmd_log(
                     &home_dir,
+                    &redactor,
                     started_at,
                     finished_at,
                     &action,
@@ -2846,6 +2931,7 @@ fn start_snapshots_thin_run(
     return_to: Screen,
 ) {
     let home_dir = app.home_dir.clone();
+    let redactor = app.redactor.clone();
     let (tx, rx) = mpsc::channel::<Result<SnapshotsThinResult>>();
     thread::spawn(move || {
         let res = (|| -> Result<SnapshotsThinResult> {
@@ -2854,6 +2940,7 @@ fn start_snapshots_thin_run(
             #[cfg(not(target_os = "macos"))]
             {
                 let _ = timeout;
+                let _ = &redactor;
                 return Ok(SnapshotsThinResult {
                     bytes,
                     urgency,
@@ -2890,6 +2977,7 @@ fn start_snapshots_thin_run(
 
                 let log_path = crate::logs::write_snapshots_thin_log(
                     &home_dir,
+                    &redactor,
                     started_at,
                     finished_at,
                     bytes,
@@ -3055,6 +3143,7 @@ fn submit_snapshots_delete_confirm(app: &mut App, timeout: Duration) -> Result<(
 
 fn start_snapshots_delete_run(app: &mut App, timeout: Duration, uuid: String, return_to: Screen) {
     let home_dir = app.home_dir.clone();
+    let redactor = app.redactor.clone();
     let (tx, rx) = mpsc::channel::<Result<SnapshotsDeleteResult>>();
     thread::spawn(move || {
         let res = (|| -> Result<SnapshotsDeleteResult> {
@@ -3064,6 +3153,7 @@ fn start_snapshots_delete_run(app: &mut App, timeout: Duration, uuid: String, re
             #[cfg(not(target_os = "macos"))]
             {
                 let _ = timeout;
+                let _ = &redactor;
                 return Ok(SnapshotsDeleteResult {
                     requested_id: uuid.clone(),
                     resolved_uuid: Some(uuid),
@@ -3176,6 +3266,7 @@ fn start_snapshots_delete_run(app: &mut App, timeout: Duration, uuid: String, re
                 };
                 let log_path = crate::logs::write_snapshots_delete_log(
                     &home_dir,
+                    &redactor,
                     started_at,
                     finished_at,
                     &uuid,
@@ -3248,6 +3339,8 @@ fn draw(f: &mut ratatui::Frame, app: &mut App) {
         Screen::SnapshotsDeleteResult => draw_snapshots_delete_result(f, chunks[1], app),
         Screen::LogsList => draw_logs_list(f, chunks[1], app),
         Screen::LogsDetail => draw_logs_detail(f, chunks[1], app),
+        Screen::LogsRestoreConfirm => draw_logs_restore_confirm(f, chunks[1], app),
+        Screen::LogsRestoreResult => draw_logs_restore_result(f, chunks[1], app),
         Screen::ReportView => draw_report(f, chunks[1], app),
         Screen::Utilities => draw_utilities(f, chunks[1], app),
         Screen::CleanupView => draw_cleanup(f, chunks[1], app),
@@ -3274,6 +3367,8 @@ fn draw_header(f: &mut ratatui::Frame, area: Rect, app: &App) {
         Screen::SnapshotsDeleteResult => "macdiet — snapshots delete（結果）",
         Screen::LogsList => "macdiet — ログ",
         Screen::LogsDetail => "macdiet — ログ（詳細）",
+        Screen::LogsRestoreConfirm => "macdiet — 元に戻す（確認）",
+        Screen::LogsRestoreResult => "macdiet — 元に戻す（結果）",
         Screen::ReportView => "macdiet — レポート",
         Screen::Utilities => "macdiet — ユーティリティ",
         Screen::CleanupView => "macdiet — 個別削除（ゴミ箱へ移動）",
@@ -3400,7 +3495,15 @@ fn draw_footer(f: &mut ratatui::Frame, area: Rect, app: &App) {
             "q 終了 | Ctrl-C 強制終了 | ? ヘルプ",
         ),
         Screen::LogsDetail => (
-            "↑↓/j/k スクロール | r 再読込 | b/Esc 戻る",
+            "↑↓/j/k スクロール | r 再読込 | u 元に戻す（掃除のログ） | b/Esc 戻る",
+            "q 終了 | Ctrl-C 強制終了 | ? ヘルプ",
+        ),
+        Screen::LogsRestoreConfirm => (
+            "Enter 送信 | Backspace 削除 | Esc キャンセル",
+            "q 終了 | Ctrl-C 強制終了 | ? ヘルプ",
+        ),
+        Screen::LogsRestoreResult => (
+            "b/Esc 戻る（ログ一覧を更新）",
             "q 終了 | Ctrl-C 強制終了 | ? ヘルプ",
         ),
         Screen::ReportView => (
@@ -4456,6 +4559,162 @@ fn draw_logs_detail(f: &mut ratatui::Frame, area: Rect, app: &mut App) {
     f.render_widget(content, chunks[1]);
 }
 
+fn draw_logs_restore_confirm(f: &mut ratatui::Frame, area: Rect, app: &mut App) {
+    let Some(confirm) = app.logs_restore_confirm.as_ref() else {
+        let w = Paragraph::new("確認状態がありません。")
+            .block(Block::default().borders(Borders::ALL).title("確認"));
+        f.render_widget(w, area);
+        return;
+    };
+
+    let mut lines = Vec::<Line>::new();
+    lines.push(Line::from(vec![
+        Span::styled(
+            "ゴミ箱から元の場所へ戻します",
+            Style::default().add_modifier(Modifier::BOLD),
+        ),
+        Span::raw(" "),
+        Span::styled(
+            format!("（{} 件）", confirm.moved.len()),
+            Style::default().fg(Color::DarkGray),
+        ),
+    ]));
+    lines.push(Line::from(vec![
+        Span::styled("ログ: ", Style::default().fg(Color::DarkGray)),
+        Span::raw(mask_home_path(&confirm.log_path, Some(&app.home_dir))),
+    ]));
+    for record in &confirm.moved {
+        let note = if crate::privacy::is_redacted_path(&record.from.to_string_lossy()) {
+            "（パスが伏せられているため戻せません）"
+        } else if std::fs::symlink_metadata(&record.to).is_err() {
+            "（ゴミ箱に見つかりません）"
+        } else if std::fs::symlink_metadata(&record.from).is_ok() {
+            "（元の場所に既にあるため戻しません）"
+        } else {
+            ""
+        };
+        lines.push(Line::from(vec![
+            Span::raw(format!(
+                "  {} -> {}",
+                mask_home_path(&record.to, Some(&app.home_dir)),
+                mask_home_path(&record.from, Some(&app.home_dir))
+            )),
+            Span::styled(note, Style::default().fg(Color::Yellow)),
+        ]));
+    }
+    if let Some(err) = confirm.error.as_deref() {
+        lines.push(Line::from(""));
+        lines.push(Line::from(Span::styled(
+            err,
+            Style::default().fg(Color::Red),
+        )));
+    }
+    lines.push(Line::from(""));
+    lines.push(Line::from(vec![
+        Span::styled(
+            "続行するには 'restore' と入力してください: ",
+            Style::default().fg(Color::DarkGray),
+        ),
+        Span::styled(
+            confirm.input.clone(),
+            Style::default().add_modifier(Modifier::BOLD),
+        ),
+    ]));
+
+    let w = Paragraph::new(Text::from(lines))
+        .block(Block::default().borders(Borders::ALL).title("確認"))
+        .wrap(Wrap { trim: false });
+    f.render_widget(w, area);
+}
+
+fn draw_logs_restore_result(f: &mut ratatui::Frame, area: Rect, app: &mut App) {
+    let Some(result) = app.logs_restore_result.as_ref() else {
+        let w = Paragraph::new("結果がありません。")
+            .block(Block::default().borders(Borders::ALL).title("結果"));
+        f.render_widget(w, area);
+        return;
+    };
+    let outcome = &result.outcome;
+
+    let status = if outcome.errors.is_empty() && outcome.conflicts.is_empty() {
+        Span::styled(
+            "OK",
+            Style::default()
+                .fg(Color::Green)
+                .add_modifier(Modifier::BOLD),
+        )
+    } else {
+        Span::styled(
+            "ERROR",
+            Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
+        )
+    };
+    let log_hint = result
+        .log_path
+        .as_ref()
+        .map(|p| mask_home_path(p, Some(&app.home_dir)))
+        .unwrap_or_else(|| "（ログなし）".to_string());
+
+    let mut lines = Vec::<Line>::new();
+    lines.push(Line::from(vec![
+        Span::styled(
+            "元に戻しました",
+            Style::default().add_modifier(Modifier::BOLD),
+        ),
+        Span::raw("  "),
+        Span::styled("状態: ", Style::default().fg(Color::DarkGray)),
+        status,
+    ]));
+    lines.push(Line::from(format!(
+        "元に戻した: {} 件 / ゴミ箱に見つからず: {} 件 / 元の場所に既にあり: {} 件 / エラー: {} 件",
+        outcome.restored.len(),
+        outcome.skipped_missing.len(),
+        outcome.conflicts.len(),
+        outcome.errors.len()
+    )));
+    lines.push(Line::from(vec![
+        Span::styled("ログ: ", Style::default().fg(Color::DarkGray)),
+        Span::raw(log_hint),
+    ]));
+    if let Some(err) = result.log_error.as_deref() {
+        lines.push(Line::from(Span::styled(
+            format!("ログ書き込みエラー: {err}"),
+            Style::default().fg(Color::Red),
+        )));
+    }
+    lines.push(Line::from(""));
+    for record in &outcome.restored {
+        lines.push(Line::from(format!(
+            "復元: {}",
+            mask_home_path(&record.to, Some(&app.home_dir))
+        )));
+    }
+    for conflict in &outcome.conflicts {
+        lines.push(Line::from(Span::styled(
+            format!(
+                "既にあり: {}",
+                mask_home_path(conflict, Some(&app.home_dir))
+            ),
+            Style::default().fg(Color::Yellow),
+        )));
+    }
+    for err in &outcome.errors {
+        lines.push(Line::from(Span::styled(
+            format!(
+                "エラー: {}: {}",
+                mask_home_path(&err.path, Some(&app.home_dir)),
+                err.error
+            ),
+            Style::default().fg(Color::Red),
+        )));
+    }
+
+    let w = Paragraph::new(Text::from(lines))
+        .block(Block::default().borders(Borders::ALL).title("結果"))
+        .wrap(Wrap { trim: false });
+    f.render_widget(w, area);
+}
+
 fn draw_running(f: &mut ratatui::Frame, area: Rect, app: &App) {
     let spinner = ["⠋", "⠙", "⠹", "⠸", "⠼", "⠴", "⠦", "⠧", "⠇", "⠏"];
     let idx = (app.tick as usize) % spinner.len();
@@ -4486,12 +4745,18 @@ fn draw_running(f: &mut ratatui::Frame, area: Rect, app: &App) {
         }
     };
 
-    let w = Paragraph::new(Line::from(vec![
+    let mut lines = vec![Line::from(vec![
         Span::styled(s, Style::default().add_modifier(Modifier::BOLD)),
         Span::raw(" "),
         Span::raw(msg),
-    ]))
-    .block(Block::default().borders(Borders::ALL));
+    ])];
+    if let Some(status) = app.pending.as_ref().and_then(|p| p.progress.status_line()) {
+        lines.push(Line::from(Span::styled(
+            status,
+            Style::default().add_modifier(Modifier::DIM),
+        )));
+    }
+    let w = Paragraph::new(lines).block(Block::default().borders(Borders::ALL));
     f.render_widget(w, centered_rect(60, 20, area));
 }
 
@@ -5325,8 +5590,9 @@ fn draw_utilities(f: &mut ratatui::Frame, area: Rect, app: &mut App) {
     f.render_stateful_widget(list, body[0], &mut app.utilities_state);
 
     let fallback_report = Report {
-        schema_version: "1.0".to_string(),
+        schema_version: crate::core::REPORT_SCHEMA_VERSION.to_string(),
         tool_version: env!("CARGO_PKG_VERSION").to_string(),
+        host: None,
         os: crate::core::OsInfo {
             name: "unknown".to_string(),
             version: "unknown".to_string(),
@@ -5339,6 +5605,8 @@ fn draw_utilities(f: &mut ratatui::Frame, area: Rect, app: &mut App) {
         },
         findings: vec![],
         actions: vec![],
+        classification: None,
+        volumes: vec![],
     };
     let report = app.report.as_ref().unwrap_or(&fallback_report);
 
@@ -6370,7 +6638,8 @@ fn draw_help(f: &mut ratatui::Frame, area: Rect, _app: &App) {
         Line::from("ログ:"),
         Line::from("  ↑↓/j/k: 選択  Enter: 開く  r: 更新  b: 戻る"),
         Line::from("  /  : フィルタ（一覧を絞り込み）"),
-        Line::from("  （詳細）↑↓/j/k: スクロール  r: 再読込  b: 戻る"),
+        Line::from("  （詳細）↑↓/j/k: スクロール  r: 再読込  u: 元に戻す（掃除のログ）  b: 戻る"),
+        Line::from("  （元に戻す）restore（typed confirm）→ ゴミ箱から元の場所へ"),
         Line::from(""),
         Line::from("フィルタ入力:"),
         Line::from("  Enter/Esc: 入力終了  Backspace: 削除  Ctrl-U: クリア"),
@@ -6653,9 +6922,15 @@ fn parse_scan_request_from_inputs(app: &App) -> Result<ScanRequest> {
     Ok(ScanRequest {
         scope,
         deep: true,
+        duplicates: false,
+        classify: false,
+        one_file_system: None,
+        volumes: vec![],
         max_depth,
         top_dirs,
         exclude,
+        export_ncdu: None,
+        import_ncdu: None,
         show_progress: false,
     })
 }
@@ -6778,9 +7053,15 @@ fn default_scan_request(app: &App) -> ScanRequest {
     ScanRequest {
         scope,
         deep: true,
+        duplicates: false,
+        classify: false,
+        one_file_system: None,
+        volumes: vec![],
         max_depth: 3,
         top_dirs: 20,
         exclude: app.scan_exclude.clone(),
+        export_ncdu: None,
+        import_ncdu: None,
         show_progress: false,
     }
 }
@@ -6893,6 +7174,80 @@ fn reload_log_detail(app: &mut App) {
     }
 }
 
+fn open_logs_restore_confirm(app: &mut App) {
+    let Some(view) = app.logs_view.as_ref() else {
+        return;
+    };
+    if !view.entry.file_name.starts_with("fix-apply-") {
+        open_error_return_to(
+            app,
+            "元に戻せるのは掃除（fix-apply）のログだけです。",
+            Screen::LogsDetail,
+        );
+        return;
+    }
+    if app.dry_run {
+        open_error_return_to(
+            app,
+            "dry-run モード: 元に戻す操作は無効です。",
+            Screen::LogsDetail,
+        );
+        return;
+    }
+    let log_path = view.entry.path.clone();
+    match crate::logs::read_fix_apply_log(&log_path, &app.home_dir) {
+        Ok(moved) if moved.is_empty() => open_error_return_to(
+            app,
+            "元に戻す項目がありません（ゴミ箱へ移動した記録がありません）。",
+            Screen::LogsDetail,
+        ),
+        Ok(moved) => {
+            app.logs_restore_confirm = Some(LogsRestoreConfirm {
+                log_path,
+                moved,
+                input: String::new(),
+                error: None,
+            });
+            app.screen = Screen::LogsRestoreConfirm;
+        }
+        Err(err) => open_error_return_to(app, err.to_string(), Screen::LogsDetail),
+    }
+}
+
+fn submit_logs_restore_confirm(app: &mut App) {
+    let Some(confirm) = app.logs_restore_confirm.as_mut() else {
+        return;
+    };
+    if confirm.input.trim() != "restore" {
+        confirm.error = Some("続行するには 'restore' と入力してください。".to_string());
+        return;
+    }
+    let Some(confirm) = app.logs_restore_confirm.take() else {
+        return;
+    };
+
+    let started_at = time::OffsetDateTime::now_utc();
+    let outcome = crate::actions::restore_from_trash(&confirm.moved, &app.home_dir);
+    let finished_at = time::OffsetDateTime::now_utc();
+    let (log_path, log_error) = match crate::logs::write_undo_log(
+        &app.home_dir,
+        &app.redactor,
+        started_at,
+        finished_at,
+        &confirm.log_path,
+        &outcome,
+    ) {
+        Ok(p) => (Some(p), None),
+        Err(err) => (None, Some(err.to_string())),
+    };
+    app.logs_restore_result = Some(LogsRestoreResult {
+        outcome,
+        log_path,
+        log_error,
+    });
+    app.screen = Screen::LogsRestoreResult;
+}
+
 fn load_logs_entries(home_dir: &std::path::Path) -> Result<Vec<LogEntry>> {
     let dir = crate::logs::logs_dir(home_dir);
     if !dir.exists() {
@@ -7084,6 +7439,23 @@ fn load_log_detail(
                 if let Some(exit_code) = v.pointer("/attempt/exit_code").and_then(|x| x.as_i64()) {
                     summary.push(format!("exit_code: {exit_code}"));
                 }
+            } else if v.get("command").and_then(|x| x.as_str()) == Some("undo") {
+                let count = |ptr: &str| {
+                    v.pointer(ptr)
+                        .and_then(|x| x.as_array())
+                        .map(|a| a.len())
+                        .unwrap_or(0)
+                };
+                if let Some(source) = v.get("source_log").and_then(|x| x.as_str()) {
+                    summary.push(format!("元のログ: {source}"));
+                }
+                summary.push(format!(
+                    "復元: {}  スキップ(不存在): {}  既にあり: {}  エラー: {}",
+                    count("/outcome/restored"),
+                    count("/outcome/skipped_missing"),
+                    count("/outcome/conflicts"),
+                    count("/outcome/errors")
+                ));
             } else if v.get("command").and_then(|x| x.as_str()) == Some("snapshots delete") {
                 if let Some(requested) = v.get("requested_id").and_then(|x| x.as_str()) {
                     summary.push(format!("指定ID: {requested}"));
@@ -7158,6 +7530,8 @@ fn log_kind_label(file_name: &str) -> &'static str {
         "スナップショット(thin)"
     } else if file_name.starts_with("snapshots-delete-") {
         "スナップショット(delete)"
+    } else if file_name.starts_with("undo-") {
+        "元に戻す"
     } else {
         "ログ"
     }
@@ -7242,16 +7616,22 @@ mod tests {
         Engine::new(crate::engine::EngineOptions {
             timeout: Duration::from_secs(1),
             privacy_mask_home: false,
+            redaction: crate::privacy::Redaction::None,
             include_evidence: false,
             show_progress: false,
+            source_roots: vec![],
+            use_cache: false,
+            sizer: crate::scan::Sizer::Native,
+            cargo_stale_crate_days: 90,
         })
         .expect("テスト用 Engine の初期化")
     }
 
     fn report_with_actions(actions: Vec<ActionPlan>) -> Report {
         Report {
-            schema_version: "1.0".to_string(),
+            schema_version: crate::core::REPORT_SCHEMA_VERSION.to_string(),
             tool_version: "test".to_string(),
+            host: None,
             os: OsInfo {
                 name: "macOS".to_string(),
                 version: "test".to_string(),
@@ -7264,6 +7644,8 @@ mod tests {
             },
             findings: vec![],
             actions,
+            classification: None,
+            volumes: vec![],
         }
     }
 
@@ -7272,8 +7654,9 @@ mod tests {
         actions: Vec<ActionPlan>,
     ) -> Report {
         Report {
-            schema_version: "1.0".to_string(),
+            schema_version: crate::core::REPORT_SCHEMA_VERSION.to_string(),
             tool_version: "test".to_string(),
+            host: None,
             os: OsInfo {
                 name: "macOS".to_string(),
                 version: "test".to_string(),
@@ -7286,6 +7669,8 @@ mod tests {
             },
             findings,
             actions,
+            classification: None,
+            volumes: vec![],
         }
     }
 
@@ -7446,6 +7831,70 @@ mod tests {
         );
     }
 
+    #[test]
+    fn logs_detail_restore_requires_typed_confirm_and_writes_undo_log() {
+        let engine = test_engine();
+        let home = std::env::temp_dir().join(format!("macdiet-tui-undo-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&home);
+        std::fs::create_dir_all(home.join(".Trash/.npm")).expect("create trash");
+        let outcome = crate::actions::ApplyOutcome {
+            moved: vec![crate::actions::TrashMoveRecord {
+                from: home.join(".npm"),
+                to: home.join(".Trash/.npm"),
+            }],
+            skipped_missing: vec![],
+            errors: vec![],
+        };
+        let now = time::OffsetDateTime::now_utc();
+        let redactor =
+            crate::privacy::Redactor::new(crate::privacy::Redaction::Home, &home, [0; 32]);
+        crate::logs::write_fix_apply_log(&home, &redactor, now, now, RiskLevel::R1, &[], &outcome)
+            .expect("write log");
+
+        let mut app = App::new(
+            false,
+            home.clone(),
+            RiskLevel::R1,
+            false,
+            "dev".to_string(),
+            vec![],
+        );
+        refresh_logs(&mut app);
+        open_selected_log_detail(&mut app);
+        assert_eq!(app.screen, Screen::LogsDetail);
+
+        let press = |app: &mut App, code: KeyCode| {
+            handle_key(app, &engine, KeyEvent::new(code, KeyModifiers::NONE)).expect("handle_key")
+        };
+        press(&mut app, KeyCode::Char('u'));
+        assert_eq!(app.screen, Screen::LogsRestoreConfirm);
+        press(&mut app, KeyCode::Char('y'));
+        press(&mut app, KeyCode::Enter);
+        assert_eq!(app.screen, Screen::LogsRestoreConfirm);
+        assert!(!home.join(".npm").exists());
+
+        press(&mut app, KeyCode::Backspace);
+        for c in "restore".chars() {
+            press(&mut app, KeyCode::Char(c));
+        }
+        press(&mut app, KeyCode::Enter);
+        assert_eq!(app.screen, Screen::LogsRestoreResult);
+        assert!(home.join(".npm").is_dir());
+        let result = app.logs_restore_result.as_ref().expect("result");
+        assert_eq!(result.outcome.restored.len(), 1);
+        assert!(result.log_path.as_ref().is_some_and(|p| p.exists()));
+
+        press(&mut app, KeyCode::Esc);
+        assert_eq!(app.screen, Screen::LogsList);
+        assert!(
+            app.logs_entries
+                .iter()
+                .any(|e| e.file_name.starts_with("undo-"))
+        );
+
+        let _ = std::fs::remove_dir_all(&home);
+    }
+
     #[test]
     fn fix_run_cmd_confirm_esc_cancels() {
         let engine = test_engine();
@@ -171,6 +171,48 @@ pub fn print_doctor(report: &Report, cfg: &UiConfig, top_n: usize) {
             }
         }
     }
+
+    if !report.volumes.is_empty() {
+        let _ = writeln!(out);
+        let _ = writeln!(out, "ボリューム:");
+        for v in &report.volumes {
+            let _ = writeln!(
+                out,
+                "- {}: {}（{} files, errors={}）device={} fs={}",
+                v.path,
+                format_bytes(v.total_bytes),
+                v.file_count,
+                v.error_count,
+                v.device,
+                v.fs_type
+            );
+        }
+    }
+
+    if let Some(classification) = &report.classification {
+        let _ = writeln!(out);
+        let _ = writeln!(out, "種類別:");
+        for c in &classification.categories {
+            let _ = writeln!(
+                out,
+                "- {}: {}（{} files）",
+                file_category_label(&c.category),
+                format_bytes(c.bytes),
+                c.files
+            );
+        }
+        let _ = writeln!(out);
+        let _ = writeln!(out, "経過日数別（mtime / atime）:");
+        for a in &classification.ages {
+            let _ = writeln!(
+                out,
+                "- {}: {} / {}",
+                a.bucket,
+                format_bytes(a.mtime_bytes),
+                format_bytes(a.atime_bytes)
+            );
+        }
+    }
 }
 
 pub fn print_fix_plan(actions: &[ActionPlan], cfg: &UiConfig, max_risk: RiskLevel) {
@@ -714,6 +756,229 @@ fn visible_width_ansi(s: &str) -> usize {
     width
 }
 
+pub fn print_fleet_summary(summary: &crate::fleet::FleetSummary, cfg: &UiConfig) {
+    if cfg.quiet {
+        return;
+    }
+
+    let mut out = io::stdout().lock();
+    let machines = summary.machines.len();
+    let _ = writeln!(
+        out,
+        "フリート集計: {machines}台 / 推定合計 {}（1 台あたり中央値 {}）",
+        format_bytes(summary.estimated_total_bytes),
+        format_bytes(summary.median_total_bytes)
+    );
+    if summary.superseded_reports > 0 {
+        let _ = writeln!(
+            out,
+            "同じホストの古いレポート {}件は集計から除きました（最新のみ）。",
+            summary.superseded_reports
+        );
+    }
+    if summary.types.is_empty() {
+        let _ = writeln!(out, "所見はありません。");
+        return;
+    }
+    let _ = writeln!(out);
+    let _ = writeln!(
+        out,
+        "{}  {}  {}  所見",
+        pad_start_display("台数", 7),
+        pad_start_display("合計", 10),
+        pad_start_display("中央値", 10)
+    );
+    let rows = summary.types.len().min(cfg.max_table_rows.max(1));
+    for t in &summary.types[..rows] {
+        let _ = writeln!(
+            out,
+            "{}  {}  {}  {} ({})",
+            pad_start_display(&format!("{}/{machines}", t.machines), 7),
+            pad_start_display(&format_bytes(t.total_bytes), 10),
+            pad_start_display(&format_bytes(t.median_bytes), 10),
+            t.title,
+            t.finding_type
+        );
+        if !t.worst.is_empty() {
+            let worst: Vec<String> = t
+                .worst
+                .iter()
+                .map(|w| format!("{} {}", w.host, format_bytes(w.bytes)))
+                .collect();
+            let _ = writeln!(out, "{}上位: {}", " ".repeat(35), worst.join(", "));
+        }
+    }
+    if summary.types.len() > rows {
+        let _ = writeln!(
+            out,
+            "…ほか {}件（`--markdown` / `--json` で全件）",
+            summary.types.len() - rows
+        );
+    }
+}
+
+pub fn print_report_diff(diff: &crate::core::ReportDiff, cfg: &UiConfig) {
+    use crate::core::FindingChange;
+
+    if cfg.quiet {
+        return;
+    }
+
+    let mut out = io::stdout().lock();
+    let _ = writeln!(
+        out,
+        "レポート差分: {} → {}",
+        diff.old_generated_at, diff.new_generated_at
+    );
+    for (label, d) in [
+        ("推定合計", diff.estimated_total_bytes),
+        ("未観測", diff.unobserved_bytes),
+    ] {
+        let _ = writeln!(
+            out,
+            "- {label}: {} → {}（{}）",
+            format_bytes(d.old),
+            format_bytes(d.new),
+            format_delta_bytes(d.delta)
+        );
+    }
+    let _ = writeln!(out, "- 変化なし: {}件", diff.unchanged_count);
+
+    for change in [
+        FindingChange::Grown,
+        FindingChange::Shrunk,
+        FindingChange::New,
+        FindingChange::Disappeared,
+    ] {
+        let rows: Vec<&crate::core::FindingDelta> = diff.changed(change).collect();
+        if rows.is_empty() {
+            continue;
+        }
+        let _ = writeln!(out);
+        let _ = writeln!(out, "{}（{}件）:", finding_change_label(change), rows.len());
+        let shown = rows.len().min(cfg.max_table_rows.max(1));
+        for f in &rows[..shown] {
+            let delta = format_delta_bytes(f.bytes.delta);
+            let delta = if cfg.color {
+                let code = if f.bytes.delta > 0 { "31" } else { "32" };
+                format!("\x1b[{code}m{delta}\x1b[0m")
+            } else {
+                delta
+            };
+            let _ = writeln!(
+                out,
+                "- {}  {} → {}（{}） id={}",
+                f.title,
+                format_bytes(f.bytes.old),
+                format_bytes(f.bytes.new),
+                delta,
+                f.id
+            );
+        }
+        if rows.len() > shown {
+            let _ = writeln!(out, "- …ほか {}件", rows.len() - shown);
+        }
+    }
+}
+
+pub fn print_history(trend: &crate::history::HistoryTrend, cfg: &UiConfig, enabled: bool) {
+    if cfg.quiet {
+        return;
+    }
+
+    let mut out = io::stdout().lock();
+    if trend.runs.is_empty() {
+        let _ = writeln!(out, "履歴はまだありません。");
+        if !enabled {
+            let _ = writeln!(
+                out,
+                "ヒント: config の `[history] enabled = true`（または MACDIET_HISTORY_ENABLED=1）で doctor/scan の結果が保存されます。"
+            );
+        }
+        return;
+    }
+
+    let first = trend
+        .runs
+        .first()
+        .map(|r| r.generated_at.as_str())
+        .unwrap_or("");
+    let last = trend
+        .runs
+        .last()
+        .map(|r| r.generated_at.as_str())
+        .unwrap_or("");
+    let _ = writeln!(out, "履歴: {}件（{first} 〜 {last}）", trend.runs.len());
+    let _ = writeln!(out);
+
+    // 所見名は幅が揃わないため、既存の表と同じく最後の列に置く。
+    let cell_width = 11;
+    let spark_width = visible_width_ansi("推移").max(trend.runs.len());
+    let mut header = String::new();
+    for run in &trend.runs {
+        let label = run.generated_at.get(5..16).unwrap_or("?").replace('T', " ");
+        header.push_str(&pad_start_display(&label, cell_width));
+        header.push_str("  ");
+    }
+    let _ = writeln!(
+        out,
+        "{header}{}  所見",
+        pad_end_display("推移", spark_width)
+    );
+
+    let mut print_row = |label: &str, values: &[Option<u64>]| {
+        let mut line = String::new();
+        for v in values {
+            let cell = v.map(format_bytes).unwrap_or_else(|| "-".to_string());
+            line.push_str(&pad_start_display(&cell, cell_width));
+            line.push_str("  ");
+        }
+        let spark = pad_end_display(&crate::history::sparkline(values), spark_width);
+        let _ = writeln!(out, "{line}{spark}  {label}");
+    };
+    let totals: Vec<Option<u64>> = trend
+        .estimated_total_bytes
+        .iter()
+        .map(|b| Some(*b))
+        .collect();
+    print_row("推定合計", &totals);
+
+    let rows = trend.findings.len().min(cfg.max_table_rows);
+    for row in &trend.findings[..rows] {
+        print_row(&format!("{} ({})", row.title, row.id), &row.bytes);
+    }
+    if trend.findings.len() > rows {
+        let _ = writeln!(
+            out,
+            "…ほか {}件（`--finding <id>` で絞り込み）",
+            trend.findings.len() - rows
+        );
+    }
+}
+
+pub fn finding_change_label(change: crate::core::FindingChange) -> &'static str {
+    match change {
+        crate::core::FindingChange::Grown => "増加",
+        crate::core::FindingChange::Shrunk => "減少",
+        crate::core::FindingChange::New => "新規",
+        crate::core::FindingChange::Disappeared => "消滅",
+    }
+}
+
+pub fn file_category_label(category: &str) -> &str {
+    match category {
+        "media" => "メディア",
+        "archive" => "アーカイブ",
+        "disk_image" => "VM/ディスクイメージ",
+        "database" => "データベース",
+        "build_output" => "ビルド成果物",
+        "log" => "ログ",
+        "cache" => "キャッシュ",
+        "other" => "その他",
+        other => other,
+    }
+}
+
 pub fn format_bytes(bytes: u64) -> String {
     const KB: f64 = 1024.0;
     const MB: f64 = KB * 1024.0;
@@ -735,3 +1000,12 @@ pub fn format_bytes(bytes: u64) -> String {
     }
     format!("{:.1} TiB", b / TB)
 }
+
+pub fn format_delta_bytes(delta: i64) -> String {
+    let sign = match delta.signum() {
+        -1 => "-",
+        0 => "±",
+        _ => "+",
+    };
+    format!("{sign}{}", format_bytes(delta.unsigned_abs()))
+}
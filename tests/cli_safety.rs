@@ -10,9 +10,15 @@ fn macdiet_cmd(home: &Path) -> Command {
     cmd.env_remove("MACDIET_UI_MAX_TABLE_ROWS");
     cmd.env_remove("MACDIET_SCAN_DEFAULT_SCOPE");
     cmd.env_remove("MACDIET_SCAN_EXCLUDE");
+    cmd.env_remove("MACDIET_SCAN_SOURCE_ROOTS");
+    cmd.env_remove("MACDIET_SCAN_SIZER");
     cmd.env_remove("MACDIET_FIX_DEFAULT_RISK_MAX");
     cmd.env_remove("MACDIET_PRIVACY_MASK_HOME");
+    cmd.env_remove("MACDIET_PRIVACY_REDACTION");
     cmd.env_remove("MACDIET_REPORT_INCLUDE_EVIDENCE");
+    cmd.env_remove("MACDIET_REPORT_INCLUDE_HOST");
+    cmd.env_remove("MACDIET_HISTORY_ENABLED");
+    cmd.env_remove("MACDIET_RULES_CARGO_STALE_CRATE_DAYS");
     cmd
 }
 
@@ -104,6 +110,201 @@ fn fix_target_accepts_action_id() {
     let _ = std::fs::remove_dir_all(&home);
 }
 
+#[test]
+fn fix_from_report_rechecks_actions_and_refuses_drifted_targets() {
+    let home = make_temp_home();
+    write_file(home.join(".npm/cache.bin").as_path(), b"hello");
+    write_file(
+        home.join("Library/Developer/XCTestDevices/clone.bin")
+            .as_path(),
+        b"hello",
+    );
+
+    let out = run(&home, &["--json", "report"]);
+    assert!(out.status.success());
+    let mut saved: serde_json::Value = serde_json::from_slice(&out.stdout).expect("report json");
+    let report_path = home.join("saved-report.json");
+    let report_arg = report_path.to_string_lossy().to_string();
+    std::fs::write(&report_path, &out.stdout).expect("write report");
+
+    let action_ids = |out: &Output| -> Vec<String> {
+        let v: serde_json::Value = serde_json::from_slice(&out.stdout).expect("fix json");
+        v["actions"]
+            .as_array()
+            .expect("actions")
+            .iter()
+            .filter_map(|a| a["id"].as_str().map(str::to_string))
+            .collect()
+    };
+
+    let out = run(
+        &home,
+        &[
+            "--json",
+            "fix",
+            "--risk",
+            "R1",
+            "--from-report",
+            &report_arg,
+        ],
+    );
+    assert!(
+        out.status.success(),
+        "stderr={}",
+        String::from_utf8_lossy(&out.stderr)
+    );
+    assert!(action_ids(&out).contains(&"npm-cache-trash".to_string()));
+    let stdout = String::from_utf8_lossy(&out.stdout);
+    assert!(
+        stdout.contains("保存済みレポートから計画"),
+        "stdout={stdout}"
+    );
+
+    // 許可リスト外の RUN_CMD は保存済みレポートからは扱わない。
+    let actions = saved["actions"].as_array_mut().expect("actions");
+    let npm = actions
+        .iter()
+        .find(|a| a["id"] == "npm-cache-trash")
+        .expect("npm-cache-trash")
+        .clone();
+    let mut injected = npm.clone();
+    injected["id"] = serde_json::Value::from("injected-cmd");
+    injected["kind"] = serde_json::json!({"kind": "RUN_CMD", "cmd": "rm", "args": ["-rf", "~"]});
+    actions.push(injected);
+    std::fs::write(&report_path, serde_json::to_vec(&saved).expect("json")).expect("write");
+    let out = run(
+        &home,
+        &[
+            "--json",
+            "fix",
+            "--risk",
+            "R1",
+            "--from-report",
+            &report_arg,
+        ],
+    );
+    assert!(out.status.success());
+    assert!(!action_ids(&out).contains(&"injected-cmd".to_string()));
+    let stderr = String::from_utf8_lossy(&out.stderr);
+    assert!(
+        stderr.contains("除外: injected-cmd: 許可リストにないコマンドです"),
+        "stderr={stderr}"
+    );
+
+    // ファイル上のリスクは使わず、現在の診断結果から取り直す。
+    let mut relabeled = saved.clone();
+    let xctest = relabeled["actions"]
+        .as_array_mut()
+        .expect("actions")
+        .iter_mut()
+        .find(|a| a["id"] == "xctest-devices-trash")
+        .expect("xctest-devices-trash");
+    assert_eq!(xctest["risk_level"], "R2");
+    xctest["risk_level"] = serde_json::Value::from("R1");
+    std::fs::write(&report_path, serde_json::to_vec(&relabeled).expect("json")).expect("write");
+    let out = run(
+        &home,
+        &[
+            "--json",
+            "fix",
+            "--risk",
+            "R1",
+            "--from-report",
+            &report_arg,
+        ],
+    );
+    assert!(out.status.success());
+    assert!(!action_ids(&out).contains(&"xctest-devices-trash".to_string()));
+
+    // 許可リストの形でも、現在の診断結果に無い RUN_CMD は除外する。
+    let mut forged = npm.clone();
+    forged["id"] = serde_json::Value::from("lang-version-uninstall:pyenv:python:3.8.0");
+    forged["risk_level"] = serde_json::Value::from("R2");
+    forged["kind"] = serde_json::json!({"kind": "RUN_CMD", "cmd": "pyenv", "args": ["uninstall", "-f", "3.8.0"]});
+    let mut tampered = saved.clone();
+    tampered["actions"]
+        .as_array_mut()
+        .expect("actions")
+        .push(forged);
+    std::fs::write(&report_path, serde_json::to_vec(&tampered).expect("json")).expect("write");
+    let out = run(
+        &home,
+        &[
+            "--json",
+            "fix",
+            "--risk",
+            "R2",
+            "--from-report",
+            &report_arg,
+        ],
+    );
+    assert!(out.status.success());
+    let ids = action_ids(&out);
+    assert!(!ids.contains(&"lang-version-uninstall:pyenv:python:3.8.0".to_string()));
+    assert!(
+        ids.contains(&"xctest-devices-trash".to_string()),
+        "ids={ids:?}"
+    );
+    let stderr = String::from_utf8_lossy(&out.stderr);
+    assert!(
+        stderr.contains(
+            "除外: lang-version-uninstall:pyenv:python:3.8.0: 現在の診断結果に同じアクションがありません"
+        ),
+        "stderr={stderr}"
+    );
+
+    // 許可リスト外のパスは入力エラーとして拒否する。
+    let mut outside = npm.clone();
+    outside["id"] = serde_json::Value::from("outside-home");
+    outside["kind"] = serde_json::json!({"kind": "TRASH_MOVE", "paths": ["/etc"]});
+    let mut tampered = saved.clone();
+    tampered["actions"]
+        .as_array_mut()
+        .expect("actions")
+        .push(outside);
+    std::fs::write(&report_path, serde_json::to_vec(&tampered).expect("json")).expect("write");
+    let out = run(
+        &home,
+        &[
+            "--json",
+            "fix",
+            "--risk",
+            "R1",
+            "--from-report",
+            &report_arg,
+        ],
+    );
+    assert_eq!(out.status.code(), Some(2));
+
+    // レポート生成後に対象が大きくなったら除外する。
+    std::fs::write(&report_path, serde_json::to_vec(&saved).expect("json")).expect("write");
+    write_file(
+        home.join(".npm/_cacache/big.bin").as_path(),
+        &vec![0u8; 4 * 1024 * 1024],
+    );
+    let out = run(
+        &home,
+        &[
+            "--json",
+            "fix",
+            "--risk",
+            "R1",
+            "--from-report",
+            &report_arg,
+        ],
+    );
+    assert!(out.status.success());
+    assert!(!action_ids(&out).contains(&"npm-cache-trash".to_string()));
+    let stderr = String::from_utf8_lossy(&out.stderr);
+    assert!(
+        stderr.contains("除外: npm-cache-trash: "),
+        "stderr={stderr}"
+    );
+    assert!(home.join(".npm/cache.bin").exists());
+
+    let _ = std::fs::remove_dir_all(&home);
+}
+
 #[test]
 fn fix_apply_requires_tty_and_does_not_change_filesystem() {
     let home = make_temp_home();
@@ -123,6 +324,69 @@ fn fix_apply_requires_tty_and_does_not_change_filesystem() {
     let _ = std::fs::remove_dir_all(&home);
 }
 
+#[test]
+fn undo_previews_latest_fix_apply_log_and_requires_tty_to_restore() {
+    let home = make_temp_home();
+    write_file(home.join(".Trash/.npm/cache.bin").as_path(), b"hello");
+    write_file(home.join(".gradle/caches/new.bin").as_path(), b"new");
+    write_file(home.join(".Trash/caches/old.bin").as_path(), b"old");
+    let log = serde_json::json!({
+        "schema_version": "1.0",
+        "command": "fix",
+        "status": "ok",
+        "outcome": {
+            "moved": [
+                {"from": "~/.npm", "to": "~/.Trash/.npm"},
+                {"from": "~/.gradle/caches", "to": "~/.Trash/caches"},
+            ],
+            "skipped_missing": [],
+            "errors": [],
+        },
+    });
+    write_file(
+        home.join(".config/macdiet/logs/fix-apply-1-100.json")
+            .as_path(),
+        log.to_string().as_bytes(),
+    );
+    write_file(
+        home.join(".config/macdiet/logs/fix-apply-1-99.json")
+            .as_path(),
+        b"{}",
+    );
+
+    let out = run(&home, &["--dry-run", "undo", "latest"]);
+    assert!(
+        out.status.success(),
+        "stderr={}",
+        String::from_utf8_lossy(&out.stderr)
+    );
+    let stdout = String::from_utf8_lossy(&out.stdout);
+    assert!(stdout.contains("fix-apply-1-100.json"), "stdout={stdout}");
+    assert!(
+        stdout.contains("~/.Trash/.npm -> ~/.npm\n"),
+        "stdout={stdout}"
+    );
+    assert!(
+        stdout.contains("~/.Trash/caches -> ~/.gradle/caches（元の場所に既にあるため戻しません）"),
+        "stdout={stdout}"
+    );
+
+    let out = run(&home, &["undo", "latest"]);
+    assert_eq!(out.status.code(), Some(2));
+    let stderr = String::from_utf8_lossy(&out.stderr);
+    assert!(stderr.contains("TTY が必要"), "stderr={stderr}");
+    assert!(home.join(".Trash/.npm/cache.bin").exists());
+    assert!(!home.join(".npm").exists());
+
+    let out = run(
+        &home,
+        &["undo", "~/.config/macdiet/logs/fix-apply-1-99.json"],
+    );
+    assert_eq!(out.status.code(), Some(2));
+
+    let _ = std::fs::remove_dir_all(&home);
+}
+
 #[test]
 fn fix_interactive_requires_tty() {
     let home = make_temp_home();
@@ -239,6 +503,46 @@ fn doctor_detects_gradle_caches() {
     let _ = std::fs::remove_dir_all(&home);
 }
 
+#[test]
+fn doctor_events_ndjson_streams_rule_progress_to_stderr() {
+    let home = make_temp_home();
+    write_file(home.join(".gradle/caches/cache.bin").as_path(), b"hello");
+
+    let out = run(&home, &["doctor", "--json", "--events", "ndjson"]);
+    assert!(out.status.success());
+    let _: serde_json::Value = serde_json::from_slice(&out.stdout).expect("stdout is report json");
+
+    let stderr = String::from_utf8_lossy(&out.stderr);
+    let events: Vec<serde_json::Value> = stderr
+        .lines()
+        .map(|l| serde_json::from_str(l).unwrap_or_else(|_| panic!("not ndjson: {l}")))
+        .collect();
+    let kinds: Vec<&str> = events.iter().filter_map(|e| e["event"].as_str()).collect();
+    assert!(kinds.contains(&"rule_started"), "stderr={stderr}");
+    assert_eq!(kinds.last(), Some(&"report_ready"), "stderr={stderr}");
+    assert!(
+        events.iter().any(|e| e["event"] == "rule_finished"
+            && e["rule"] == "gradle-caches"
+            && e["findings"] == 1),
+        "stderr={stderr}"
+    );
+
+    let out = run(&home, &["doctor", "--events", "json"]);
+    assert_eq!(out.status.code(), Some(2));
+    let out = run(&home, &["doctor", "--events-fd", "3"]);
+    assert_eq!(out.status.code(), Some(2));
+    for fd in ["0", "1", "2"] {
+        let out = run(
+            &home,
+            &["--events", "ndjson", "--events-fd", fd, "--json", "doctor"],
+        );
+        assert_eq!(out.status.code(), Some(2), "fd={fd}");
+        assert!(out.stdout.is_empty(), "fd={fd}");
+    }
+
+    let _ = std::fs::remove_dir_all(&home);
+}
+
 #[test]
 fn doctor_includes_docker_system_df_evidence_when_docker_present() {
     use std::os::unix::fs::PermissionsExt;
@@ -351,11 +655,472 @@ fn scan_dev_scope_includes_gradle_dir() {
     let _ = std::fs::remove_dir_all(&home);
 }
 
+#[test]
+fn scan_duplicates_reports_wasted_bytes_and_respects_exclude() {
+    let home = make_temp_home();
+    let data = vec![1u8; 2 * 1024 * 1024];
+    write_file(home.join("work/one/model.bin").as_path(), &data);
+    write_file(home.join("work/two/model.bin").as_path(), &data);
+    write_file(home.join("work/vendor-copy/model.bin").as_path(), &data);
+
+    let out = run(
+        &home,
+        &[
+            "scan",
+            "--duplicates",
+            "--scope",
+            "work",
+            "--exclude",
+            "**/vendor-copy/**",
+            "--json",
+        ],
+    );
+    assert!(out.status.success());
+
+    let v: serde_json::Value = serde_json::from_slice(&out.stdout).expect("parse json");
+    let findings = v
+        .get("findings")
+        .and_then(|f| f.as_array())
+        .expect("findings array");
+    assert_eq!(findings.len(), 1, "findings={findings:?}");
+    let f = &findings[0];
+    assert_eq!(
+        f.get("type").and_then(|t| t.as_str()),
+        Some("SCAN_DUPLICATE_FILES")
+    );
+    assert_eq!(
+        f.get("estimated_bytes").and_then(|b| b.as_u64()),
+        Some(data.len() as u64)
+    );
+
+    let _ = std::fs::remove_dir_all(&home);
+}
+
+#[test]
+fn scan_import_ncdu_reproduces_deep_scan_findings() {
+    let home = make_temp_home();
+    write_file(
+        home.join(".npm/_cacache/a/data.bin").as_path(),
+        &[0u8; 3000],
+    );
+    write_file(
+        home.join(".npm/_cacache/b/data.bin").as_path(),
+        &[0u8; 1000],
+    );
+    write_file(
+        home.join(".cargo/registry/cache/crate.bin").as_path(),
+        &[0u8; 2000],
+    );
+    let export = home.join("export.json");
+
+    let top_dirs = |v: &serde_json::Value| -> Vec<(String, u64)> {
+        v.get("findings")
+            .and_then(|f| f.as_array())
+            .expect("findings array")
+            .iter()
+            .filter(|f| f.get("type").and_then(|t| t.as_str()) == Some("SCAN_TOP_DIR"))
+            .map(|f| {
+                (
+                    f.get("id")
+                        .and_then(|id| id.as_str())
+                        .unwrap_or("")
+                        .to_string(),
+                    f.get("estimated_bytes")
+                        .and_then(|b| b.as_u64())
+                        .unwrap_or(0),
+                )
+            })
+            .collect()
+    };
+
+    let out = run(
+        &home,
+        &[
+            "scan",
+            "--deep",
+            "--export-ncdu",
+            export.to_str().expect("utf8 path"),
+            "--json",
+        ],
+    );
+    assert!(out.status.success());
+    let deep: serde_json::Value = serde_json::from_slice(&out.stdout).expect("parse json");
+    let expected = top_dirs(&deep);
+    assert!(
+        expected.contains(&("scan-top:~/.npm/_cacache/a".to_string(), 3000)),
+        "expected={expected:?}"
+    );
+
+    let exported: serde_json::Value =
+        serde_json::from_slice(&std::fs::read(&export).expect("read export")).expect("parse");
+    assert_eq!(exported.get(0).and_then(|v| v.as_u64()), Some(1));
+    assert_eq!(
+        exported
+            .get(3)
+            .and_then(|root| root.get(0))
+            .and_then(|info| info.get("name")),
+        Some(&serde_json::Value::from(home.display().to_string()))
+    );
+
+    let _ = std::fs::remove_dir_all(home.join(".npm"));
+    let _ = std::fs::remove_dir_all(home.join(".cargo"));
+    let out = run(
+        &home,
+        &[
+            "scan",
+            "--import-ncdu",
+            export.to_str().expect("utf8 path"),
+            "--json",
+        ],
+    );
+    assert!(out.status.success());
+    let imported: serde_json::Value = serde_json::from_slice(&out.stdout).expect("parse json");
+    assert_eq!(top_dirs(&imported), expected);
+
+    let out = run(
+        &home,
+        &["scan", "--export-ncdu", export.to_str().expect("utf8 path")],
+    );
+    assert!(!out.status.success());
+
+    let _ = std::fs::remove_dir_all(&home);
+}
+
+#[test]
+fn scan_format_folded_emits_masked_stacks() {
+    let home = make_temp_home();
+    write_file(home.join("work/a/x/deep/data.bin").as_path(), &[0u8; 3000]);
+    write_file(home.join("work/a;b/data.bin").as_path(), &[0u8; 700]);
+    write_file(home.join("work/top.bin").as_path(), &[0u8; 10]);
+
+    let out = run(
+        &home,
+        &[
+            "scan",
+            "--deep",
+            "--scope",
+            "work",
+            "--max-depth",
+            "2",
+            "--format",
+            "folded",
+        ],
+    );
+    assert!(out.status.success());
+    let stdout = String::from_utf8_lossy(&out.stdout);
+    let lines: Vec<&str> = stdout.lines().collect();
+    assert_eq!(
+        lines,
+        vec!["~;work 10", "~;work;a;x 3000", "~;work;a_b 700"],
+        "stdout={stdout}"
+    );
+
+    let out = {
+        let mut cmd = macdiet_cmd(&home);
+        cmd.env("MACDIET_PRIVACY_MASK_HOME", "false");
+        cmd.args(["scan", "--deep", "--scope", "work", "--format", "folded"]);
+        cmd.output().expect("run macdiet")
+    };
+    assert!(out.status.success());
+    let stdout = String::from_utf8_lossy(&out.stdout);
+    let home_frames = home
+        .to_string_lossy()
+        .trim_start_matches('/')
+        .replace('/', ";");
+    assert!(
+        stdout.contains(&format!("{home_frames};work;a;x;deep 3000")),
+        "stdout={stdout}"
+    );
+
+    let out = run(&home, &["scan", "--format", "folded"]);
+    assert!(!out.status.success());
+
+    let _ = std::fs::remove_dir_all(&home);
+}
+
+#[test]
+fn scan_volume_reports_per_volume_totals() {
+    let home = make_temp_home();
+    let vol_a = home.join("mnt/a");
+    let vol_b = home.join("mnt/b");
+    write_file(vol_a.join("data/one.bin").as_path(), &[0u8; 1500]);
+    write_file(vol_b.join("two.bin").as_path(), &[0u8; 700]);
+
+    let out = run(
+        &home,
+        &[
+            "scan",
+            "--deep",
+            "--volume",
+            vol_a.to_str().expect("utf8 path"),
+            "--volume",
+            vol_b.to_str().expect("utf8 path"),
+            "--json",
+        ],
+    );
+    assert!(
+        out.status.success(),
+        "stderr={}",
+        String::from_utf8_lossy(&out.stderr)
+    );
+    let v: serde_json::Value = serde_json::from_slice(&out.stdout).expect("parse json");
+    let volumes: Vec<(String, u64)> = v
+        .get("volumes")
+        .and_then(|v| v.as_array())
+        .expect("volumes array")
+        .iter()
+        .map(|v| {
+            (
+                v.get("path")
+                    .and_then(|p| p.as_str())
+                    .unwrap_or("")
+                    .to_string(),
+                v.get("total_bytes").and_then(|b| b.as_u64()).unwrap_or(0),
+            )
+        })
+        .collect();
+    assert_eq!(
+        volumes,
+        vec![("~/mnt/a".to_string(), 1500), ("~/mnt/b".to_string(), 700)]
+    );
+    let notes = v
+        .get("summary")
+        .and_then(|s| s.get("notes"))
+        .and_then(|n| n.as_array())
+        .expect("notes array");
+    assert!(
+        notes.iter().any(|n| n
+            .as_str()
+            .is_some_and(|n| n.contains("one_file_system=true"))),
+        "notes={notes:?}"
+    );
+
+    let out = run(
+        &home,
+        &["scan", "--deep", "--volume", "a", "--scope", "dev"],
+    );
+    assert!(!out.status.success());
+
+    let _ = std::fs::remove_dir_all(&home);
+}
+
+#[test]
+fn growth_reports_directories_that_grew_during_sampling() {
+    let home = make_temp_home();
+    write_file(home.join("work/steady/a.bin").as_path(), &[0u8; 500]);
+    write_file(home.join("work/busy/log.txt").as_path(), &[0u8; 100]);
+
+    let child = macdiet_cmd(&home)
+        .args([
+            "growth",
+            "--interval",
+            "1s",
+            "--duration",
+            "3s",
+            "--scope",
+            "work",
+            "--json",
+        ])
+        .stdout(std::process::Stdio::piped())
+        .stderr(std::process::Stdio::piped())
+        .spawn()
+        .expect("spawn macdiet");
+    std::thread::sleep(std::time::Duration::from_millis(1500));
+    write_file(home.join("work/busy/more.bin").as_path(), &[0u8; 4000]);
+
+    let out = child.wait_with_output().expect("wait macdiet");
+    assert!(
+        out.status.success(),
+        "stderr={}",
+        String::from_utf8_lossy(&out.stderr)
+    );
+    let v: serde_json::Value = serde_json::from_slice(&out.stdout).expect("parse json");
+    let findings = v
+        .get("findings")
+        .and_then(|f| f.as_array())
+        .expect("findings array");
+    let growth: Vec<(&str, u64)> = findings
+        .iter()
+        .filter(|f| f.get("type").and_then(|t| t.as_str()) == Some("GROWTH_HOTSPOT"))
+        .map(|f| {
+            (
+                f.get("id").and_then(|i| i.as_str()).unwrap_or(""),
+                f.get("estimated_bytes")
+                    .and_then(|b| b.as_u64())
+                    .unwrap_or(0),
+            )
+        })
+        .collect();
+    assert_eq!(growth, vec![("growth:~/work/busy", 4000)], "{findings:?}");
+
+    let out = run(&home, &["growth", "--interval", "5m", "--duration", "1m"]);
+    assert_eq!(out.status.code(), Some(2));
+    let out = run(&home, &["growth", "--interval", "soon"]);
+    assert_eq!(out.status.code(), Some(2));
+    for duration in ["999999999999999999h", "18446744073709551615s"] {
+        let out = run(&home, &["growth", "--duration", duration]);
+        assert_eq!(out.status.code(), Some(2), "{duration}");
+    }
+
+    let _ = std::fs::remove_dir_all(&home);
+}
+
+#[test]
+fn growth_stops_on_sigint_and_still_emits_report() {
+    let home = make_temp_home();
+    write_file(home.join("work/a/one.bin").as_path(), &[0u8; 100]);
+
+    let child = macdiet_cmd(&home)
+        .args([
+            "growth",
+            "--interval",
+            "1s",
+            "--duration",
+            "30s",
+            "--scope",
+            "work",
+            "--json",
+        ])
+        .stdout(std::process::Stdio::piped())
+        .stderr(std::process::Stdio::piped())
+        .spawn()
+        .expect("spawn macdiet");
+    std::thread::sleep(std::time::Duration::from_millis(2500));
+    let started = std::time::Instant::now();
+    let status = Command::new("kill")
+        .args(["-INT", &child.id().to_string()])
+        .status()
+        .expect("run kill");
+    assert!(status.success());
+
+    let out = child.wait_with_output().expect("wait macdiet");
+    assert!(started.elapsed() < std::time::Duration::from_secs(10));
+    assert!(
+        out.status.success(),
+        "stderr={}",
+        String::from_utf8_lossy(&out.stderr)
+    );
+    let v: serde_json::Value = serde_json::from_slice(&out.stdout).expect("parse json");
+    let notes = v
+        .get("summary")
+        .and_then(|s| s.get("notes"))
+        .and_then(|n| n.as_array())
+        .expect("notes array");
+    assert!(
+        notes
+            .iter()
+            .any(|n| n.as_str().is_some_and(|n| n.starts_with("中断: Ctrl-C"))),
+        "notes={notes:?}"
+    );
+
+    let _ = std::fs::remove_dir_all(&home);
+}
+
+#[test]
+fn history_records_doctor_runs_with_retention_and_shows_trend() {
+    let home = make_temp_home();
+    for i in 1..=3usize {
+        write_file(
+            home.join(format!(".gradle/caches/part{i}.bin")).as_path(),
+            &vec![0u8; 1000 * i],
+        );
+        let out = macdiet_cmd(&home)
+            .env("MACDIET_HISTORY_ENABLED", "1")
+            .env("MACDIET_HISTORY_MAX_RUNS", "2")
+            .args(["--no-cache", "doctor"])
+            .output()
+            .expect("run macdiet");
+        assert!(out.status.success());
+    }
+    let out = run(&home, &["--no-cache", "doctor"]);
+    assert!(out.status.success());
+
+    let files = std::fs::read_dir(home.join(".config/macdiet/history"))
+        .expect("history dir")
+        .count();
+    assert_eq!(files, 2);
+
+    let out = run(&home, &["--json", "history", "--finding", "gradle-caches"]);
+    assert!(
+        out.status.success(),
+        "stderr={}",
+        String::from_utf8_lossy(&out.stderr)
+    );
+    let v: serde_json::Value = serde_json::from_slice(&out.stdout).expect("parse json");
+    assert_eq!(
+        v.get("runs").and_then(|r| r.as_array()).map(Vec::len),
+        Some(2)
+    );
+    let findings = v
+        .get("findings")
+        .and_then(|f| f.as_array())
+        .expect("findings array");
+    assert_eq!(findings.len(), 1);
+    assert_eq!(
+        findings[0].get("bytes"),
+        Some(&serde_json::json!([3000, 6000]))
+    );
+
+    let out = run(&home, &["history"]);
+    assert!(out.status.success());
+    let stdout = String::from_utf8_lossy(&out.stdout);
+    assert!(stdout.contains("推移"), "stdout={stdout}");
+    assert!(stdout.contains("_#"), "stdout={stdout}");
+    assert!(stdout.contains("(gradle-caches)"), "stdout={stdout}");
+
+    let out = run(&home, &["history", "--command", "fix"]);
+    assert_eq!(out.status.code(), Some(2));
+
+    let _ = std::fs::remove_dir_all(&home);
+}
+
+#[test]
+fn doctor_reuses_size_cache_unless_no_cache() {
+    let home = make_temp_home();
+    write_file(home.join(".npm/_cacache/data.bin").as_path(), b"hello");
+
+    let notes = |args: &[&str]| -> Vec<String> {
+        let out = run(&home, args);
+        assert!(out.status.success());
+        let v: serde_json::Value = serde_json::from_slice(&out.stdout).expect("parse json");
+        v.get("summary")
+            .and_then(|s| s.get("notes"))
+            .and_then(|n| n.as_array())
+            .expect("notes array")
+            .iter()
+            .filter_map(|n| n.as_str().map(str::to_string))
+            .collect()
+    };
+    let has_cache_note = |notes: &[String]| notes.iter().any(|n| n.starts_with("キャッシュ:"));
+
+    let cache_file = home.join(".config/macdiet/cache/sizes.json");
+    assert!(!has_cache_note(&notes(&["--no-cache", "doctor", "--json"])));
+    assert!(!cache_file.exists());
+    assert!(!has_cache_note(&notes(&["doctor", "--json"])));
+    assert!(cache_file.exists());
+    let written = std::fs::metadata(&cache_file)
+        .and_then(|m| m.modified())
+        .expect("cache mtime");
+    assert!(has_cache_note(&notes(&["doctor", "--json"])));
+    assert_eq!(
+        std::fs::metadata(&cache_file)
+            .and_then(|m| m.modified())
+            .expect("cache mtime"),
+        written
+    );
+    assert!(!has_cache_note(&notes(&["doctor", "--json", "--no-cache"])));
+
+    let _ = std::fs::remove_dir_all(&home);
+}
+
 #[test]
 fn doctor_respects_ui_max_table_rows_in_headers() {
     let home = make_temp_home();
     write_file(home.join(".npm/cache.bin").as_path(), b"hello");
-    write_file(home.join(".cargo/registry/cache.bin").as_path(), b"hello");
+    write_file(
+        home.join(".cargo/registry/src/index/cache.bin").as_path(),
+        b"hello",
+    );
 
     let out = {
         let mut cmd = macdiet_cmd(&home);
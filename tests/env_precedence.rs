@@ -10,9 +10,15 @@ fn base_cmd(home: &Path) -> Command {
     cmd.env_remove("MACDIET_UI_MAX_TABLE_ROWS");
     cmd.env_remove("MACDIET_SCAN_DEFAULT_SCOPE");
     cmd.env_remove("MACDIET_SCAN_EXCLUDE");
+    cmd.env_remove("MACDIET_SCAN_SOURCE_ROOTS");
+    cmd.env_remove("MACDIET_SCAN_SIZER");
     cmd.env_remove("MACDIET_FIX_DEFAULT_RISK_MAX");
     cmd.env_remove("MACDIET_PRIVACY_MASK_HOME");
+    cmd.env_remove("MACDIET_PRIVACY_REDACTION");
     cmd.env_remove("MACDIET_REPORT_INCLUDE_EVIDENCE");
+    cmd.env_remove("MACDIET_REPORT_INCLUDE_HOST");
+    cmd.env_remove("MACDIET_HISTORY_ENABLED");
+    cmd.env_remove("MACDIET_RULES_CARGO_STALE_CRATE_DAYS");
     cmd
 }
 
@@ -121,3 +127,165 @@ default_risk_max = "R1"
 
     let _ = std::fs::remove_dir_all(&home);
 }
+
+#[test]
+fn env_source_roots_enable_language_version_reference_check() {
+    let home = make_temp_home();
+    write_file(
+        home.join(".pyenv/versions/3.10.0/bin/python").as_path(),
+        b"python",
+    );
+    write_file(
+        home.join(".pyenv/versions/3.11.4/bin/python").as_path(),
+        b"python",
+    );
+    write_file(home.join("work/app/.python-version").as_path(), b"3.11\n");
+
+    let report = |source_roots: Option<&str>| -> serde_json::Value {
+        let mut cmd = base_cmd(&home);
+        if let Some(v) = source_roots {
+            cmd.env("MACDIET_SCAN_SOURCE_ROOTS", v);
+        }
+        cmd.args(["report", "--json"]);
+        let out = cmd.output().expect("run macdiet");
+        assert!(out.status.success());
+        serde_json::from_slice(&out.stdout).expect("parse json")
+    };
+    let action_ids = |v: &serde_json::Value| -> Vec<String> {
+        v.get("actions")
+            .and_then(|a| a.as_array())
+            .expect("actions array")
+            .iter()
+            .filter_map(|a| a.get("id").and_then(|id| id.as_str()).map(str::to_string))
+            .collect()
+    };
+
+    let ids = action_ids(&report(None));
+    assert!(
+        !ids.iter()
+            .any(|id| id.starts_with("lang-version-uninstall:")),
+        "ids={ids:?}"
+    );
+
+    let ids = action_ids(&report(Some("~/work")));
+    assert!(
+        ids.contains(&"lang-version-uninstall:pyenv:python:3.10.0".to_string()),
+        "ids={ids:?}"
+    );
+    assert!(
+        !ids.contains(&"lang-version-uninstall:pyenv:python:3.11.4".to_string()),
+        "ids={ids:?}"
+    );
+
+    let _ = std::fs::remove_dir_all(&home);
+}
+
+#[test]
+fn env_scan_sizer_selects_du_or_native_estimates() {
+    let home = make_temp_home();
+    write_file(home.join(".npm/_cacache/blob.bin").as_path(), &[0u8; 3000]);
+
+    let methods = |sizer: Option<&str>, cache: bool| -> Vec<String> {
+        let mut cmd = base_cmd(&home);
+        if let Some(v) = sizer {
+            cmd.env("MACDIET_SCAN_SIZER", v);
+        }
+        if !cache {
+            cmd.arg("--no-cache");
+        }
+        cmd.args(["report", "--json", "--include-evidence"]);
+        let out = cmd.output().expect("run macdiet");
+        assert!(
+            out.status.success(),
+            "stderr={}",
+            String::from_utf8_lossy(&out.stderr)
+        );
+        let v: serde_json::Value = serde_json::from_slice(&out.stdout).expect("parse json");
+        v.get("findings")
+            .and_then(|f| f.as_array())
+            .expect("findings array")
+            .iter()
+            .flat_map(|f| {
+                f.get("evidence")
+                    .and_then(|e| e.as_array())
+                    .cloned()
+                    .unwrap_or_default()
+            })
+            .filter_map(|ev| {
+                let value = ev.get("value").and_then(|v| v.as_str())?;
+                value
+                    .split_whitespace()
+                    .find_map(|kv| kv.strip_prefix("method="))
+                    .map(str::to_string)
+            })
+            .collect()
+    };
+
+    let native = methods(None, false);
+    assert!(!native.is_empty());
+    assert!(native.iter().all(|m| m == "Native"), "methods={native:?}");
+    let du = methods(Some("du"), false);
+    assert!(du.iter().any(|m| m == "Du"), "methods={du:?}");
+
+    // キャッシュ有効時も設定した sizer で測る（2 回目はキャッシュにヒットする）。
+    for _ in 0..2 {
+        let du = methods(Some("du"), true);
+        assert!(du.iter().any(|m| m == "Du"), "methods={du:?}");
+        let native = methods(Some("native"), true);
+        assert!(!native.is_empty());
+        assert!(native.iter().all(|m| m == "Native"), "methods={native:?}");
+    }
+
+    let out = base_cmd(&home)
+        .env("MACDIET_SCAN_SIZER", "fast")
+        .args(["config", "--show"])
+        .output()
+        .expect("run macdiet");
+    assert_eq!(out.status.code(), Some(2));
+
+    let _ = std::fs::remove_dir_all(&home);
+}
+
+#[test]
+fn env_cargo_stale_crate_days_overrides_config_threshold() {
+    let home = make_temp_home();
+    write_file(
+        home.join(".cargo/registry/cache/index.crates.io-test/demo-0.1.0.crate")
+            .as_path(),
+        b"hello",
+    );
+    write_file(
+        home.join(".config/macdiet/config.toml").as_path(),
+        br#"
+[rules]
+cargo_stale_crate_days = 100000
+"#,
+    );
+
+    let stale_action_ids = |env: Option<&str>| {
+        let mut cmd = base_cmd(&home);
+        if let Some(days) = env {
+            cmd.env("MACDIET_RULES_CARGO_STALE_CRATE_DAYS", days);
+        }
+        let out = cmd
+            .args(["doctor", "--json"])
+            .output()
+            .expect("run macdiet");
+        assert!(out.status.success());
+        let v: serde_json::Value = serde_json::from_slice(&out.stdout).expect("parse json");
+        v["actions"]
+            .as_array()
+            .expect("actions array")
+            .iter()
+            .filter(|a| a["id"] == "cargo-registry-stale-crates-trash")
+            .map(|a| a["title"].as_str().unwrap_or_default().to_string())
+            .collect::<Vec<_>>()
+    };
+
+    assert!(stale_action_ids(None).is_empty());
+    let titles = stale_action_ids(Some("0"));
+    assert_eq!(titles.len(), 1);
+    assert!(titles[0].contains("0日以上"), "titles={titles:?}");
+
+    let _ = std::fs::remove_dir_all(&home);
+}
@@ -10,9 +10,15 @@ fn macdiet_cmd(home: &Path) -> Command {
     cmd.env_remove("MACDIET_UI_MAX_TABLE_ROWS");
     cmd.env_remove("MACDIET_SCAN_DEFAULT_SCOPE");
     cmd.env_remove("MACDIET_SCAN_EXCLUDE");
+    cmd.env_remove("MACDIET_SCAN_SOURCE_ROOTS");
+    cmd.env_remove("MACDIET_SCAN_SIZER");
     cmd.env_remove("MACDIET_FIX_DEFAULT_RISK_MAX");
     cmd.env_remove("MACDIET_PRIVACY_MASK_HOME");
+    cmd.env_remove("MACDIET_PRIVACY_REDACTION");
     cmd.env_remove("MACDIET_REPORT_INCLUDE_EVIDENCE");
+    cmd.env_remove("MACDIET_REPORT_INCLUDE_HOST");
+    cmd.env_remove("MACDIET_HISTORY_ENABLED");
+    cmd.env_remove("MACDIET_RULES_CARGO_STALE_CRATE_DAYS");
     cmd
 }
 
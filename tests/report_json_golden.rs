@@ -7,6 +7,7 @@ fn report_json_matches_golden() {
     let report = Report {
         schema_version: "1.0".to_string(),
         tool_version: "0.1.0".to_string(),
+        host: None,
         os: OsInfo {
             name: "macOS".to_string(),
             version: "26.x".to_string(),
@@ -43,6 +44,8 @@ fn report_json_matches_golden() {
             },
             notes: vec![],
         }],
+        classification: None,
+        volumes: vec![],
     };
 
     let actual = serde_json::to_value(&report).expect("serialize report");
@@ -10,9 +10,15 @@ fn macdiet_cmd(home: &Path) -> Command {
     cmd.env_remove("MACDIET_UI_MAX_TABLE_ROWS");
     cmd.env_remove("MACDIET_SCAN_DEFAULT_SCOPE");
     cmd.env_remove("MACDIET_SCAN_EXCLUDE");
+    cmd.env_remove("MACDIET_SCAN_SOURCE_ROOTS");
+    cmd.env_remove("MACDIET_SCAN_SIZER");
     cmd.env_remove("MACDIET_FIX_DEFAULT_RISK_MAX");
     cmd.env_remove("MACDIET_PRIVACY_MASK_HOME");
+    cmd.env_remove("MACDIET_PRIVACY_REDACTION");
     cmd.env_remove("MACDIET_REPORT_INCLUDE_EVIDENCE");
+    cmd.env_remove("MACDIET_REPORT_INCLUDE_HOST");
+    cmd.env_remove("MACDIET_HISTORY_ENABLED");
+    cmd.env_remove("MACDIET_RULES_CARGO_STALE_CRATE_DAYS");
     cmd
 }
 
@@ -227,3 +233,558 @@ exit 1
 
     let _ = std::fs::remove_dir_all(&home);
 }
+
+#[test]
+fn scan_classify_markdown_renders_category_and_age_tables() {
+    let home = make_temp_home();
+    write_file(home.join("work/movie.mov").as_path(), &[0u8; 2048]);
+    write_file(home.join("work/backup.zip").as_path(), &[0u8; 1024]);
+
+    let out = run(
+        &home,
+        &[
+            "scan",
+            "--deep",
+            "--classify",
+            "--scope",
+            "work",
+            "--markdown",
+        ],
+    );
+    assert!(
+        out.status.success(),
+        "stderr={}",
+        String::from_utf8_lossy(&out.stderr)
+    );
+    let stdout = String::from_utf8_lossy(&out.stdout);
+    assert!(stdout.contains("## 種類別"), "stdout={stdout}");
+    assert!(
+        stdout.contains("| メディア (`media`) | 2.0 KiB | 1 |"),
+        "stdout={stdout}"
+    );
+    assert!(
+        stdout.contains("| アーカイブ (`archive`) | 1.0 KiB | 1 |"),
+        "stdout={stdout}"
+    );
+    assert!(stdout.contains("## 経過日数別"), "stdout={stdout}");
+    assert!(stdout.contains("| <7d | 3.0 KiB |"), "stdout={stdout}");
+
+    let out = run(&home, &["scan", "--deep", "--scope", "work", "--json"]);
+    assert!(out.status.success());
+    let v: serde_json::Value = serde_json::from_slice(&out.stdout).expect("parse json");
+    assert!(v.get("classification").is_none());
+
+    let _ = std::fs::remove_dir_all(&home);
+}
+
+#[test]
+fn report_diff_matches_findings_by_id_in_all_formats() {
+    let home = make_temp_home();
+    write_file(home.join(".npm/a.bin").as_path(), &[0u8; 100]);
+    write_file(home.join(".gradle/caches/a.bin").as_path(), &[0u8; 100]);
+    let out = run(&home, &["--no-cache", "report", "--json"]);
+    assert!(out.status.success());
+    let old = home.join("old.json");
+    std::fs::write(&old, &out.stdout).expect("write old report");
+
+    std::fs::remove_dir_all(home.join(".gradle")).expect("remove gradle");
+    write_file(home.join(".npm/b.bin").as_path(), &[0u8; 5000]);
+    write_file(
+        home.join("Library/Caches/Homebrew/x.tar.gz").as_path(),
+        &[0u8; 300],
+    );
+    let out = run(&home, &["--no-cache", "report", "--json"]);
+    assert!(out.status.success());
+    let new = home.join("new.json");
+    std::fs::write(&new, &out.stdout).expect("write new report");
+
+    let old_s = old.to_str().expect("utf8 path");
+    let new_s = new.to_str().expect("utf8 path");
+    let out = run(&home, &["--json", "report", "diff", old_s, new_s]);
+    assert!(
+        out.status.success(),
+        "stderr={}",
+        String::from_utf8_lossy(&out.stderr)
+    );
+    let v: serde_json::Value = serde_json::from_slice(&out.stdout).expect("parse json");
+    let changes: Vec<(&str, &str, i64)> = v
+        .get("findings")
+        .and_then(|f| f.as_array())
+        .expect("findings array")
+        .iter()
+        .map(|f| {
+            (
+                f.get("id").and_then(|i| i.as_str()).unwrap_or(""),
+                f.get("change").and_then(|c| c.as_str()).unwrap_or(""),
+                f.pointer("/bytes/delta")
+                    .and_then(|d| d.as_i64())
+                    .unwrap_or(0),
+            )
+        })
+        .collect();
+    assert_eq!(
+        changes,
+        vec![
+            ("npm-cache", "grown", 5000),
+            ("homebrew-cache", "new", 300),
+            ("gradle-caches", "disappeared", -100),
+        ]
+    );
+    assert_eq!(
+        v.pointer("/estimated_total_bytes/delta")
+            .and_then(|d| d.as_i64()),
+        Some(5200)
+    );
+    assert!(v.pointer("/unobserved_bytes/old").is_some());
+
+    let out = run(&home, &["report", "diff", old_s, new_s, "--markdown"]);
+    assert!(out.status.success());
+    let stdout = String::from_utf8_lossy(&out.stdout);
+    assert!(
+        stdout.starts_with("# macdiet レポート差分"),
+        "stdout={stdout}"
+    );
+    assert!(stdout.contains("## 増加 (1)"), "stdout={stdout}");
+    assert!(stdout.contains("## 減少 (0)"), "stdout={stdout}");
+    assert!(
+        stdout.contains("| `gradle-caches` | 100 B | 0 B | -100 B |"),
+        "stdout={stdout}"
+    );
+
+    let out = run(&home, &["report", "diff", old_s, new_s]);
+    assert!(out.status.success());
+    let stdout = String::from_utf8_lossy(&out.stdout);
+    assert!(stdout.contains("消滅（1件）:"), "stdout={stdout}");
+
+    let mut future: serde_json::Value =
+        serde_json::from_slice(&std::fs::read(&old).expect("read old")).expect("parse old");
+    future["schema_version"] = serde_json::Value::from("2.0");
+    std::fs::write(&old, serde_json::to_vec(&future).expect("json")).expect("write");
+    let out = run(&home, &["report", "diff", old_s, new_s]);
+    assert_eq!(out.status.code(), Some(2));
+    let stderr = String::from_utf8_lossy(&out.stderr);
+    assert!(
+        stderr.contains("スキーマバージョンが一致しません")
+            && stderr.contains("schema_version=2.0"),
+        "stderr={stderr}"
+    );
+
+    let _ = std::fs::remove_dir_all(&home);
+}
+
+#[test]
+fn report_html_is_self_contained_and_respects_evidence_and_mask() {
+    let home = make_temp_home();
+    write_file(home.join(".npm/cache.bin").as_path(), b"hello");
+    let html_path = home.join("report.html");
+    let html_arg = html_path.to_string_lossy().to_string();
+
+    let out = run(&home, &["report", "--html", &html_arg]);
+    assert!(
+        out.status.success(),
+        "stderr={}",
+        String::from_utf8_lossy(&out.stderr)
+    );
+    assert!(out.stdout.is_empty(), "html goes to the file only");
+    let html = std::fs::read_to_string(&html_path).expect("read html");
+    assert!(html.starts_with("<!DOCTYPE html>"), "html={html}");
+    assert!(!html.contains("http://") && !html.contains("https://"));
+    assert!(!html.contains("<link") && !html.contains("<script src"));
+    assert!(html.contains("<table class=\"sortable\" id=\"findings\">"));
+    assert!(html.contains("<table class=\"sortable\" id=\"actions\">"));
+    assert!(html.contains("<div class=\"treemap\">"));
+    assert!(html.contains("<span class=\"risk risk-R1\">R1</span>"));
+    assert!(html.contains("npm cache をゴミ箱へ移動"));
+    assert!(html.contains("<code>~/.npm</code>"), "html={html}");
+    assert!(!html.contains(&home.to_string_lossy().to_string()));
+    assert!(!html.contains("根拠 ("), "evidence should be hidden by default");
+
+    let out = run(&home, &["report", "--html", &html_arg, "--include-evidence"]);
+    assert!(out.status.success());
+    let html = std::fs::read_to_string(&html_path).expect("read html");
+    assert!(html.contains("<details><summary>根拠 ("), "html={html}");
+    assert!(html.contains("統計: <code>files="), "html={html}");
+
+    let out = run(&home, &["report", "--html", &html_arg, "--markdown"]);
+    assert_eq!(out.status.code(), Some(2));
+
+    let _ = std::fs::remove_dir_all(&home);
+}
+
+#[test]
+fn report_csv_tsv_and_csv_dir_write_flat_tables() {
+    let home = make_temp_home();
+    write_file(home.join(".npm/cache.bin").as_path(), b"hello");
+
+    let out = run(&home, &["report", "--csv"]);
+    assert!(
+        out.status.success(),
+        "stderr={}",
+        String::from_utf8_lossy(&out.stderr)
+    );
+    let stdout = String::from_utf8_lossy(&out.stdout);
+    assert!(
+        stdout.starts_with(
+            "id,type,title,estimated_bytes,confidence,risk_level,recommended_actions\r\n"
+        ),
+        "stdout={stdout}"
+    );
+    // 標準出力には既定で所見の表だけを出し、2 つの表を続けない。
+    assert!(!stdout.contains("\r\n\r\n"), "stdout={stdout}");
+    assert!(
+        !stdout.contains("estimated_reclaimed_bytes"),
+        "stdout={stdout}"
+    );
+
+    let out = run(&home, &["report", "--csv", "--table", "actions"]);
+    assert!(out.status.success());
+    let stdout = String::from_utf8_lossy(&out.stdout);
+    assert!(
+        stdout.starts_with(
+            "id,title,risk_level,estimated_reclaimed_bytes,kind,command,paths,related_findings\r\n"
+        ),
+        "stdout={stdout}"
+    );
+    assert!(stdout.contains(",TRASH_MOVE,,~/.npm,"), "stdout={stdout}");
+    assert!(!stdout.contains("\r\n\r\n"), "stdout={stdout}");
+
+    let out = run(&home, &["report", "--tsv"]);
+    assert!(out.status.success());
+    let stdout = String::from_utf8_lossy(&out.stdout);
+    assert!(stdout.starts_with("id\ttype\ttitle\t"), "stdout={stdout}");
+    assert!(!stdout.contains('\r'), "stdout={stdout}");
+
+    let dir = home.join("tables");
+    let dir_arg = dir.to_string_lossy().to_string();
+    let out = run(&home, &["report", "--csv-dir", &dir_arg]);
+    assert!(out.status.success());
+    assert!(out.stdout.is_empty());
+    let findings = std::fs::read_to_string(dir.join("findings.csv")).expect("findings.csv");
+    let actions = std::fs::read_to_string(dir.join("actions.csv")).expect("actions.csv");
+    assert!(findings.starts_with("id,type,"), "findings={findings}");
+    assert!(actions.starts_with("id,title,"), "actions={actions}");
+    assert!(actions.contains("RUN_CMD,"), "actions={actions}");
+
+    let out = run(&home, &["report", "--csv", "--markdown"]);
+    assert_eq!(out.status.code(), Some(2));
+    let out = run(&home, &["report", "--csv", "--tsv"]);
+    assert_eq!(out.status.code(), Some(2));
+    for args in [
+        &["report", "--csv", "--table", "both"][..],
+        &["report", "--table", "actions"],
+        &["report", "--csv-dir", &dir_arg, "--table", "actions"],
+    ] {
+        let out = run(&home, args);
+        assert_eq!(out.status.code(), Some(2), "args={args:?}");
+    }
+
+    let _ = std::fs::remove_dir_all(&home);
+}
+
+#[test]
+fn report_openmetrics_writes_gauges_atomically_to_path() {
+    let home = make_temp_home();
+    write_file(home.join(".npm/cache.bin").as_path(), b"hello");
+    let prom = home.join("textfile/macdiet.prom");
+    std::fs::create_dir_all(prom.parent().expect("parent")).expect("mkdir");
+    let prom_arg = prom.to_string_lossy().to_string();
+
+    let out = run(&home, &["report", "--openmetrics", &prom_arg]);
+    assert!(
+        out.status.success(),
+        "stderr={}",
+        String::from_utf8_lossy(&out.stderr)
+    );
+    assert!(out.stdout.is_empty());
+    let metrics = std::fs::read_to_string(&prom).expect("read metrics");
+    assert!(
+        metrics.contains("# TYPE macdiet_estimated_total_bytes gauge\n"),
+        "metrics={metrics}"
+    );
+    assert!(metrics.contains("\nmacdiet_unobserved_bytes "), "metrics={metrics}");
+    assert!(
+        metrics.contains("macdiet_finding_bytes{id=\"npm-cache\",type=\"NODE_NPM_CACHE_LARGE\",risk=\"R1\"} "),
+        "metrics={metrics}"
+    );
+    assert!(
+        metrics.contains("macdiet_finding_confidence{id=\"npm-cache\","),
+        "metrics={metrics}"
+    );
+    assert!(metrics.ends_with("# EOF\n"), "metrics={metrics}");
+
+    let leftovers: Vec<_> = std::fs::read_dir(prom.parent().expect("parent"))
+        .expect("read dir")
+        .flatten()
+        .map(|e| e.file_name())
+        .collect();
+    assert_eq!(leftovers, vec![std::ffi::OsString::from("macdiet.prom")]);
+
+    let out = run(&home, &["report", "--openmetrics", &prom_arg, "--csv"]);
+    assert_eq!(out.status.code(), Some(2));
+
+    let _ = std::fs::remove_dir_all(&home);
+}
+
+#[test]
+fn schema_report_and_report_validate_accept_current_and_older_versions() {
+    let home = make_temp_home();
+    write_file(home.join(".npm/cache.bin").as_path(), b"hello");
+
+    let out = run(&home, &["schema", "report"]);
+    assert!(out.status.success());
+    let schema: serde_json::Value = serde_json::from_slice(&out.stdout).expect("schema json");
+    assert_eq!(schema["type"], "object");
+    assert!(schema["definitions"]["Finding"].is_object(), "{schema}");
+    let out = run(&home, &["schema", "log", "fix-apply"]);
+    assert!(out.status.success());
+    let out = run(&home, &["schema", "log", "nope"]);
+    assert_eq!(out.status.code(), Some(2));
+
+    let out = run(&home, &["--json", "report"]);
+    assert!(out.status.success());
+    let report_path = home.join("report.json");
+    std::fs::write(&report_path, &out.stdout).expect("write report");
+    let report_arg = report_path.to_string_lossy().to_string();
+    let out = run(&home, &["report", "validate", &report_arg]);
+    assert!(
+        out.status.success(),
+        "stdout={} stderr={}",
+        String::from_utf8_lossy(&out.stdout),
+        String::from_utf8_lossy(&out.stderr)
+    );
+    assert!(String::from_utf8_lossy(&out.stdout).starts_with("OK: "));
+
+    let mut report: serde_json::Value =
+        serde_json::from_slice(&std::fs::read(&report_path).expect("read")).expect("json");
+    report["schema_version"] = serde_json::Value::from("1.0");
+    std::fs::write(&report_path, serde_json::to_vec(&report).expect("json")).expect("write");
+    let out = run(&home, &["report", "validate", &report_arg]);
+    assert!(out.status.success());
+    let stdout = String::from_utf8_lossy(&out.stdout);
+    assert!(stdout.contains("schema_version=1.0"), "stdout={stdout}");
+    let out = run(&home, &["report", "diff", &report_arg, &report_arg]);
+    assert!(
+        out.status.success(),
+        "older reports should be migrated: stderr={}",
+        String::from_utf8_lossy(&out.stderr)
+    );
+
+    report["findings"][0]["risk_level"] = serde_json::Value::from("R9");
+    report["summary"]["estimated_total_bytes"] = serde_json::Value::from("big");
+    std::fs::write(&report_path, serde_json::to_vec(&report).expect("json")).expect("write");
+    let out = run(&home, &["--json", "report", "validate", &report_arg]);
+    assert_eq!(out.status.code(), Some(2));
+    let result: serde_json::Value = serde_json::from_slice(&out.stdout).expect("result json");
+    assert_eq!(result["valid"], false);
+    let errors: Vec<&str> = result["errors"]
+        .as_array()
+        .expect("errors")
+        .iter()
+        .filter_map(|e| e.as_str())
+        .collect();
+    assert!(
+        errors
+            .iter()
+            .any(|e| e.starts_with("/findings/0/risk_level:")),
+        "{errors:?}"
+    );
+    assert!(
+        errors
+            .iter()
+            .any(|e| e.starts_with("/summary/estimated_total_bytes:")),
+        "{errors:?}"
+    );
+
+    let _ = std::fs::remove_dir_all(&home);
+}
+
+#[test]
+fn fleet_summarize_merges_reports_by_type_and_host() {
+    let home = make_temp_home();
+    write_file(home.join(".npm/cache.bin").as_path(), b"hello");
+    write_file(&home.join(".gradle/caches/blob.bin"), &[0u8; 100]);
+
+    let out = run(&home, &["--json", "report"]);
+    assert!(out.status.success());
+    let base: serde_json::Value = serde_json::from_slice(&out.stdout).expect("report json");
+    assert!(base.get("host").is_none(), "host must be opt-in: {base}");
+
+    let host_of = |redaction: &str| -> String {
+        let out = macdiet_cmd(&home)
+            .env("MACDIET_PRIVACY_REDACTION", redaction)
+            .args(["--json", "report", "--host"])
+            .output()
+            .expect("run macdiet");
+        assert!(out.status.success());
+        let v: serde_json::Value = serde_json::from_slice(&out.stdout).expect("report json");
+        v["host"].as_str().expect("host").to_string()
+    };
+    let raw = host_of("none");
+    assert!(!raw.starts_with('#'), "host={raw}");
+    assert_eq!(host_of("home"), raw);
+    let masked = host_of("strict");
+    assert!(
+        masked.starts_with('#') && masked.len() == 9,
+        "host={masked}"
+    );
+    assert_eq!(host_of("strict"), masked);
+
+    let dir = home.join("fleet");
+    std::fs::create_dir_all(&dir).expect("mkdir");
+    let mut paths = Vec::new();
+    for (name, host, gradle_bytes) in [
+        ("a.json", Some("mac-a"), 100u64),
+        ("b.json", Some("mac-b"), 300),
+        ("c.json", None, 0),
+    ] {
+        let mut report = base.clone();
+        match host {
+            Some(h) => report["host"] = serde_json::Value::from(h),
+            None => {
+                report.as_object_mut().expect("object").remove("host");
+            }
+        }
+        let findings = report["findings"].as_array_mut().expect("findings");
+        if gradle_bytes == 0 {
+            findings.retain(|f| f["type"] != "GRADLE_CACHES_LARGE");
+        } else {
+            for f in findings.iter_mut().filter(|f| f["type"] == "GRADLE_CACHES_LARGE") {
+                f["estimated_bytes"] = serde_json::Value::from(gradle_bytes);
+            }
+        }
+        let path = dir.join(name);
+        std::fs::write(&path, serde_json::to_vec(&report).expect("json")).expect("write");
+        paths.push(path.to_string_lossy().to_string());
+    }
+    let mut args = vec!["--json", "fleet", "summarize"];
+    args.extend(paths.iter().map(|p| p.as_str()));
+
+    let out = run(&home, &args);
+    assert!(
+        out.status.success(),
+        "stderr={}",
+        String::from_utf8_lossy(&out.stderr)
+    );
+    let summary: serde_json::Value = serde_json::from_slice(&out.stdout).expect("summary json");
+    let hosts: Vec<&str> = summary["machines"]
+        .as_array()
+        .expect("machines")
+        .iter()
+        .filter_map(|m| m["host"].as_str())
+        .collect();
+    assert_eq!(hosts, vec!["c.json", "mac-a", "mac-b"]);
+    let gradle = summary["types"]
+        .as_array()
+        .expect("types")
+        .iter()
+        .find(|t| t["type"] == "GRADLE_CACHES_LARGE")
+        .expect("gradle type");
+    assert_eq!(gradle["machines"], 2);
+    assert_eq!(gradle["total_bytes"], 400);
+    assert_eq!(gradle["median_bytes"], 200);
+    assert_eq!(gradle["worst"][0]["host"], "mac-b");
+    let npm = summary["types"]
+        .as_array()
+        .expect("types")
+        .iter()
+        .find(|t| t["type"] == "NODE_NPM_CACHE_LARGE")
+        .expect("npm type");
+    assert_eq!(npm["machines"], 3);
+
+    args[0] = "fleet";
+    args[1] = "summarize";
+    args[2] = "--markdown";
+    let out = run(&home, &args);
+    assert!(out.status.success());
+    let stdout = String::from_utf8_lossy(&out.stdout);
+    assert!(
+        stdout.starts_with("# macdiet フリート集計"),
+        "stdout={stdout}"
+    );
+    assert!(stdout.contains("- マシン数: 3"), "stdout={stdout}");
+    assert!(
+        stdout.contains(
+            "| Gradle caches（ビルドキャッシュ） | `GRADLE_CACHES_LARGE` | 2 / 3 | 400 B | 200 B | mac-b (300 B), mac-a (100 B) |"
+        ),
+        "stdout={stdout}"
+    );
+
+    let _ = std::fs::remove_dir_all(&home);
+}
+
+#[test]
+fn report_strict_redaction_hashes_project_names_stably() {
+    let home = make_temp_home();
+    write_file(home.join(".npm/cache.bin").as_path(), b"hello");
+    write_file(
+        home.join("Downloads/acme-client/AcmeTool.dmg").as_path(),
+        &[0u8; 1024],
+    );
+
+    let out = run(&home, &["report", "--json", "--include-evidence"]);
+    assert!(out.status.success());
+    let plain = String::from_utf8_lossy(&out.stdout).to_string();
+    assert!(plain.contains("acme-client/AcmeTool.dmg"), "{plain}");
+
+    let strict = |args: &[&str]| {
+        let out = macdiet_cmd(&home)
+            .env("MACDIET_PRIVACY_REDACTION", "strict")
+            .args(args)
+            .output()
+            .expect("run macdiet");
+        assert!(
+            out.status.success(),
+            "stderr={}",
+            String::from_utf8_lossy(&out.stderr)
+        );
+        String::from_utf8_lossy(&out.stdout).to_string()
+    };
+    let json = strict(&["report", "--json", "--include-evidence"]);
+    assert!(
+        !json.contains("acme-client") && !json.contains("AcmeTool"),
+        "{json}"
+    );
+    assert!(json.contains("~/Downloads/#"), "{json}");
+    assert!(json.contains("~/.npm"), "{json}");
+    assert!(home.join(".config/macdiet/redaction-salt").exists());
+    let installer_id = |json: &str| {
+        let v: serde_json::Value = serde_json::from_str(json).expect("parse json");
+        v["findings"]
+            .as_array()
+            .expect("findings")
+            .iter()
+            .filter_map(|f| f["id"].as_str())
+            .find(|id| id.starts_with("installer-leftover:"))
+            .map(str::to_string)
+            .expect("installer finding")
+    };
+    assert_eq!(
+        installer_id(&strict(&["report", "--json", "--include-evidence"])),
+        installer_id(&json)
+    );
+
+    let markdown = strict(&["report", "--markdown", "--include-evidence"]);
+    assert!(!markdown.contains("acme-client") && !markdown.contains("AcmeTool"));
+    assert!(markdown.contains("~/Downloads/#"), "{markdown}");
+
+    let out = macdiet_cmd(&home)
+        .env("MACDIET_PRIVACY_REDACTION", "strict")
+        .env("MACDIET_HISTORY_ENABLED", "1")
+        .args(["doctor", "--json"])
+        .output()
+        .expect("run macdiet");
+    assert!(out.status.success());
+    let saved: Vec<String> = std::fs::read_dir(home.join(".config/macdiet/history"))
+        .expect("history dir")
+        .map(|e| std::fs::read_to_string(e.expect("entry").path()).expect("read history"))
+        .collect();
+    assert_eq!(saved.len(), 1);
+    assert!(saved[0].contains("~/Downloads/#"), "{}", saved[0]);
+    assert!(
+        !saved[0].contains("acme-client") && !saved[0].contains("AcmeTool"),
+        "{}",
+        saved[0]
+    );
+
+    let _ = std::fs::remove_dir_all(&home);
+}